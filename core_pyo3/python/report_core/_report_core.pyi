@@ -144,7 +144,8 @@ class Service(ABC):
         Arguments:
         ---
             name: str
-                Имя листа
+                Имя листа: от 1 до 31 символа, без символов []:*?/\\
+                и апострофа в начале или конце, уникальное без учета регистра
             sheet_state: str
                 Состояние и видимость листа
         Returns:
@@ -221,6 +222,543 @@ class Service(ABC):
 
         """
     @final
+    def _register_style(self, name: str, style: dict[str, Any]) -> None:
+        """
+        Регистрация именованного стиля книги. Стиль с тем же именем заменяется
+        ---
+
+        Стиль описывается словарем с ключами font, fill, border,
+        alignment, wrap, number_format и protection, например:
+        {"font": {"bold": True, "color": "#1F4E78"}, "fill": {"color": "DDEBF7"},
+        "border": {"bottom": {"style": "thin"}}, "alignment": {"horizontal": "center"},
+        "wrap": True, "number_format": "# ##0.00",
+        "protection": {"locked": False, "hidden": False}}
+
+        Arguments:
+        ---
+            name: str
+                Имя стиля
+            style: dict[str, Any]
+                Описание стиля
+        """
+
+    @final
+    def _rename_sheet(self, old_name: str, new_name: str) -> None:
+        """
+        Переименование листа с обновлением ссылок в формулах, именах
+        и внутренних гиперссылках, в том числе ссылках оглавления
+        ---
+
+        Arguments:
+        ---
+            old_name: str
+                Текущее имя листа
+            new_name: str
+                Новое имя листа, с теми же ограничениями, что и в add_sheet
+        """
+
+    @final
+    def _protect(
+        self,
+        password: str | None = None,
+        lock_structure: bool = True,
+        lock_windows: bool = False,
+    ) -> None:
+        """
+        Защита структуры книги. Прежняя защита заменяется
+        ---
+
+        Arguments:
+        ---
+            password: str | None
+                Пароль снятия защиты, None - без пароля
+            lock_structure: bool
+                Запрет добавления, удаления, переименования, перемещения
+                и скрытия листов
+            lock_windows: bool
+                Запрет изменения размеров и положения окон книги
+        """
+
+    @final
+    def _unprotect(self) -> None:
+        """Снятие защиты структуры книги"""
+
+    @final
+    def _build_toc(self, options: dict[str, Any] | None = None) -> Sheet:
+        """
+        Построение листа оглавления со ссылками на видимые листы книги
+        ---
+
+        Лист оглавления ставится первым, прежнее оглавление с тем же именем заменяется.
+        Лист с данными с тем же именем не заменяется, а вызывает ошибку.
+        На каждый видимый лист добавляется ссылка на оглавление.
+
+        Arguments:
+        ---
+            options: dict[str, Any] | None
+                Параметры оглавления:
+                sheet_name - имя листа, по умолчанию Оглавление;
+                title - заголовок, None - без заголовка;
+                descriptions - описания листов по имени листа;
+                back_link - текст ссылки на оглавление, None - без ссылки;
+                back_link_cell - ячейка ссылки, например {"row": 1, "column": 1}
+
+        Returns:
+        ---
+            Sheet
+                Лист оглавления
+        """
+
+    def _define_name(
+        self,
+        name: str,
+        reference: str,
+        scope: str | None = None,
+    ) -> None:
+        """
+        Добавление имени (именованного диапазона). Имя с той же областью заменяется
+        ---
+
+        Имя можно использовать в формулах, ссылки в нем обновляются
+        при вставке и удалении строк/колонок и переименовании листов.
+
+        Arguments:
+        ---
+            name: str
+                Имя, например ИтогоДоходы
+            reference: str
+                Ссылка или формула, например 'Отчет'!$B$2:$B$10
+            scope: str | None
+                Лист, в пределах которого действует имя. None - вся книга
+        """
+
+    @final
+    def _insert_rows(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Вставка строк листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер строки, перед которой вставляются строки
+            amount: int
+                Количество строк
+        """
+
+    @final
+    def _insert_cols(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Вставка колонок листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер колонки, перед которой вставляются колонки
+            amount: int
+                Количество колонок
+        """
+
+    @final
+    def _delete_rows(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Удаление строк листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер строки
+            amount: int
+                Количество строк
+        """
+
+    @final
+    def _delete_cols(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Удаление колонок листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер колонки
+            amount: int
+                Количество колонок
+        """
+
+    @final
+    def calculate(self) -> None:
+        """
+        Пересчет всех формул книги
+        ---
+        Результаты вычислений сохраняются в значениях ячеек с формулами.
+        """
+
+    @final
+    def to_json(self) -> str:
+        """
+        Преобразование книги в json
+        ---
+
+        Returns:
+        ---
+            str
+        """
+
+    @final
+    def save_xlsx(self, path: str) -> None:
+        """
+        Сохранение книги в файл XLSX
+        ---
+
+        Arguments:
+        ---
+            path: str
+                Путь к файлу
+        """
+
+    @final
+    def to_dict(self) -> dict[str, Any]:
+        """
+        Преобразование книги в dict
+        ---
+
+        Returns:
+        ---
+            dict[str, Any]
+        """
+
+class Book:
+    sheets: Sequence[Sheet]
+    defined_names: Sequence[tuple[str, str, str | None]]
+    protection: dict[str, Any] | None
+    """
+    Защита структуры книги, например:
+    {"password_hash": "CBEB", "lock_structure": True, "lock_windows": False}
+    """
+
+    @final
+    def __init__(self) -> None:
+        """
+        Инициализация Книги
+        ----
+
+        Example:
+        ---
+        .. code-block:: python3
+
+            book = Book()
+        """
+        ...
+
+    @final
+    def add_sheet(
+        self,
+        name: str,
+        sheet_state: str = "visible",
+    ) -> Sheet:
+        """
+        Добавление листа в книгу
+        ---
+
+        Arguments:
+        ---
+            name: str
+                Имя листа: от 1 до 31 символа, без символов []:*?/\\
+                и апострофа в начале или конце, уникальное без учета регистра
+            sheet_state: str
+                Состояние и видимость листа
+        Returns:
+        ---
+            Sheet
+        """
+
+    @final
+    def copy_sheet(
+        self,
+        sheet: Sheet,
+    ) -> Sheet:
+        """
+        Корирование листа в книгу
+        ---
+
+        Arguments:
+        ---
+            sheet: Sheet
+                Лист
+        Returns:
+        ---
+            Sheet
+        """
+
+    @final
+    def get_sheet_index(
+        self,
+        idx: int,
+    ) -> Sheet | None:
+        """
+        Получение листа по индексу
+        ---
+
+        Arguments:
+        ---
+            idx: int
+                Индекс листа
+
+        Returns:
+        ---
+            Sheet | None
+
+        """
+    @final
+    def get_sheet_name(
+        self,
+        name: str,
+    ) -> Sheet | None:
+        """
+        Получение листа по названию без учета регистра
+        ---
+
+        Arguments:
+        ---
+            name: str
+                Имя листа
+
+        Returns:
+        ---
+            Sheet | None
+        """
+
+    @final
+    def register_style(self, name: str, style: dict[str, Any]) -> None:
+        """
+        Регистрация именованного стиля книги. Стиль с тем же именем заменяется
+        ---
+
+        Стиль описывается словарем с ключами font, fill, border,
+        alignment, wrap, number_format и protection, например:
+        {"font": {"bold": True, "color": "#1F4E78"}, "fill": {"color": "DDEBF7"},
+        "border": {"bottom": {"style": "thin"}}, "alignment": {"horizontal": "center"},
+        "wrap": True, "number_format": "# ##0.00",
+        "protection": {"locked": False, "hidden": False}}
+
+        Arguments:
+        ---
+            name: str
+                Имя стиля
+            style: dict[str, Any]
+                Описание стиля
+        """
+
+    @final
+    def rename_sheet(self, old_name: str, new_name: str) -> None:
+        """
+        Переименование листа с обновлением ссылок в формулах, именах
+        и внутренних гиперссылках, в том числе ссылках оглавления
+        ---
+
+        Arguments:
+        ---
+            old_name: str
+                Текущее имя листа
+            new_name: str
+                Новое имя листа, с теми же ограничениями, что и в add_sheet
+        """
+
+    @final
+    def build_toc(self, options: dict[str, Any] | None = None) -> Sheet:
+        """
+        Построение листа оглавления со ссылками на видимые листы книги
+        ---
+
+        Лист оглавления ставится первым, прежнее оглавление с тем же именем заменяется.
+        Лист с данными с тем же именем не заменяется, а вызывает ошибку.
+        На каждый видимый лист добавляется ссылка на оглавление.
+
+        Arguments:
+        ---
+            options: dict[str, Any] | None
+                Параметры оглавления:
+                sheet_name - имя листа, по умолчанию Оглавление;
+                title - заголовок, None - без заголовка;
+                descriptions - описания листов по имени листа;
+                back_link - текст ссылки на оглавление, None - без ссылки;
+                back_link_cell - ячейка ссылки, например {"row": 1, "column": 1}
+
+        Returns:
+        ---
+            Sheet
+                Лист оглавления
+        """
+
+    @final
+    def protect(
+        self,
+        password: str | None = None,
+        lock_structure: bool = True,
+        lock_windows: bool = False,
+    ) -> None:
+        """
+        Защита структуры книги. Прежняя защита заменяется
+        ---
+
+        Arguments:
+        ---
+            password: str | None
+                Пароль снятия защиты, None - без пароля
+            lock_structure: bool
+                Запрет добавления, удаления, переименования, перемещения
+                и скрытия листов
+            lock_windows: bool
+                Запрет изменения размеров и положения окон книги
+        """
+
+    @final
+    def unprotect(self) -> None:
+        """Снятие защиты структуры книги"""
+
+    def define_name(
+        self,
+        name: str,
+        reference: str,
+        scope: str | None = None,
+    ) -> None:
+        """
+        Добавление имени (именованного диапазона). Имя с той же областью заменяется
+        ---
+
+        Имя можно использовать в формулах, ссылки в нем обновляются
+        при вставке и удалении строк/колонок и переименовании листов.
+
+        Arguments:
+        ---
+            name: str
+                Имя, например ИтогоДоходы
+            reference: str
+                Ссылка или формула, например 'Отчет'!$B$2:$B$10
+            scope: str | None
+                Лист, в пределах которого действует имя. None - вся книга
+        """
+
+    @final
+    def get_style(self, name: str) -> dict[str, Any] | None:
+        """
+        Получение описания зарегистрированного стиля
+        ---
+
+        Arguments:
+        ---
+            name: str
+                Имя стиля
+
+        Returns:
+        ---
+            dict[str, Any] | None
+        """
+
+    @final
+    def get_display_value(self, sheet_name: str, row: int, col: int) -> str | None:
+        """
+        Получение отображаемого значения ячейки с учетом зарегистрированных стилей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            row: int
+                Номер строки
+            col: int
+                Номер колонки
+
+        Returns:
+        ---
+            str | None
+        """
+
+    @final
+    def insert_rows(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Вставка строк листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер строки, перед которой вставляются строки
+            amount: int
+                Количество строк
+        """
+
+    @final
+    def insert_cols(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Вставка колонок листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер колонки, перед которой вставляются колонки
+            amount: int
+                Количество колонок
+        """
+
+    @final
+    def delete_rows(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Удаление строк листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер строки
+            amount: int
+                Количество строк
+        """
+
+    @final
+    def delete_cols(self, sheet_name: str, idx: int, amount: int) -> None:
+        """
+        Удаление колонок листа с пересчетом ссылок в формулах всей книги
+        ---
+
+        Arguments:
+        ---
+            sheet_name: str
+                Имя листа
+            idx: int
+                Номер колонки
+            amount: int
+                Количество колонок
+        """
+
+    @final
+    def calculate(self) -> None:
+        """
+        Пересчет всех формул книги
+        ---
+        Результаты вычислений сохраняются в значениях ячеек с формулами.
+        """
+
+    @final
     def to_json(self) -> str:
         """
         Преобразование книги в json
@@ -231,6 +769,34 @@ class Service(ABC):
             str
         """
 
+    @final
+    @staticmethod
+    def from_json(value: str) -> Book:
+        """
+        Восстановление книги из json, полученного методом to_json
+        ---
+
+        Arguments:
+        ---
+            value: str
+                Книга в формате json
+        Returns:
+        ---
+            Book
+        """
+
+    @final
+    def save_xlsx(self, path: str) -> None:
+        """
+        Сохранение книги в файл XLSX
+        ---
+
+        Arguments:
+        ---
+            path: str
+                Путь к файлу
+        """
+
     @final
     def to_dict(self) -> dict[str, Any]:
         """
@@ -239,176 +805,334 @@ class Service(ABC):
 
         Returns:
         ---
-            dict[str, Any]
+            dict[str, Any]
+        """
+
+class Sheet:
+    """Тип данных листа с которыми работает парсер."""
+
+    name: str
+    merge_cells: Sequence[tuple[int, int, int, int]]
+    cells: Sequence[Cell]
+    sheet_state: str
+    comments: Sequence[Comment]
+    sheet_view: dict[str, Any]
+    """
+    Настройки отображения листа. При установке незаданные ключи
+    принимают значения по умолчанию, например:
+    {"frozen_rows": 1, "frozen_cols": 0, "split": {"x": 2000, "y": 0},
+    "zoom": 85, "show_grid_lines": False, "selected_cell": {"row": 2, "column": 1},
+    "tab_color": "#1F4E78", "right_to_left": False}
+    """
+    auto_filter: tuple[int, int, int, int] | None
+    """Диапазон автофильтра (start_row, end_row, start_col, end_col)"""
+    page_setup: dict[str, Any]
+    """
+    Параметры страницы и печати. При установке незаданные ключи
+    принимают значения по умолчанию, например:
+    {"print_area": {"start_row": 1, "end_row": 50, "start_col": 1, "end_col": 8},
+    "orientation": "landscape", "paper_size": 9,
+    "margins": {"left": 0.5, "right": 0.5, "top": 0.75, "bottom": 0.75},
+    "fit_to_page": {"width": 1, "height": 0}, "print_title_rows": [1, 2],
+    "header": "&CОтчет", "footer": "&RСтр. &P из &N"}
+    """
+    summary_below: bool
+    """Итоговая строка группы под строками группы (True) или над ними"""
+    summary_right: bool
+    """Итоговая колонка группы справа от колонок группы (True) или слева"""
+    protection: dict[str, Any] | None
+    """Защита листа в формате параметров метода protect, None - лист не защищен"""
+
+    @final
+    def set_sheet_state(
+        self,
+        state: Literal[
+            "visible",
+            "hidden",
+        ],
+    ) -> None:
+        """
+        Метод установки видисмости листа
+        ---
+        Arguments:
+        ---
+            state: str
+                Тип видимости
+        Returns:
+        ---
+            None
+        """
+
+    @final
+    def add_merge_cells(
+        self,
+        start_row: int | str,
+        end_row: int | None = None,
+        start_col: int | None = None,
+        end_col: int | None = None,
+        clear: bool = False,
+    ) -> None:
+        """
+        Функция для добавления объедененных ячеек на лист.
+        ---
+
+        Диапазон задается строкой в стиле A1, например add_merge_cells("A1:C1"),
+        или номерами строк и колонок. Перевернутый диапазон, диапазон из
+        одной ячейки или пересекающийся с существующим объединением
+        вызывает ошибку.
+
+        Arguments:
+        ---
+            start_row: int | str
+                Начальная строка или диапазон в стиле A1
+            end_row: int | None
+                Конечная строка
+            start_col: int | None
+                Начальная колонка
+            end_col: int | None
+                Конечная колонка
+            clear: bool
+                Очистить значения всех ячеек, кроме левой верхней, как в Excel
+        """
+
+    @final
+    def remove_merge_cells(
+        self,
+        start_row: int | str,
+        end_row: int | None = None,
+        start_col: int | None = None,
+        end_col: int | None = None,
+    ) -> bool:
+        """
+        Функция для удаления объединения с точно совпадающим диапазоном.
+        ---
+
+        Arguments:
+        ---
+            start_row: int | str
+                Начальная строка или диапазон в стиле A1
+            end_row: int | None
+                Конечная строка
+            start_col: int | None
+                Начальная колонка
+            end_col: int | None
+                Конечная колонка
+
+        Returns:
+        ---
+            bool
+                True, если объединение было удалено
         """
 
-class Book:
-    sheets: Sequence[Sheet]
-
     @final
-    def __init__(self) -> None:
+    def unmerge(self, row: int, col: int) -> tuple[int, int, int, int] | None:
         """
-        Инициализация Книги
-        ----
+        Функция для снятия объединения, в которое входит ячейка.
+        ---
 
-        Example:
+        Arguments:
         ---
-        .. code-block:: python3
+            row: int
+                Строка ячейки
+            col: int
+                Колонка ячейки
 
-            book = Book()
+        Returns:
+        ---
+            tuple[int, int, int, int] | None
+                Снятый диапазон (start_row, end_row, start_col, end_col) или None
         """
-        ...
 
     @final
-    def add_sheet(
+    def add_conditional_format(
         self,
-        name: str,
-        sheet_state: str = "visible",
-    ) -> Sheet:
+        ranges: Sequence[tuple[int, int, int, int]],
+        rule: dict[str, Any],
+        style: str | dict[str, Any] | None = None,
+        stop_if_true: bool = False,
+        priority: int | None = None,
+    ) -> None:
         """
-        Добавление листа в книгу
+        Функция для добавления условного форматирования на лист.
         ---
 
+        Типы правил: cell_is, expression, color_scale, data_bar,
+        duplicate_values и top, например:
+        {"type": "cell_is", "operator": "less_than", "formula": "0"}
+        {"type": "expression", "formula": "=$C2>$D2"}
+        {"type": "color_scale", "min": {"type": "min", "color": "F8696B"},
+        "max": {"type": "max", "color": "63BE7B"}}
+        {"type": "data_bar", "color": "638EC6"}
+        {"type": "duplicate_values"}
+        {"type": "top", "rank": 10, "bottom": False, "percent": False}
+
         Arguments:
         ---
-            name: str
-                Имя листа
-            sheet_state: str
-                Состояние и видимость листа
-        Returns:
-        ---
-            Sheet
+            ranges: Sequence[tuple[int, int, int, int]]
+                Диапазоны (start_row, end_row, start_col, end_col)
+            rule: dict[str, Any]
+                Описание правила
+            style: str | dict[str, Any] | None
+                Имя зарегистрированного стиля или словарь со стилем,
+                применяемым при выполнении условия
+            stop_if_true: bool
+                Не проверять следующие правила, если условие выполнено
+            priority: int | None
+                Приоритет правила на листе, начиная с 1, без повторов.
+                Правила без приоритета проверяются после остальных
         """
 
     @final
-    def copy_sheet(
+    def add_data_validation(
         self,
-        sheet: Sheet,
-    ) -> Sheet:
+        ranges: Sequence[tuple[int, int, int, int]],
+        rule: dict[str, Any],
+        allow_blank: bool = True,
+        input_title: str | None = None,
+        input_message: str | None = None,
+        error_title: str | None = None,
+        error_message: str | None = None,
+        error_style: Literal["stop", "warning", "information"] = "stop",
+    ) -> None:
         """
-        Корирование листа в книгу
+        Функция для добавления проверки данных на лист.
         ---
 
+        Типы правил: list, list_range, whole, decimal, date, text_length
+        и custom, например:
+        {"type": "list", "values": ["Да", "Нет"]}
+        {"type": "list_range", "range": "Справочник!$A$1:$A$10"}
+        {"type": "whole", "operator": "between", "formula": "1", "formula2": "10"}
+        {"type": "date", "operator": "greater_than", "formula": "DATE(2024,1,1)"}
+        {"type": "text_length", "operator": "less_than_or_equal", "formula": "50"}
+        {"type": "custom", "formula": "=ISNUMBER(A1)"}
+
         Arguments:
         ---
-            sheet: Sheet
-                Лист
-        Returns:
-        ---
-            Sheet
+            ranges: Sequence[tuple[int, int, int, int]]
+                Диапазоны (start_row, end_row, start_col, end_col)
+            rule: dict[str, Any]
+                Описание правила
+            allow_blank: bool
+                Разрешены ли пустые значения
+            input_title: str | None
+                Заголовок подсказки при выборе ячейки
+            input_message: str | None
+                Текст подсказки при выборе ячейки
+            error_title: str | None
+                Заголовок сообщения об ошибке
+            error_message: str | None
+                Текст сообщения об ошибке
+            error_style: Literal["stop", "warning", "information"]
+                Реакция на недопустимое значение
         """
 
     @final
-    def get_sheet_index(
-        self,
-        idx: int,
-    ) -> Sheet | None:
+    def freeze_panes(self, rows: int, cols: int = 0) -> None:
         """
-        Получение листа по индексу
+        Закрепление строк сверху и колонок слева. 0 снимает закрепление
         ---
 
         Arguments:
         ---
-            idx: int
-                Индекс листа
+            rows: int
+                Количество закрепленных строк
+            cols: int
+                Количество закрепленных колонок
+        """
 
-        Returns:
+    @final
+    def group_rows(self, start: int, end: int, level: int = 1) -> None:
+        """
+        Группировка строк для сворачивания
         ---
-            Sheet | None
 
+        В группе не больше 65 536 строк: свойства хранятся для каждой строки.
+
+        Arguments:
+        ---
+            start: int
+                Номер первой строки группы
+            end: int
+                Номер последней строки группы
+            level: int
+                Уровень группировки 1-7, 0 - разгруппировать
         """
+
     @final
-    def get_sheet_name(
-        self,
-        name: str,
-    ) -> Sheet | None:
+    def collapse_rows(self, start: int, end: int) -> None:
         """
-        Получение листа по названию
+        Сворачивание группы строк: строк скрываются, итоговая отмечается свернутой
         ---
 
-        Arguments:
-        ---
-            name: str
-                Имя листа
+        Диапазон ограничен так же, как в group_rows.
 
-        Returns:
+        Arguments:
         ---
-            Sheet | None
+            start: int
+                Номер первой строки группы
+            end: int
+                Номер последней строки группы
         """
 
     @final
-    def to_json(self) -> str:
+    def expand_rows(self, start: int, end: int) -> None:
         """
-        Преобразование книги в json
+        Разворачивание группы строк вместе с вложенными группами
         ---
 
-        Returns:
+        Диапазон ограничен так же, как в group_rows.
+
+        Arguments:
         ---
-            str
+            start: int
+                Номер первой строки группы
+            end: int
+                Номер последней строки группы
         """
 
     @final
-    def to_dict(self) -> dict[str, Any]:
+    def group_cols(self, start: int, end: int, level: int = 1) -> None:
         """
-        Преобразование книги в dict
+        Группировка колонок для сворачивания
         ---
 
-        Returns:
+        Arguments:
         ---
-            dict[str, Any]
+            start: int
+                Номер первой колонки группы
+            end: int
+                Номер последней колонки группы
+            level: int
+                Уровень группировки 1-7, 0 - разгруппировать
         """
 
-class Sheet:
-    """Тип данных листа с которыми работает парсер."""
-
-    name: str
-    merge_cells: Sequence[tuple[int, int, int, int]]
-    cells: Sequence[Cell]
-    sheet_state: str
-    comments: Sequence[Comment]
-
     @final
-    def set_sheet_state(
-        self,
-        state: Literal[
-            "visible",
-            "hidden",
-        ],
-    ) -> None:
+    def collapse_cols(self, start: int, end: int) -> None:
         """
-        Метод установки видисмости листа
+        Сворачивание группы колонок: колонок скрываются, итоговая отмечается свернутой
         ---
+
         Arguments:
         ---
-            state: str
-                Тип видимости
-        Returns:
-        ---
-            None
+            start: int
+                Номер первой колонки группы
+            end: int
+                Номер последней колонки группы
         """
 
     @final
-    def add_merge_cells(
-        self,
-        start_row: int,
-        end_row: int,
-        start_col: int,
-        end_col: int,
-    ) -> None:
+    def expand_cols(self, start: int, end: int) -> None:
         """
-        Функция для добавления объедененных ячеек на лист.
+        Разворачивание группы колонок вместе с вложенными группами
         ---
 
         Arguments:
         ---
-            start_row: int
-                Начальная строка
-            end_row: int
-                Конечная строка
-            start_col: int
-                Начальная колонка
-            end_col: int
-                Конечная колонка
+            start: int
+                Номер первой колонки группы
+            end: int
+                Номер последней колонки группы
         """
 
     @final
@@ -477,7 +1201,7 @@ class Sheet:
         """
 
     @final
-    def get_value_cell(self, row: int, col: int) -> str:
+    def get_value_cell(self, row: int, col: int, merged: bool = False) -> str:
         """
         Функция для получения значения ячейки.
         ---
@@ -488,12 +1212,32 @@ class Sheet:
                 Строка ячейки
             col: int
                 Колонка ячейки
+            merged: bool
+                Для объединенной ячейки вернуть значение левой верхней ячейки объединения
 
         Returns:
         ---
             str
         """
     @final
+    def merged_range_at(self, row: int, col: int) -> tuple[int, int, int, int] | None:
+        """
+        Функция для получения объединения, в которое входит ячейка.
+        ---
+
+        Arguments:
+        ---
+            row: int
+                Строка ячейки
+            col: int
+                Колонка ячейки
+
+        Returns:
+        ---
+            tuple[int, int, int, int] | None
+                Диапазон (start_row, end_row, start_col, end_col) или None
+        """
+    @final
     def set_height_row(self, row_num: int, val: float) -> None:
         """
         Метод установки высоты ячеек
@@ -566,32 +1310,71 @@ class Sheet:
         """
 
     @final
-    def delete_cols(self, idx: int, cols: int) -> None:
+    def insert_cols(self, idx: int, amount: int) -> None:
         """
-        Метод удаления колонок
+        Метод вставки колонок. Ячейки, объединения, комментарии
+        и ссылки в формулах сдвигаются вправо.
+        Ссылки на этот лист из формул других листов и имена книги
+        не пересчитываются, для этого используйте Book.insert_cols
         ---
 
         Arguments:
         ---
             idx: int
-                Номер колонки
-            cols: int
+                Номер колонки, перед которой вставляются колонки
+            amount: int
                 Количество колонок
         """
 
     @final
-    def delete_rows(self, idx: int, rows: int) -> None:
+    def insert_rows(self, idx: int, amount: int) -> None:
         """
-        Метод удаления строк
+        Метод вставки строк. Ячейки, объединения, комментарии
+        и ссылки в формулах сдвигаются вниз.
+        Ссылки на этот лист из формул других листов и имена книги
+        не пересчитываются, для этого используйте Book.insert_rows
         ---
 
         Arguments:
         ---
             idx: int
-                Номер строки
-            rows: int
+                Номер строки, перед которой вставляются строки
+            amount: int
                 Количество строк
+        """
+
+    @final
+    def delete_cols(self, idx: int, amount: int) -> None:
+        """
+        Метод удаления колонок. Ячейки, объединения, комментарии
+        и ссылки в формулах листа сдвигаются влево.
+        Ссылки на этот лист из формул других листов и имена книги
+        не пересчитываются, для этого используйте Book.delete_cols
+        ---
+
+        Arguments:
+        ---
+            idx: int
+                Номер первой удаляемой колонки
+            amount: int
+                Количество колонок
+        """
+
+    @final
+    def delete_rows(self, idx: int, amount: int) -> None:
+        """
+        Метод удаления строк. Ячейки, объединения, комментарии
+        и ссылки в формулах листа сдвигаются вверх.
+        Ссылки на этот лист из формул других листов и имена книги
+        не пересчитываются, для этого используйте Book.delete_rows
+        ---
 
+        Arguments:
+        ---
+            idx: int
+                Номер первой удаляемой строки
+            amount: int
+                Количество строк
         """
 
     @final
@@ -688,15 +1471,94 @@ class Sheet:
         """
 
     @final
-    def find_cell_by_regex(self, regex: str) -> Cell | None:
+    def protect(
+        self,
+        password: str | None = None,
+        options: dict[str, Any] | None = None,
+    ) -> None:
+        """
+        Защита листа. Прежняя защита заменяется
+        ---
+
+        Изменять можно только ячейки, в стиле которых снята блокировка:
+        {"protection": {"locked": False}}.
+
+        Arguments:
+        ---
+            password: str | None
+                Пароль снятия защиты, None - без пароля
+            options: dict[str, Any] | None
+                Разрешенные действия, незаданные ключи принимают значения
+                по умолчанию, например:
+                {"select_locked_cells": True, "select_unlocked_cells": True,
+                "format_cells": False, "format_columns": False, "format_rows": False,
+                "insert_columns": False, "insert_rows": False,
+                "insert_hyperlinks": False, "delete_columns": False,
+                "delete_rows": False, "sort": True, "auto_filter": True,
+                "pivot_tables": False, "objects": False, "scenarios": False}
+        """
+
+    @final
+    def unprotect(self) -> None:
+        """Снятие защиты листа"""
+
+    @final
+    def get_level_by_row(self, row_num: int) -> int:
+        """
+        Метод для получения уровня группировки строки
+        ---
+
+        Arguments:
+        ---
+            row_num: int
+                Номер строки
+
+        Returns:
+        ---
+            int
+                Уровень группировки, 0 - вне группы
+        """
+
+    @final
+    def get_level_by_column(self, col_num: int) -> int:
+        """
+        Метод для получения уровня группировки колонки
+        ---
+
+        Arguments:
+        ---
+            col_num: int
+                Номер колонки
+
+        Returns:
+        ---
+            int
+                Уровень группировки, 0 - вне группы
+        """
+
+    @final
+    def find_cell_by_regex(
+        self,
+        regex: str,
+        by_cols: bool = False,
+        merged: bool = False,
+    ) -> Cell | None:
         """
         Функция для получения ячейки по регулярному (шаблону) значению.
         ---
 
+        Возвращается первое совпадение построчно (слева направо, сверху вниз),
+        при by_cols=True - по колонкам (сверху вниз, слева направо).
+
         Arguments:
         ---
             regex: str
                 Шаблон (регулярное значение)
+            by_cols: bool
+                Искать по колонкам
+            merged: bool
+                Пропускать ячейки, закрытые объединением: объединение
+                ищется по значению левой верхней ячейки
 
         Returns:
         ---
@@ -704,15 +1566,28 @@ class Sheet:
         """
 
     @final
-    def find_cell_by_str(self, value: str) -> Cell | None:
+    def find_cell_by_str(
+        self,
+        value: str,
+        by_cols: bool = False,
+        merged: bool = False,
+    ) -> Cell | None:
         """
         Функция для получения ячейки по строковому значению.
         ---
 
+        Возвращается первое совпадение построчно (слева направо, сверху вниз),
+        при by_cols=True - по колонкам (сверху вниз, слева направо).
+
         Arguments:
         ---
             value: str
                 строка (значение)
+            by_cols: bool
+                Искать по колонкам
+            merged: bool
+                Пропускать ячейки, закрытые объединением: объединение
+                ищется по значению левой верхней ячейки
 
         Returns:
         ---
@@ -725,10 +1600,12 @@ class Sheet:
         Функция для получения ячейки по буквенной координате (A1).
         ---
 
+        Некорректная координата, например "A" или "1A", вызывает ValueError.
+
         Arguments:
         ---------
             letter: str
-                Координата (буквенная) A1
+                Координата (буквенная) A1 или $A$1
 
         Returns:
         --------
@@ -852,8 +1729,8 @@ class Sheet:
     @final
     def find_cells_range_rows(
         self,
-        start_row: int,
-        end_row: int,
+        start_row: int | str,
+        end_row: int | None = None,
     ) -> Sequence[Cell]:
         """
         Функция для получения ячeек в диапазоне строк.
@@ -861,9 +1738,9 @@ class Sheet:
 
         Arguments:
         ---
-            start_row: int
-                Cтартовая строка
-            end_row: int
+            start_row: int | str
+                Cтартовая строка или диапазон в стиле A1, например "3:5"
+            end_row: int | None
                 Финишная строка
 
         Returns:
@@ -874,8 +1751,8 @@ class Sheet:
     @final
     def find_cells_range_cols(
         self,
-        start_col: int,
-        end_col: int,
+        start_col: int | str,
+        end_col: int | None = None,
     ) -> Sequence[Cell]:
         """
         Функция для получения ячeек в диапазоне колонок.
@@ -883,9 +1760,9 @@ class Sheet:
 
         Arguments:
         ---
-            start_col: int
-                Cтартовая колонка
-            end_col: int
+            start_col: int | str
+                Cтартовая колонка или диапазон в стиле A1, например "A:C"
+            end_col: int | None
                 Финишная колонка
 
         Returns:
@@ -898,6 +1775,7 @@ class Sheet:
         self,
         row: int,
         col: int,
+        merged: bool = False,
     ) -> str | None:
         """
         Функция для поиска значения ячейки по координатам
@@ -909,6 +1787,8 @@ class Sheet:
                 Индекс строки
             col: int
                 Индекс колонки
+            merged: bool
+                Для объединенной ячейки вернуть значение левой верхней ячейки объединения
 
         Returns:
         ---
@@ -923,9 +1803,46 @@ class Cell:
     value: Any | None
     formula: str | None
     data_type: str
-    style: str | None
+    # Имя стиля из реестра книги или словарь со встроенным описанием стиля
+    style: str | dict[str, Any] | None
+    # Числовой формат ячейки, например "# ##0,00", "0.0%" или "dd.mm.yyyy"
+    number_format: str | None
     hidden_value: str | None
 
+    @property
+    def hyperlink(self) -> str | None:
+        """
+        Получение адреса гиперссылки: внешний адрес или место в книге
+        ---
+
+        Returns:
+        ---
+            str | None
+        """
+
+    @property
+    def hyperlink_tooltip(self) -> str | None:
+        """
+        Получение всплывающей подсказки гиперссылки
+        ---
+
+        Returns:
+        ---
+            str | None
+        """
+
+    @property
+    def rich_text(self) -> list[dict[str, Any]] | None:
+        """
+        Получение форматированного текста ячейки. Текст без форматирования - value
+        ---
+
+        Returns:
+        ---
+            list[dict[str, Any]] | None
+                Фрагменты вида {"text": "Итого: ", "font": {"bold": True}}
+        """
+
     @property
     def letter(self) -> int:
         """
@@ -937,6 +1854,19 @@ class Cell:
             str
         """
 
+    @property
+    def display_value(self) -> str:
+        """
+        Получение значения в том виде, в котором его покажет Excel
+        с учетом числового формата ячейки. Формат именованного стиля
+        книги не учитывается, см. Book.get_display_value
+        ---
+
+        Returns:
+        ---
+            str
+        """
+
     @property
     def is_formula(self) -> bool:
         """
@@ -996,6 +1926,37 @@ class Cell:
 
         """
 
+    @final
+    def set_hyperlink(self, target: str, tooltip: str | None = None) -> None:
+        """
+        Метод для установки гиперссылки. Пустой адрес удаляет гиперссылку
+        ---
+
+        Arguments:
+        ---------
+            target: str
+                Внешний адрес (https://..., mailto:...) или место в книге,
+                например 'Лист 2'!A1
+            tooltip: str | None
+                Всплывающая подсказка
+
+        """
+
+    @final
+    def set_value_rich_text(self, runs: list[dict[str, Any]]) -> None:
+        """
+        Метод для добавления форматированного текста из фрагментов.
+        ---
+
+        Arguments:
+        ---------
+            runs: list[dict[str, Any]]
+                Фрагменты с текстом и шрифтом (name, size, bold, italic,
+                underline, strike, color), например
+                [{"text": "Итого: ", "font": {"bold": True}}, {"text": "100"}]
+
+        """
+
     @final
     def set_value_number(self, value: float) -> None:
         """
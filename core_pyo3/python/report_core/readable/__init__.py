@@ -18,6 +18,7 @@ from ._readable import (  # type: ignore
     find_value_by_coords,
     find_values_by_col_rows,
     find_values_by_row_cols,
+    load_workbook,
 )
 
 __all__ = [
@@ -25,6 +26,7 @@ __all__ = [
     "ReadableSheet",
     "ReadableCell",
     "create_finder",
+    "load_workbook",
     "find_value_by_coords",
     "find_cell_by_coords",
     "find_cell_by_regex",
@@ -20,6 +20,8 @@ class Finder:
     def find_sheet_by_name(self, name: str) -> ReadableSheet | None: ...
     @final
     def find_sheet_by_regex(self, pattern: str) -> ReadableSheet | None: ...
+    @final
+    def resolve_name(self, name: str) -> Sequence[ReadableCell]: ...
 
 class ReadableSheet:
     name: str
@@ -33,7 +35,11 @@ class ReadableSheet:
     @property
     def max_column(self) -> int: ...
     @final
-    def get_value_cell(self, row: int, column: int) -> str: ...
+    def get_value_cell(self, row: int, col: int, merged: bool = False) -> str: ...
+    @final
+    def merged_range_at(
+        self, row: int, col: int
+    ) -> tuple[int, int, int, int] | None: ...
     @final
     def get_cells_by_range(
         self,
@@ -43,9 +49,13 @@ class ReadableSheet:
         end_col: int | None = None,
     ) -> Sequence[ReadableCell]: ...
     @final
-    def find_cell_by_regex(self, regex: str) -> ReadableCell | None: ...
+    def find_cell_by_regex(
+        self, regex: str, by_cols: bool = False, merged: bool = False
+    ) -> ReadableCell | None: ...
     @final
-    def find_cell_by_str(self, value: str) -> ReadableCell | None: ...
+    def find_cell_by_str(
+        self, value: str, by_cols: bool = False, merged: bool = False
+    ) -> ReadableCell | None: ...
     @final
     def find_cell_by_coords(self, row: int, col: int) -> ReadableCell | None: ...
     @final
@@ -72,22 +82,24 @@ class ReadableSheet:
     ) -> Sequence[ReadableCell]: ...
     @final
     def find_cells_range_rows(
-        self, start_row: int, end_row: int
+        self, start_row: int | str, end_row: int | None = None
     ) -> Sequence[ReadableCell]: ...
     @final
     def find_cells_range_cols(
-        self, start_col: int, end_col: int
+        self, start_col: int | str, end_col: int | None = None
     ) -> Sequence[ReadableCell]: ...
     @final
     def find_values_by_col_rows(
-        self, col: int, rows: Sequence[int]
+        self, col: int, rows: Sequence[int], merged: bool = False
     ) -> Sequence[str]: ...
     @final
     def find_values_by_row_cols(
-        self, row: int, cols: Sequence[int]
+        self, row: int, cols: Sequence[int], merged: bool = False
     ) -> Sequence[str]: ...
     @final
-    def find_value_by_coords(self, row: int, col: int) -> str | None: ...
+    def find_value_by_coords(
+        self, row: int, col: int, merged: bool = False
+    ) -> str | None: ...
 
 class ReadableCell:
     value: str
@@ -97,6 +109,14 @@ class ReadableCell:
 
     def __repr__(self) -> str: ...
     @property
+    def display_value(self) -> str: ...
+    @property
+    def hyperlink(self) -> str | None: ...
+    @property
+    def hyperlink_tooltip(self) -> str | None: ...
+    @property
+    def rich_text(self) -> list[dict[str, Any]] | None: ...
+    @property
     def row(self) -> int: ...
     @property
     def column(self) -> int: ...
@@ -149,10 +169,16 @@ def find_cells_between_regex(
     before_regex: str, after_regex: str, cells: Sequence[ReadableCell]
 ) -> Sequence[ReadableCell]: ...
 def find_cells_range_rows(
-    start_row: int, end_row: int, cells: Sequence[ReadableCell]
+    start_row: int | str,
+    end_row: int | None = None,
+    *,
+    cells: Sequence[ReadableCell],
 ) -> Sequence[ReadableCell]: ...
 def find_cells_range_cols(
-    start_col: int, end_col: int, cells: Sequence[ReadableCell]
+    start_col: int | str,
+    end_col: int | None = None,
+    *,
+    cells: Sequence[ReadableCell],
 ) -> Sequence[ReadableCell]: ...
 def find_values_by_col_rows(
     col: int, rows: Sequence[int], cells: Sequence[ReadableCell]
@@ -161,3 +187,4 @@ def find_values_by_row_cols(
     row: int, cols: Sequence[int], cells: Sequence[ReadableCell]
 ) -> Sequence[str]: ...
 def create_finder(sheets: Sequence[Any]) -> Finder: ...
+def load_workbook(path: str) -> Finder: ...
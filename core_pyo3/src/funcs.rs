@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
-use core_rs::{funcs, traits::ReadableCell};
+use core_rs::{funcs, structs::coordinate::Coordinate, traits::ReadableCell};
 use pyo3::{prelude::*, types::PyList};
 
-use crate::structs::readable::cell::WrapperCell;
+use crate::structs::{
+    coordinate::{extract_col_span, extract_coordinate, extract_row_span},
+    readable::cell::WrapperCell,
+};
 
 /// вспомогательная функция для преобразования PyList во WrapperCell
 fn extract_pylist(cells: &Bound<'_, PyList>) -> PyResult<Vec<WrapperCell>> {
@@ -91,11 +94,12 @@ pub(crate) fn find_cell_by_letter(
     letter: String,
     cells: &Bound<'_, PyList>,
 ) -> PyResult<Option<WrapperCell>> {
+    let Coordinate { row, column } = extract_coordinate(&letter)?;
     let cells = extract_pylist(cells)?;
 
     py.detach(|| {
         let cells = cells.iter().map(|c| &c.0).collect();
-        Ok(funcs::find_cell_by_letter(letter, cells)?.map(|c| WrapperCell(Arc::clone(c))))
+        Ok(funcs::find_cell_by_coords(row, column, cells)?.map(|c| WrapperCell(Arc::clone(c))))
     })
 }
 
@@ -217,12 +221,14 @@ pub(crate) fn find_cells_between_regex(
 
 #[inline]
 #[pyfunction]
+#[pyo3(signature = (start_row, end_row=None, *, cells))]
 pub(crate) fn find_cells_range_rows(
     py: Python<'_>,
-    start_row: u32,
-    end_row: u32,
+    start_row: &Bound<'_, PyAny>,
+    end_row: Option<u32>,
     cells: &Bound<'_, PyList>,
 ) -> PyResult<Vec<WrapperCell>> {
+    let (start_row, end_row) = extract_row_span(start_row, end_row)?;
     let cells = extract_pylist(cells)?;
 
     py.detach(|| {
@@ -236,12 +242,14 @@ pub(crate) fn find_cells_range_rows(
 
 #[inline]
 #[pyfunction]
+#[pyo3(signature = (start_col, end_col=None, *, cells))]
 pub(crate) fn find_cells_range_cols(
     py: Python<'_>,
-    start_col: u16,
-    end_col: u16,
+    start_col: &Bound<'_, PyAny>,
+    end_col: Option<u16>,
     cells: &Bound<'_, PyList>,
 ) -> PyResult<Vec<WrapperCell>> {
+    let (start_col, end_col) = extract_col_span(start_col, end_col)?;
     let cells = extract_pylist(cells)?;
 
     py.detach(|| {
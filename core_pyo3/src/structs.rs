@@ -1,6 +1,12 @@
 pub mod book;
 pub mod cell;
 pub mod comment;
+pub mod coordinate;
+pub mod data_validation;
+pub mod json;
+pub mod protection;
 pub mod readable;
 pub mod service;
 pub mod sheet;
+pub mod style;
+pub mod toc;
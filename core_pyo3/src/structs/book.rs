@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
-use core_rs::structs::book::Book;
+use core_rs::structs::{book::Book, protection::WorkbookProtection};
 
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 
-use super::sheet::WrapperSheet;
+use super::{
+    json::{from_py, to_py},
+    sheet::WrapperSheet,
+    toc::extract_toc_options,
+};
 
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core", name = "Book")]
@@ -43,7 +47,7 @@ impl WrapperBook {
 
     #[pyo3(signature = (name, sheet_state="visible"))]
     pub fn add_sheet(&self, name: &str, sheet_state: &str) -> PyResult<WrapperSheet> {
-        let sheet = self.0.write().add_sheet(name, sheet_state);
+        let sheet = self.0.write().add_sheet(name, sheet_state)?;
 
         Ok(WrapperSheet(sheet))
     }
@@ -70,10 +74,128 @@ impl WrapperBook {
         }
     }
 
+    pub fn insert_rows(&self, sheet_name: &str, idx: u32, amount: u32) -> PyResult<()> {
+        Ok(self.0.write().insert_rows(sheet_name, idx, amount)?)
+    }
+
+    pub fn insert_cols(&self, sheet_name: &str, idx: u16, amount: u16) -> PyResult<()> {
+        Ok(self.0.write().insert_cols(sheet_name, idx, amount)?)
+    }
+
+    pub fn register_style(&self, name: &str, style: &Bound<'_, PyAny>) -> PyResult<()> {
+        let format = from_py(style)?;
+
+        Ok(self.0.write().register_style(name, format)?)
+    }
+
+    pub fn rename_sheet(&self, old_name: &str, new_name: &str) -> PyResult<()> {
+        Ok(self.0.write().rename_sheet(old_name, new_name)?)
+    }
+
+    #[pyo3(signature = (options=None))]
+    pub fn build_toc(&self, options: Option<&Bound<'_, PyAny>>) -> PyResult<WrapperSheet> {
+        let options = extract_toc_options(options)?;
+        let sheet = self.0.write().build_toc(&options)?;
+
+        Ok(WrapperSheet(sheet))
+    }
+
+    #[pyo3(signature = (password=None, lock_structure=true, lock_windows=false))]
+    pub fn protect(
+        &self,
+        password: Option<&str>,
+        lock_structure: bool,
+        lock_windows: bool,
+    ) -> PyResult<()> {
+        let mut protection = WorkbookProtection {
+            lock_structure,
+            lock_windows,
+            ..Default::default()
+        };
+        if let Some(password) = password {
+            protection.set_password(password);
+        }
+
+        Ok(self.0.write().set_protection(Some(protection))?)
+    }
+
+    pub fn unprotect(&self) -> PyResult<()> {
+        Ok(self.0.write().set_protection(None)?)
+    }
+
+    #[getter]
+    pub fn get_protection(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        self.0
+            .read()
+            .get_protection()
+            .map(|protection| to_py(py, protection))
+            .transpose()
+    }
+
+    #[pyo3(signature = (name, reference, scope=None))]
+    pub fn define_name(&self, name: &str, reference: &str, scope: Option<&str>) -> PyResult<()> {
+        Ok(self.0.write().define_name(name, reference, scope)?)
+    }
+
+    #[getter]
+    pub fn defined_names(&self) -> PyResult<Vec<(String, String, Option<String>)>> {
+        let names = self
+            .0
+            .read()
+            .get_defined_names()
+            .iter()
+            .map(|n| {
+                (
+                    n.get_name().to_string(),
+                    n.get_reference().to_string(),
+                    n.get_scope().map(str::to_string),
+                )
+            })
+            .collect();
+
+        Ok(names)
+    }
+
+    pub fn get_style(&self, py: Python<'_>, name: &str) -> PyResult<Option<Py<PyAny>>> {
+        match self.0.read().get_style(name) {
+            Some(format) => Ok(Some(to_py(py, format)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_display_value(&self, sheet_name: &str, row: u32, col: u16) -> Option<String> {
+        self.0.read().get_display_value(sheet_name, (row, col))
+    }
+
+    pub fn delete_rows(&self, sheet_name: &str, idx: u32, amount: u32) -> PyResult<()> {
+        Ok(self.0.write().delete_rows(sheet_name, idx, amount)?)
+    }
+
+    pub fn delete_cols(&self, sheet_name: &str, idx: u16, amount: u16) -> PyResult<()> {
+        Ok(self.0.write().delete_cols(sheet_name, idx, amount)?)
+    }
+
+    pub fn calculate(&self, py: Python<'_>) -> PyResult<()> {
+        py.detach(|| self.0.read().calculate());
+
+        Ok(())
+    }
+
     pub fn to_json(&self) -> PyResult<String> {
         Ok(self.0.read().to_json()?)
     }
 
+    #[staticmethod]
+    pub fn from_json(py: Python<'_>, value: &str) -> PyResult<Self> {
+        let book = py.detach(|| Book::from_json(value))?;
+
+        Ok(Self(Arc::new(RwLock::new(book))))
+    }
+
+    pub fn save_xlsx(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        py.detach(|| Ok(self.0.read().save_xlsx(path)?))
+    }
+
     pub fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let s = self.0.read().to_json()?;
         let py_module_json = py.import("json")?;
@@ -4,10 +4,15 @@ use pyo3::prelude::*;
 use std::sync::Arc;
 
 use core_rs::{
-    structs::cell::Cell,
+    structs::{cell::Cell, rich_text::RichText},
     traits::{ReadableCell, WriteableCell},
 };
 
+use super::{
+    json::{from_py, to_py},
+    style::extract_style,
+};
+
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core", name = "Cell")]
 #[derive(Debug, Clone)]
@@ -50,6 +55,13 @@ impl WrapperCell {
         Ok(slf.get_value())
     }
 
+    #[getter]
+    pub fn get_display_value(&self) -> PyResult<String> {
+        let slf = self.0.read();
+
+        Ok(slf.get_display_value())
+    }
+
     #[getter]
     pub fn get_formula(&self) -> PyResult<Option<String>> {
         let slf = self.0.read();
@@ -58,11 +70,50 @@ impl WrapperCell {
     }
 
     #[getter]
-    pub fn get_style(&self) -> PyResult<Option<String>> {
+    pub fn get_style(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let slf = self.0.read();
+
+        match slf.get_style() {
+            Some(style) => match style.get_format() {
+                Some(format) => Ok(Some(to_py(py, format)?)),
+                None => Ok(Some(style.get_id().into_pyobject(py)?.into_any().unbind())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    #[getter]
+    pub fn get_number_format(&self) -> PyResult<Option<String>> {
+        let slf = self.0.read();
+
+        Ok(slf.get_number_format())
+    }
+
+    #[getter]
+    pub fn get_hyperlink(&self) -> PyResult<Option<String>> {
+        let slf = self.0.read();
+
+        Ok(slf
+            .get_hyperlink()
+            .map(|link| link.get_target().to_string()))
+    }
+
+    #[getter]
+    pub fn get_hyperlink_tooltip(&self) -> PyResult<Option<String>> {
         let slf = self.0.read();
-        let style = slf.get_style().map(|s| s.get_id());
 
-        Ok(style)
+        Ok(slf
+            .get_hyperlink()
+            .and_then(|link| link.get_tooltip().map(str::to_string)))
+    }
+
+    #[getter]
+    pub fn get_rich_text(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let slf = self.0.read();
+
+        slf.get_rich_text()
+            .map(|text| to_py(py, text.get_runs()))
+            .transpose()
     }
 
     #[getter]
@@ -138,9 +189,18 @@ impl WrapperCell {
     }
 
     #[setter]
-    pub fn set_style(&self, value: &str) -> PyResult<()> {
+    pub fn set_style(&self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let style = extract_style(value)?;
+        let mut slf = self.0.write();
+        slf.set_style(style);
+
+        Ok(())
+    }
+
+    #[setter]
+    pub fn set_number_format(&self, value: Option<&str>) -> PyResult<()> {
         let mut slf = self.0.write();
-        slf.set_style(value);
+        slf.set_number_format(value.unwrap_or_default());
 
         Ok(())
     }
@@ -153,6 +213,23 @@ impl WrapperCell {
         Ok(())
     }
 
+    #[pyo3(signature = (target, tooltip=None))]
+    pub fn set_hyperlink(&self, target: &str, tooltip: Option<&str>) -> PyResult<()> {
+        let mut slf = self.0.write();
+        slf.set_hyperlink(target, tooltip);
+
+        Ok(())
+    }
+
+    pub fn set_value_rich_text(&self, runs: &Bound<'_, PyAny>) -> PyResult<()> {
+        let value = RichText::new(from_py(runs)?);
+
+        let mut slf = self.0.write();
+        slf.set_value_rich_text(value);
+
+        Ok(())
+    }
+
     pub fn set_value_number(&self, value: f64) -> PyResult<()> {
         let mut slf = self.0.write();
         slf.set_value_number(value);
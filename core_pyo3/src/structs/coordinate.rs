@@ -0,0 +1,64 @@
+use core_rs::structs::{coordinate::Coordinate, range::Range};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+};
+
+/// Разбор адреса ячейки в стиле A1
+pub(crate) fn extract_coordinate(letter: &str) -> PyResult<Coordinate> {
+    Coordinate::parse(letter).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Разбор диапазона в стиле A1, например "B2:D10", "A:C" или "3:5"
+pub(crate) fn extract_range(value: &str) -> PyResult<Range> {
+    Range::parse(value).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Диапазон из строки в стиле A1 или из границ (start_row, end_row, start_col, end_col)
+pub(crate) fn extract_range_args(
+    start_row: &Bound<'_, PyAny>,
+    end_row: Option<u32>,
+    start_col: Option<u16>,
+    end_col: Option<u16>,
+) -> PyResult<Range> {
+    match (start_row.extract::<String>(), end_row, start_col, end_col) {
+        (Ok(value), None, None, None) => extract_range(&value),
+        (Err(_), Some(end_row), Some(start_col), Some(end_col)) => Ok(Range::new(
+            start_row.extract()?,
+            end_row,
+            start_col,
+            end_col,
+        )),
+        _ => Err(PyTypeError::new_err(
+            "Expected a range like 'A1:C1' or start_row, end_row, start_col, end_col",
+        )),
+    }
+}
+
+/// Границы строк из номеров или из диапазона в стиле A1, например "3:5"
+pub(crate) fn extract_row_span(
+    start_row: &Bound<'_, PyAny>,
+    end_row: Option<u32>,
+) -> PyResult<(u32, u32)> {
+    match (start_row.extract::<String>(), end_row) {
+        (Ok(value), None) => extract_range(&value).map(|r| (r.start_row, r.end_row)),
+        (Err(_), Some(end_row)) => Ok((start_row.extract()?, end_row)),
+        _ => Err(PyTypeError::new_err(
+            "Expected a range like '3:5' or start_row, end_row",
+        )),
+    }
+}
+
+/// Границы колонок из номеров или из диапазона в стиле A1, например "A:C"
+pub(crate) fn extract_col_span(
+    start_col: &Bound<'_, PyAny>,
+    end_col: Option<u16>,
+) -> PyResult<(u16, u16)> {
+    match (start_col.extract::<String>(), end_col) {
+        (Ok(value), None) => extract_range(&value).map(|r| (r.start_col, r.end_col)),
+        (Err(_), Some(end_col)) => Ok((start_col.extract()?, end_col)),
+        _ => Err(PyTypeError::new_err(
+            "Expected a range like 'A:C' or start_col, end_col",
+        )),
+    }
+}
@@ -0,0 +1,14 @@
+use core_rs::structs::data_validation::ErrorStyle;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Реакция на недопустимое значение из строки
+pub(crate) fn extract_error_style(value: &str) -> PyResult<ErrorStyle> {
+    match value {
+        "stop" => Ok(ErrorStyle::Stop),
+        "warning" => Ok(ErrorStyle::Warning),
+        "information" => Ok(ErrorStyle::Information),
+        _ => Err(PyValueError::new_err(format!(
+            "Invalid error style: {value}"
+        ))),
+    }
+}
@@ -0,0 +1,23 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Преобразование объекта Python (словаря, списка) в структуру через JSON
+pub(crate) fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json = value
+        .py()
+        .import("json")?
+        .getattr("dumps")?
+        .call1((value,))?;
+
+    serde_json::from_str(&json.extract::<String>()?)
+        .map_err(|e| PyValueError::new_err(format!("Invalid value: {e}")))
+}
+
+/// Преобразование структуры в объект Python (словарь, список) через JSON
+pub(crate) fn to_py<T: Serialize + ?Sized>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| PyValueError::new_err(format!("Invalid value: {e}")))?;
+    let py_value = py.import("json")?.getattr("loads")?.call1((json,))?;
+
+    Ok(py_value.into())
+}
@@ -0,0 +1,21 @@
+use core_rs::structs::protection::SheetProtection;
+use pyo3::prelude::*;
+
+use super::json::from_py;
+
+/// Преобразование словаря Python в параметры защиты листа
+pub(crate) fn extract_sheet_protection(
+    password: Option<&str>,
+    options: Option<&Bound<'_, PyAny>>,
+) -> PyResult<SheetProtection> {
+    let mut protection = match options.filter(|v| !v.is_none()) {
+        Some(value) => from_py(value)?,
+        None => SheetProtection::default(),
+    };
+
+    if let Some(password) = password {
+        protection.set_password(password);
+    }
+
+    Ok(protection)
+}
@@ -20,3 +20,13 @@ pub(crate) fn create_finder(py: Python, sheets: &Bound<'_, PyList>) -> PyResult<
 
     py.detach(|| Ok(WrapperFinder(Arc::new(RwLock::new(Finder::new(sheets?))))))
 }
+
+#[inline]
+#[pyfunction]
+pub(crate) fn load_workbook(py: Python, path: &str) -> PyResult<WrapperFinder> {
+    py.detach(|| {
+        let finder = Finder::load_xlsx(path)?;
+
+        Ok(WrapperFinder(Arc::new(RwLock::new(finder))))
+    })
+}
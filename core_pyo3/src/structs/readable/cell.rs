@@ -10,7 +10,7 @@ use pyo3::{
     types::{PyDict, PyString},
 };
 
-use crate::py_extract;
+use crate::{py_extract, structs::json::to_py};
 
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core.readable", name = "ReadableCell")]
@@ -83,6 +83,13 @@ impl WrapperCell {
         Ok(slf.get_value())
     }
 
+    #[getter]
+    pub fn get_display_value(&self) -> PyResult<String> {
+        let slf = self.0.read();
+
+        Ok(slf.get_display_value())
+    }
+
     #[getter]
     pub fn get_formula(&self) -> PyResult<Option<String>> {
         let slf = self.0.read();
@@ -98,6 +105,33 @@ impl WrapperCell {
         Ok(style)
     }
 
+    #[getter]
+    pub fn get_hyperlink(&self) -> PyResult<Option<String>> {
+        let slf = self.0.read();
+
+        Ok(slf
+            .get_hyperlink()
+            .map(|link| link.get_target().to_string()))
+    }
+
+    #[getter]
+    pub fn get_hyperlink_tooltip(&self) -> PyResult<Option<String>> {
+        let slf = self.0.read();
+
+        Ok(slf
+            .get_hyperlink()
+            .and_then(|link| link.get_tooltip().map(str::to_string)))
+    }
+
+    #[getter]
+    pub fn get_rich_text(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let slf = self.0.read();
+
+        slf.get_rich_text()
+            .map(|text| to_py(py, text.get_runs()))
+            .transpose()
+    }
+
     #[getter]
     pub fn get_data_type(&self) -> PyResult<String> {
         let slf = self.0.read();
@@ -8,7 +8,7 @@ use pyo3::{
     types::{PyList, PyString},
 };
 
-use super::sheet::WrapperSheet;
+use super::{cell::WrapperCell, sheet::WrapperSheet};
 
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core.readable", name = "Finder")]
@@ -109,4 +109,16 @@ impl WrapperFinder {
             Ok(None)
         }
     }
+
+    pub fn resolve_name(&self, name: &str) -> PyResult<Vec<WrapperCell>> {
+        let cells = self
+            .0
+            .read()
+            .resolve_name(name)
+            .into_iter()
+            .map(WrapperCell)
+            .collect();
+
+        Ok(cells)
+    }
 }
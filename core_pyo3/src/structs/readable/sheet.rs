@@ -4,6 +4,7 @@ use ahash::HashMap;
 use core_rs::{
     structs::{
         cell::Cell,
+        cells::SearchOrder,
         coordinate::Coordinate,
         range::{MergedRange, Range},
         sheet::Sheet,
@@ -17,7 +18,10 @@ use pyo3::{
 };
 
 use super::cell::WrapperCell;
-use crate::py_extract;
+use crate::{
+    py_extract,
+    structs::coordinate::{extract_col_span, extract_coordinate, extract_row_span},
+};
 
 /// Вспомогптельная функция для преобразования cells в rust тип
 type CellMap = HashMap<(u32, u16), Arc<RwLock<Cell>>>;
@@ -133,11 +137,24 @@ impl WrapperSheet {
         Ok(merged_cells)
     }
 
-    pub fn get_value_cell(&self, row: u32, col: u16) -> PyResult<String> {
+    #[pyo3(signature = (row, col, merged=false))]
+    pub fn get_value_cell(&self, row: u32, col: u16, merged: bool) -> PyResult<String> {
         let slf = self.0.read();
 
         let coord = Coordinate::from((row, col));
-        Ok(slf.get_cell_value(coord))
+        if merged {
+            Ok(slf.get_cell_value_merged(coord))
+        } else {
+            Ok(slf.get_cell_value(coord))
+        }
+    }
+
+    pub fn merged_range_at(&self, row: u32, col: u16) -> PyResult<Option<MergedRange>> {
+        let slf = self.0.read();
+
+        Ok(slf
+            .merged_range_at(&Coordinate::new(row, col))
+            .map(|range| range.into()))
     }
 
     #[pyo3(signature = (start_row=None, end_row=None, start_col=None, end_col=None))]
@@ -158,20 +175,48 @@ impl WrapperSheet {
         Ok(cells)
     }
 
-    pub fn find_cell_by_regex(&self, regex: &str) -> PyResult<Option<WrapperCell>> {
+    #[pyo3(signature = (regex, by_cols=false, merged=false))]
+    pub fn find_cell_by_regex(
+        &self,
+        regex: &str,
+        by_cols: bool,
+        merged: bool,
+    ) -> PyResult<Option<WrapperCell>> {
+        let order = if by_cols {
+            SearchOrder::Cols
+        } else {
+            SearchOrder::Rows
+        };
         let slf = self.0.read();
+        let cell = if merged {
+            slf.find_cell_by_regex_merged(regex, order)?
+        } else {
+            slf.find_cell_by_regex(regex, order)?
+        };
 
-        Ok(slf
-            .find_cell_by_regex(regex)?
-            .map(|c| WrapperCell(Arc::clone(c))))
+        Ok(cell.map(|c| WrapperCell(Arc::clone(c))))
     }
 
-    pub fn find_cell_by_str(&self, value: &str) -> PyResult<Option<WrapperCell>> {
+    #[pyo3(signature = (value, by_cols=false, merged=false))]
+    pub fn find_cell_by_str(
+        &self,
+        value: &str,
+        by_cols: bool,
+        merged: bool,
+    ) -> PyResult<Option<WrapperCell>> {
+        let order = if by_cols {
+            SearchOrder::Cols
+        } else {
+            SearchOrder::Rows
+        };
         let slf = self.0.read();
+        let cell = if merged {
+            slf.find_cell_by_str_merged(value, order)?
+        } else {
+            slf.find_cell_by_str(value, order)?
+        };
 
-        Ok(slf
-            .find_cell_by_str(value)?
-            .map(|c| WrapperCell(Arc::clone(c))))
+        Ok(cell.map(|c| WrapperCell(Arc::clone(c))))
     }
 
     pub fn find_cell_by_coords(&self, row: u32, col: u16) -> PyResult<Option<WrapperCell>> {
@@ -183,10 +228,11 @@ impl WrapperSheet {
     }
 
     pub fn find_cell_by_letter(&self, letter: &str) -> PyResult<Option<WrapperCell>> {
+        let Coordinate { row, column } = extract_coordinate(letter)?;
         let slf = self.0.read();
 
         Ok(slf
-            .find_cell_by_letter(letter)?
+            .find_cell_by_coords(row, column)?
             .map(|c| WrapperCell(Arc::clone(c))))
     }
 
@@ -282,12 +328,13 @@ impl WrapperSheet {
         Ok(wrapper_cells)
     }
 
+    #[pyo3(signature = (start_row, end_row=None))]
     pub fn find_cells_range_rows(
         &self,
-
-        start_row: u32,
-        end_row: u32,
+        start_row: &Bound<'_, PyAny>,
+        end_row: Option<u32>,
     ) -> PyResult<Vec<WrapperCell>> {
+        let (start_row, end_row) = extract_row_span(start_row, end_row)?;
         let slf = self.0.read();
 
         let wrapper_cells = slf
@@ -299,12 +346,13 @@ impl WrapperSheet {
         Ok(wrapper_cells)
     }
 
+    #[pyo3(signature = (start_col, end_col=None))]
     pub fn find_cells_range_cols(
         &self,
-
-        start_col: u16,
-        end_col: u16,
+        start_col: &Bound<'_, PyAny>,
+        end_col: Option<u16>,
     ) -> PyResult<Vec<WrapperCell>> {
+        let (start_col, end_col) = extract_col_span(start_col, end_col)?;
         let slf = self.0.read();
 
         let wrapper_cells = slf
@@ -316,21 +364,51 @@ impl WrapperSheet {
         Ok(wrapper_cells)
     }
 
-    pub fn find_values_by_col_rows(&self, col: u16, rows: Vec<u32>) -> PyResult<Vec<String>> {
+    #[pyo3(signature = (col, rows, merged=false))]
+    pub fn find_values_by_col_rows(
+        &self,
+        col: u16,
+        rows: Vec<u32>,
+        merged: bool,
+    ) -> PyResult<Vec<String>> {
         let slf = self.0.read();
 
-        Ok(slf.find_values_by_col_rows(col, rows)?)
+        if merged {
+            Ok(slf.find_values_by_col_rows_merged(col, rows)?)
+        } else {
+            Ok(slf.find_values_by_col_rows(col, rows)?)
+        }
     }
 
-    pub fn find_values_by_row_cols(&self, row: u32, cols: Vec<u16>) -> PyResult<Vec<String>> {
+    #[pyo3(signature = (row, cols, merged=false))]
+    pub fn find_values_by_row_cols(
+        &self,
+        row: u32,
+        cols: Vec<u16>,
+        merged: bool,
+    ) -> PyResult<Vec<String>> {
         let slf = self.0.read();
 
-        Ok(slf.find_values_by_row_cols(row, cols)?)
+        if merged {
+            Ok(slf.find_values_by_row_cols_merged(row, cols)?)
+        } else {
+            Ok(slf.find_values_by_row_cols(row, cols)?)
+        }
     }
 
-    pub fn find_value_by_coords(&self, row: u32, col: u16) -> PyResult<Option<String>> {
+    #[pyo3(signature = (row, col, merged=false))]
+    pub fn find_value_by_coords(
+        &self,
+        row: u32,
+        col: u16,
+        merged: bool,
+    ) -> PyResult<Option<String>> {
         let slf = self.0.read();
 
-        Ok(slf.find_value_by_coords(row, col)?)
+        if merged {
+            Ok(slf.find_value_by_coords_merged(row, col)?)
+        } else {
+            Ok(slf.find_value_by_coords(row, col)?)
+        }
     }
 }
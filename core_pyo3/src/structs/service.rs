@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use core_rs::structs::book::Book;
+use core_rs::structs::{book::Book, protection::WorkbookProtection};
 
 use parking_lot::RwLock;
 use pyo3::{
@@ -9,7 +9,7 @@ use pyo3::{
     types::{PyDict, PyList, PyString},
 };
 
-use super::sheet::WrapperSheet;
+use super::{json::from_py, sheet::WrapperSheet, toc::extract_toc_options};
 
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core", name = "Service", subclass)]
@@ -84,7 +84,7 @@ impl WrapperService {
 
     #[pyo3(name = "_add_sheet", signature = (name, sheet_state="visible"))]
     pub fn add_sheet(&self, name: &str, sheet_state: &str) -> PyResult<WrapperSheet> {
-        let sheet = self.inner.write().add_sheet(name, sheet_state);
+        let sheet = self.inner.write().add_sheet(name, sheet_state)?;
         Ok(WrapperSheet(sheet))
     }
 
@@ -113,10 +113,89 @@ impl WrapperService {
         }
     }
 
+    #[pyo3(name = "_register_style")]
+    pub fn register_style(&self, name: &str, style: &Bound<'_, PyAny>) -> PyResult<()> {
+        let format = from_py(style)?;
+
+        Ok(self.inner.write().register_style(name, format)?)
+    }
+
+    #[pyo3(name = "_rename_sheet")]
+    pub fn rename_sheet(&self, old_name: &str, new_name: &str) -> PyResult<()> {
+        Ok(self.inner.write().rename_sheet(old_name, new_name)?)
+    }
+
+    #[pyo3(name = "_define_name", signature = (name, reference, scope=None))]
+    pub fn define_name(&self, name: &str, reference: &str, scope: Option<&str>) -> PyResult<()> {
+        Ok(self.inner.write().define_name(name, reference, scope)?)
+    }
+
+    #[pyo3(name = "_build_toc", signature = (options=None))]
+    pub fn build_toc(&self, options: Option<&Bound<'_, PyAny>>) -> PyResult<WrapperSheet> {
+        let options = extract_toc_options(options)?;
+        let sheet = self.inner.write().build_toc(&options)?;
+
+        Ok(WrapperSheet(sheet))
+    }
+
+    #[pyo3(name = "_protect", signature = (password=None, lock_structure=true, lock_windows=false))]
+    pub fn protect(
+        &self,
+        password: Option<&str>,
+        lock_structure: bool,
+        lock_windows: bool,
+    ) -> PyResult<()> {
+        let mut protection = WorkbookProtection {
+            lock_structure,
+            lock_windows,
+            ..Default::default()
+        };
+        if let Some(password) = password {
+            protection.set_password(password);
+        }
+
+        Ok(self.inner.write().set_protection(Some(protection))?)
+    }
+
+    #[pyo3(name = "_unprotect")]
+    pub fn unprotect(&self) -> PyResult<()> {
+        Ok(self.inner.write().set_protection(None)?)
+    }
+
+    #[pyo3(name = "_insert_rows")]
+    pub fn insert_rows(&self, sheet_name: &str, idx: u32, amount: u32) -> PyResult<()> {
+        Ok(self.inner.write().insert_rows(sheet_name, idx, amount)?)
+    }
+
+    #[pyo3(name = "_insert_cols")]
+    pub fn insert_cols(&self, sheet_name: &str, idx: u16, amount: u16) -> PyResult<()> {
+        Ok(self.inner.write().insert_cols(sheet_name, idx, amount)?)
+    }
+
+    #[pyo3(name = "_delete_rows")]
+    pub fn delete_rows(&self, sheet_name: &str, idx: u32, amount: u32) -> PyResult<()> {
+        Ok(self.inner.write().delete_rows(sheet_name, idx, amount)?)
+    }
+
+    #[pyo3(name = "_delete_cols")]
+    pub fn delete_cols(&self, sheet_name: &str, idx: u16, amount: u16) -> PyResult<()> {
+        Ok(self.inner.write().delete_cols(sheet_name, idx, amount)?)
+    }
+
+    pub fn calculate(&self, py: Python<'_>) -> PyResult<()> {
+        py.detach(|| self.inner.read().calculate());
+
+        Ok(())
+    }
+
     pub fn to_json(&self) -> PyResult<String> {
         Ok(self.inner.read().to_json()?)
     }
 
+    pub fn save_xlsx(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        py.detach(|| Ok(self.inner.read().save_xlsx(path)?))
+    }
+
     pub fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let s = self.inner.read().to_json()?;
         let py_module_json = py.import("json")?;
@@ -2,8 +2,11 @@ use std::sync::Arc;
 
 use core_rs::{
     structs::{
+        cells::SearchOrder,
         comment::Comment,
+        conditional_format::ConditionalFormat,
         coordinate::Coordinate,
+        data_validation::DataValidation,
         range::{MergedRange, Range},
         sheet::Sheet,
     },
@@ -12,7 +15,15 @@ use core_rs::{
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 
-use super::{cell::WrapperCell, comment::WrapperComment};
+use super::{
+    cell::WrapperCell,
+    comment::WrapperComment,
+    coordinate::{extract_col_span, extract_coordinate, extract_range_args, extract_row_span},
+    data_validation::extract_error_style,
+    json::{from_py, to_py},
+    protection::extract_sheet_protection,
+    style::extract_style,
+};
 
 #[pyclass(from_py_object)]
 #[pyo3(module = "report_core", name = "Sheet")]
@@ -98,16 +109,163 @@ impl WrapperSheet {
         Ok(merged_cells)
     }
 
+    #[pyo3(signature = (start_row, end_row=None, start_col=None, end_col=None, clear=false))]
     pub fn add_merge_cells(
         &mut self,
-        start_row: u32,
-        end_row: u32,
-        start_col: u16,
-        end_col: u16,
+        start_row: &Bound<'_, PyAny>,
+        end_row: Option<u32>,
+        start_col: Option<u16>,
+        end_col: Option<u16>,
+        clear: bool,
+    ) -> PyResult<()> {
+        let range = extract_range_args(start_row, end_row, start_col, end_col)?;
+
+        let mut slf = self.0.write();
+        if clear {
+            Ok(slf.add_merge_range_clear(range)?)
+        } else {
+            Ok(slf.add_merge_range(range)?)
+        }
+    }
+
+    #[pyo3(signature = (start_row, end_row=None, start_col=None, end_col=None))]
+    pub fn remove_merge_cells(
+        &mut self,
+        start_row: &Bound<'_, PyAny>,
+        end_row: Option<u32>,
+        start_col: Option<u16>,
+        end_col: Option<u16>,
+    ) -> PyResult<bool> {
+        let range = extract_range_args(start_row, end_row, start_col, end_col)?;
+
+        Ok(self.0.write().remove_merge_range(&range))
+    }
+
+    pub fn unmerge(&mut self, row: u32, col: u16) -> PyResult<Option<MergedRange>> {
+        Ok(self
+            .0
+            .write()
+            .unmerge(&Coordinate::new(row, col))
+            .map(|range| range.into()))
+    }
+
+    #[pyo3(signature = (ranges, rule, style=None, stop_if_true=false, priority=None))]
+    pub fn add_conditional_format(
+        &mut self,
+        ranges: Vec<MergedRange>,
+        rule: &Bound<'_, PyAny>,
+        style: Option<&Bound<'_, PyAny>>,
+        stop_if_true: bool,
+        priority: Option<u32>,
+    ) -> PyResult<()> {
+        let mut cf = ConditionalFormat::new(
+            ranges.into_iter().map(Range::from).collect(),
+            from_py(rule)?,
+        );
+        cf.style = style.map(extract_style).transpose()?;
+        cf.stop_if_true = stop_if_true;
+        cf.priority = priority;
+
+        Ok(self.0.write().add_conditional_format(cf)?)
+    }
+
+    #[pyo3(signature = (
+        ranges,
+        rule,
+        allow_blank=true,
+        input_title=None,
+        input_message=None,
+        error_title=None,
+        error_message=None,
+        error_style="stop",
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_data_validation(
+        &mut self,
+        ranges: Vec<MergedRange>,
+        rule: &Bound<'_, PyAny>,
+        allow_blank: bool,
+        input_title: Option<String>,
+        input_message: Option<String>,
+        error_title: Option<String>,
+        error_message: Option<String>,
+        error_style: &str,
     ) -> PyResult<()> {
-        let range = Range::new(start_row, end_row, start_col, end_col);
+        let mut dv = DataValidation::new(
+            ranges.into_iter().map(Range::from).collect(),
+            from_py(rule)?,
+        );
+        dv.allow_blank = allow_blank;
+        dv.input_title = input_title;
+        dv.input_message = input_message;
+        dv.error_title = error_title;
+        dv.error_message = error_message;
+        dv.error_style = extract_error_style(error_style)?;
 
-        self.0.write().add_merge_range(range);
+        Ok(self.0.write().add_data_validation(dv)?)
+    }
+
+    #[getter]
+    pub fn get_sheet_view(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, self.0.read().get_sheet_view())
+    }
+
+    #[setter]
+    pub fn set_sheet_view(&self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let view = from_py(value)?;
+
+        Ok(self.0.write().set_sheet_view(view)?)
+    }
+
+    #[pyo3(signature = (rows, cols=0))]
+    pub fn freeze_panes(&self, rows: u32, cols: u16) -> PyResult<()> {
+        Ok(self.0.write().set_freeze_panes(rows, cols)?)
+    }
+
+    #[getter]
+    pub fn get_page_setup(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, self.0.read().get_page_setup())
+    }
+
+    #[setter]
+    pub fn set_page_setup(&self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let setup = from_py(value)?;
+
+        Ok(self.0.write().set_page_setup(setup)?)
+    }
+
+    #[pyo3(signature = (password=None, options=None))]
+    pub fn protect(
+        &self,
+        password: Option<&str>,
+        options: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        let protection = extract_sheet_protection(password, options)?;
+
+        Ok(self.0.write().set_protection(Some(protection))?)
+    }
+
+    pub fn unprotect(&self) -> PyResult<()> {
+        Ok(self.0.write().set_protection(None)?)
+    }
+
+    #[getter]
+    pub fn get_protection(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        self.0
+            .read()
+            .get_protection()
+            .map(|protection| to_py(py, protection))
+            .transpose()
+    }
+
+    #[getter]
+    pub fn get_auto_filter(&self) -> PyResult<Option<MergedRange>> {
+        Ok(self.0.read().get_auto_filter().map(|range| range.into()))
+    }
+
+    #[setter]
+    pub fn set_auto_filter(&self, range: Option<MergedRange>) -> PyResult<()> {
+        self.0.write().set_auto_filter(range.map(Range::from));
 
         Ok(())
     }
@@ -145,11 +303,38 @@ impl WrapperSheet {
         Ok(WrapperCell(Arc::clone(cell)))
     }
 
-    pub fn get_value_cell(&self, row: u32, col: u16) -> PyResult<String> {
+    #[pyo3(signature = (row, col, merged=false))]
+    pub fn get_value_cell(&self, row: u32, col: u16, merged: bool) -> PyResult<String> {
         let slf = self.0.read();
 
         let coord = Coordinate::from((row, col));
-        Ok(slf.get_cell_value(coord))
+        if merged {
+            Ok(slf.get_cell_value_merged(coord))
+        } else {
+            Ok(slf.get_cell_value(coord))
+        }
+    }
+
+    pub fn merged_range_at(&self, row: u32, col: u16) -> PyResult<Option<MergedRange>> {
+        let slf = self.0.read();
+
+        Ok(slf
+            .merged_range_at(&Coordinate::new(row, col))
+            .map(|range| range.into()))
+    }
+
+    pub fn insert_cols(&self, idx: u16, amount: u16) -> PyResult<()> {
+        let mut slf = self.0.write();
+        slf.insert_cols(idx, amount);
+
+        Ok(())
+    }
+
+    pub fn insert_rows(&self, idx: u32, amount: u32) -> PyResult<()> {
+        let mut slf = self.0.write();
+        slf.insert_rows(idx, amount);
+
+        Ok(())
     }
 
     pub fn delete_cols(&self, idx: u16, amount: u16) -> PyResult<()> {
@@ -194,6 +379,56 @@ impl WrapperSheet {
         Ok(())
     }
 
+    #[pyo3(signature = (start, end, level=1))]
+    pub fn group_rows(&self, start: u32, end: u32, level: u32) -> PyResult<()> {
+        Ok(self.0.write().group_rows(start, end, level)?)
+    }
+
+    #[pyo3(signature = (start, end, level=1))]
+    pub fn group_cols(&self, start: u16, end: u16, level: u32) -> PyResult<()> {
+        Ok(self.0.write().group_cols(start, end, level)?)
+    }
+
+    pub fn collapse_rows(&self, start: u32, end: u32) -> PyResult<()> {
+        Ok(self.0.write().collapse_rows(start, end)?)
+    }
+
+    pub fn expand_rows(&self, start: u32, end: u32) -> PyResult<()> {
+        Ok(self.0.write().expand_rows(start, end)?)
+    }
+
+    pub fn collapse_cols(&self, start: u16, end: u16) -> PyResult<()> {
+        Ok(self.0.write().collapse_cols(start, end)?)
+    }
+
+    pub fn expand_cols(&self, start: u16, end: u16) -> PyResult<()> {
+        Ok(self.0.write().expand_cols(start, end)?)
+    }
+
+    #[getter]
+    pub fn get_summary_below(&self) -> PyResult<bool> {
+        Ok(self.0.read().get_summary_below())
+    }
+
+    #[setter]
+    pub fn set_summary_below(&self, val: bool) -> PyResult<()> {
+        self.0.write().set_summary_below(val);
+
+        Ok(())
+    }
+
+    #[getter]
+    pub fn get_summary_right(&self) -> PyResult<bool> {
+        Ok(self.0.read().get_summary_right())
+    }
+
+    #[setter]
+    pub fn set_summary_right(&self, val: bool) -> PyResult<()> {
+        self.0.write().set_summary_right(val);
+
+        Ok(())
+    }
+
     #[pyo3(signature = (start_row=None, end_row=None, start_col=None, end_col=None))]
     pub fn get_cells_by_range(
         &self,
@@ -240,27 +475,70 @@ impl WrapperSheet {
         Ok(*val)
     }
 
-    pub fn find_cell_by_regex(&self, regex: &str) -> PyResult<Option<WrapperCell>> {
+    pub fn get_level_by_row(&self, row_num: u32) -> PyResult<u32> {
         let slf = self.0.read();
 
-        Ok(slf
-            .find_cell_by_regex(regex)?
-            .map(|c| WrapperCell(Arc::clone(c))))
+        let val = slf.get_level_by_row(row_num);
+        Ok(*val)
     }
 
-    pub fn find_cell_by_str(&self, value: &str) -> PyResult<Option<WrapperCell>> {
+    pub fn get_level_by_column(&self, col_num: u16) -> PyResult<u32> {
         let slf = self.0.read();
 
-        Ok(slf
-            .find_cell_by_str(value)?
-            .map(|c| WrapperCell(Arc::clone(c))))
+        let val = slf.get_level_by_column(col_num);
+        Ok(*val)
+    }
+
+    #[pyo3(signature = (regex, by_cols=false, merged=false))]
+    pub fn find_cell_by_regex(
+        &self,
+        regex: &str,
+        by_cols: bool,
+        merged: bool,
+    ) -> PyResult<Option<WrapperCell>> {
+        let order = if by_cols {
+            SearchOrder::Cols
+        } else {
+            SearchOrder::Rows
+        };
+        let slf = self.0.read();
+        let cell = if merged {
+            slf.find_cell_by_regex_merged(regex, order)?
+        } else {
+            slf.find_cell_by_regex(regex, order)?
+        };
+
+        Ok(cell.map(|c| WrapperCell(Arc::clone(c))))
+    }
+
+    #[pyo3(signature = (value, by_cols=false, merged=false))]
+    pub fn find_cell_by_str(
+        &self,
+        value: &str,
+        by_cols: bool,
+        merged: bool,
+    ) -> PyResult<Option<WrapperCell>> {
+        let order = if by_cols {
+            SearchOrder::Cols
+        } else {
+            SearchOrder::Rows
+        };
+        let slf = self.0.read();
+        let cell = if merged {
+            slf.find_cell_by_str_merged(value, order)?
+        } else {
+            slf.find_cell_by_str(value, order)?
+        };
+
+        Ok(cell.map(|c| WrapperCell(Arc::clone(c))))
     }
 
     pub fn find_cell_by_letter(&self, letter: &str) -> PyResult<Option<WrapperCell>> {
+        let Coordinate { row, column } = extract_coordinate(letter)?;
         let slf = self.0.read();
 
         Ok(slf
-            .find_cell_by_letter(letter)?
+            .find_cell_by_coords(row, column)?
             .map(|c| WrapperCell(Arc::clone(c))))
     }
 
@@ -352,11 +630,13 @@ impl WrapperSheet {
         Ok(wrapped_cells)
     }
 
+    #[pyo3(signature = (start_row, end_row=None))]
     pub fn find_cells_range_rows(
         &self,
-        start_row: u32,
-        end_row: u32,
+        start_row: &Bound<'_, PyAny>,
+        end_row: Option<u32>,
     ) -> PyResult<Vec<WrapperCell>> {
+        let (start_row, end_row) = extract_row_span(start_row, end_row)?;
         let slf = self.0.read();
 
         let cells = slf.find_cells_range_rows(start_row, end_row)?;
@@ -368,11 +648,13 @@ impl WrapperSheet {
         Ok(wrapped_cells)
     }
 
+    #[pyo3(signature = (start_col, end_col=None))]
     pub fn find_cells_range_cols(
         &self,
-        start_col: u16,
-        end_col: u16,
+        start_col: &Bound<'_, PyAny>,
+        end_col: Option<u16>,
     ) -> PyResult<Vec<WrapperCell>> {
+        let (start_col, end_col) = extract_col_span(start_col, end_col)?;
         let slf = self.0.read();
 
         let cells = slf.find_cells_range_cols(start_col, end_col)?;
@@ -384,9 +666,19 @@ impl WrapperSheet {
         Ok(wrapped_cells)
     }
 
-    pub fn find_value_by_coords(&self, row: u32, col: u16) -> PyResult<Option<String>> {
+    #[pyo3(signature = (row, col, merged=false))]
+    pub fn find_value_by_coords(
+        &self,
+        row: u32,
+        col: u16,
+        merged: bool,
+    ) -> PyResult<Option<String>> {
         let slf = self.0.read();
 
-        Ok(slf.find_value_by_coords(row, col)?)
+        if merged {
+            Ok(slf.find_value_by_coords_merged(row, col)?)
+        } else {
+            Ok(slf.find_value_by_coords(row, col)?)
+        }
     }
 }
@@ -0,0 +1,13 @@
+use core_rs::structs::style::Style;
+use pyo3::{prelude::*, types::PyString};
+
+use super::json::from_py;
+
+/// Стиль ячейки из имени зарегистрированного стиля или словаря
+pub(crate) fn extract_style(value: &Bound<'_, PyAny>) -> PyResult<Style> {
+    if let Ok(name) = value.cast::<PyString>() {
+        return Ok(Style::new(name.to_str()?));
+    }
+
+    Ok(Style::inline(from_py(value)?))
+}
@@ -0,0 +1,12 @@
+use core_rs::structs::toc::TocOptions;
+use pyo3::prelude::*;
+
+use super::json::from_py;
+
+/// Преобразование словаря Python в параметры оглавления
+pub(crate) fn extract_toc_options(value: Option<&Bound<'_, PyAny>>) -> PyResult<TocOptions> {
+    match value.filter(|v| !v.is_none()) {
+        Some(value) => from_py(value),
+        None => Ok(TocOptions::default()),
+    }
+}
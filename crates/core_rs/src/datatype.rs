@@ -3,6 +3,8 @@ use std::fmt::Display;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
+use crate::structs::rich_text::RichText;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(untagged)]
 pub enum CellRawValue {
@@ -10,8 +12,11 @@ pub enum CellRawValue {
     Empty,
 
     String(Box<str>),
-    Numeric(f64),
+    /// Форматированный текст, в JSON - объект `{"runs": [...]}`
+    RichText(RichText),
+    // Integer раньше Numeric, чтобы целые числа из JSON не превращались в f64
     Integer(i32),
+    Numeric(f64),
     Bool(bool),
     Datetime(NaiveDateTime),
 }
@@ -21,7 +26,7 @@ impl CellRawValue {
     #[inline]
     pub(crate) fn get_date_type(&self) -> &str {
         match &self {
-            Self::String(_) => "s",
+            Self::String(_) | Self::RichText(_) => "s",
             Self::Integer(_) => "n",
             Self::Numeric(_) => "n",
             Self::Bool(_) => "b",
@@ -35,6 +40,7 @@ impl CellRawValue {
     pub(crate) fn _get_value_str(&self) -> String {
         match &self {
             Self::String(_)
+            | Self::RichText(_)
             | Self::Numeric(_)
             | Self::Integer(_)
             | Self::Bool(_)
@@ -49,6 +55,7 @@ impl Display for CellRawValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::String(v) => write!(f, "{v}"),
+            Self::RichText(v) => write!(f, "{v}"),
             Self::Numeric(v) => write!(f, "{v}"),
             Self::Integer(v) => write!(f, "{v}"),
             Self::Bool(v) => write!(f, "{v}"),
@@ -58,9 +65,9 @@ impl Display for CellRawValue {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CellValue {
-    #[serde(rename = "value")]
+    #[serde(rename = "value", default)]
     pub raw_value: CellRawValue,
 }
 
@@ -94,6 +101,19 @@ impl CellValue {
         self
     }
 
+    /// Метод для установки форматированного текста.
+    /// Текст без символов очищает значение.
+    #[inline]
+    pub fn set_value_rich_text(&mut self, value: RichText) -> &mut Self {
+        self.raw_value = if value.is_empty() {
+            CellRawValue::Empty
+        } else {
+            CellRawValue::RichText(value)
+        };
+
+        self
+    }
+
     #[inline]
     pub fn set_value_number(&mut self, value: f64) -> &mut Self {
         self.raw_value = CellRawValue::Numeric(value);
@@ -142,6 +162,11 @@ impl CellValue {
         matches!(self.raw_value, CellRawValue::Datetime(_))
     }
 
+    #[inline]
+    pub fn is_rich_text(&self) -> bool {
+        matches!(self.raw_value, CellRawValue::RichText(_))
+    }
+
     #[inline]
     pub fn is_empty(&self) -> bool {
         matches!(self.raw_value, CellRawValue::Empty)
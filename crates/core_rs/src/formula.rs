@@ -0,0 +1,77 @@
+pub(crate) mod eval;
+pub(crate) mod functions;
+pub(crate) mod lexer;
+pub(crate) mod parser;
+pub(crate) mod shift;
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::bail;
+
+/// Ошибки вычисления формул в представлении Excel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormulaError {
+    Div0,
+    NA,
+    Name,
+    Null,
+    Num,
+    Ref,
+    Value,
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Div0 => "#DIV/0!",
+            Self::NA => "#N/A",
+            Self::Name => "#NAME?",
+            Self::Null => "#NULL!",
+            Self::Num => "#NUM!",
+            Self::Ref => "#REF!",
+            Self::Value => "#VALUE!",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for FormulaError {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "#DIV/0!" => Ok(Self::Div0),
+            "#N/A" => Ok(Self::NA),
+            "#NAME?" => Ok(Self::Name),
+            "#NULL!" => Ok(Self::Null),
+            "#NUM!" => Ok(Self::Num),
+            "#REF!" => Ok(Self::Ref),
+            "#VALUE!" => Ok(Self::Value),
+            _ => bail!("Unknown formula error: {s}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_literals() {
+        for err in [
+            FormulaError::Div0,
+            FormulaError::NA,
+            FormulaError::Name,
+            FormulaError::Null,
+            FormulaError::Num,
+            FormulaError::Ref,
+            FormulaError::Value,
+        ] {
+            assert_eq!(err.to_string().parse::<FormulaError>().unwrap(), err);
+        }
+
+        assert_eq!("#n/a".parse::<FormulaError>().unwrap(), FormulaError::NA);
+        assert!("#ERR".parse::<FormulaError>().is_err());
+    }
+}
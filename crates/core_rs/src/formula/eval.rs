@@ -0,0 +1,704 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use ahash::{HashMap, HashSet};
+use parking_lot::RwLock;
+
+use super::{
+    FormulaError,
+    functions::call_function,
+    lexer::{Op, RefKind, Reference},
+    parser::{Expr, parse},
+};
+use crate::{
+    MAX_COL, MAX_ROW,
+    datatype::CellRawValue,
+    structs::{cell::Cell, defined_name::DefinedName, sheet::Sheet},
+    traits::{ReadableCell, ReadableSheet},
+    utils::datetime_to_excel_serial,
+};
+
+/// Значение, получаемое при вычислении формулы.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Empty,
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(FormulaError),
+    Array(Matrix),
+}
+
+/// Прямоугольный диапазон значений, построчно.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub values: Vec<Value>,
+}
+
+impl Matrix {
+    #[inline]
+    pub(crate) fn get(&self, row: usize, col: usize) -> &Value {
+        &self.values[row * self.cols + col]
+    }
+}
+
+impl From<&CellRawValue> for Value {
+    fn from(value: &CellRawValue) -> Self {
+        match value {
+            CellRawValue::Empty => Value::Empty,
+            CellRawValue::String(s) => match s.parse::<FormulaError>() {
+                Ok(e) => Value::Error(e),
+                Err(_) => Value::Text(s.to_string()),
+            },
+            CellRawValue::RichText(r) => Value::Text(r.get_text()),
+            CellRawValue::Numeric(n) => Value::Number(*n),
+            CellRawValue::Integer(i) => Value::Number(*i as f64),
+            CellRawValue::Bool(b) => Value::Bool(*b),
+            CellRawValue::Datetime(dt) => Value::Number(datetime_to_excel_serial(dt)),
+        }
+    }
+}
+
+impl From<Value> for CellRawValue {
+    fn from(value: Value) -> Self {
+        match value {
+            // Формула со ссылкой на пустую ячейку в Excel дает 0
+            Value::Empty => CellRawValue::Numeric(0.0),
+            Value::Number(n) if n.is_finite() => CellRawValue::Numeric(n),
+            Value::Number(_) => CellRawValue::String(FormulaError::Num.to_string().into()),
+            Value::Text(s) => CellRawValue::String(s.into_boxed_str()),
+            Value::Bool(b) => CellRawValue::Bool(b),
+            Value::Error(e) => CellRawValue::String(e.to_string().into()),
+            Value::Array(_) => CellRawValue::String(FormulaError::Value.to_string().into()),
+        }
+    }
+}
+
+/// Округление до 15 значащих цифр, как при отображении чисел в Excel
+pub(crate) fn round_significant(n: f64) -> f64 {
+    if !n.is_finite() || n == 0.0 {
+        return n;
+    }
+
+    format!("{n:.14e}").parse().unwrap_or(n)
+}
+
+/// Представление числа в виде текста, как в Excel в общем формате
+pub(crate) fn number_to_text(n: f64) -> String {
+    let n = round_significant(n);
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
+
+impl Value {
+    /// Приведение к числу
+    pub(crate) fn to_number(&self) -> Result<f64, FormulaError> {
+        match self {
+            Value::Empty => Ok(0.0),
+            Value::Number(n) => Ok(*n),
+            Value::Bool(b) => Ok(*b as u8 as f64),
+            Value::Text(s) => s.trim().parse::<f64>().map_err(|_| FormulaError::Value),
+            Value::Error(e) => Err(*e),
+            Value::Array(_) => Err(FormulaError::Value),
+        }
+    }
+
+    /// Приведение к тексту
+    pub(crate) fn to_text(&self) -> Result<String, FormulaError> {
+        match self {
+            Value::Empty => Ok(String::new()),
+            Value::Number(n) => Ok(number_to_text(*n)),
+            Value::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+            Value::Text(s) => Ok(s.clone()),
+            Value::Error(e) => Err(*e),
+            Value::Array(_) => Err(FormulaError::Value),
+        }
+    }
+
+    /// Приведение к логическому значению
+    pub(crate) fn to_bool(&self) -> Result<bool, FormulaError> {
+        match self {
+            Value::Empty => Ok(false),
+            Value::Number(n) => Ok(*n != 0.0),
+            Value::Bool(b) => Ok(*b),
+            Value::Text(s) => match s.to_uppercase().as_str() {
+                "TRUE" => Ok(true),
+                "FALSE" => Ok(false),
+                _ => Err(FormulaError::Value),
+            },
+            Value::Error(e) => Err(*e),
+            Value::Array(_) => Err(FormulaError::Value),
+        }
+    }
+
+    /// Значения аргумента: для диапазона - все ячейки, иначе само значение
+    pub(crate) fn flatten(&self) -> &[Value] {
+        match self {
+            Value::Array(m) => &m.values,
+            v => std::slice::from_ref(v),
+        }
+    }
+}
+
+/// Сравнение значений по правилам Excel: числа < текст < логические
+pub(crate) fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(v: &Value) -> u8 {
+        match v {
+            Value::Text(_) => 1,
+            Value::Bool(_) => 2,
+            _ => 0,
+        }
+    }
+
+    match (a, b) {
+        (Value::Empty, Value::Text(s)) => "".cmp(s.as_str()),
+        (Value::Text(s), Value::Empty) => s.as_str().cmp(""),
+        (Value::Empty, Value::Bool(v)) => false.cmp(v),
+        (Value::Bool(v), Value::Empty) => v.cmp(&false),
+        (Value::Text(x), Value::Text(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ if rank(a) != rank(b) => rank(a).cmp(&rank(b)),
+        _ => {
+            let x = a.to_number().unwrap_or_default();
+            let y = b.to_number().unwrap_or_default();
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+/// Предельная глубина рекурсивного вычисления зависимых формул.
+/// Более длинные цепочки вычисляются по частям, начиная с глубоких ячеек.
+const MAX_DEPTH: usize = 256;
+
+type CellKey = (usize, u32, u16);
+
+/// Вычислитель формул книги с кэшем результатов и защитой от циклических ссылок.
+pub(crate) struct Evaluator<'a> {
+    sheets: &'a [Arc<RwLock<Sheet>>],
+    names: Vec<String>,
+    /// Заполненная часть листов: (max_row, max_col)
+    dimensions: Vec<(u32, u16)>,
+    defined_names: &'a [DefinedName],
+    cache: HashMap<CellKey, Value>,
+    visiting: HashSet<CellKey>,
+    visiting_names: HashSet<usize>,
+    depth: usize,
+    /// Ячейка, на которой вычисление превысило `MAX_DEPTH`
+    deferred: Option<CellKey>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub(crate) fn new(sheets: &'a [Arc<RwLock<Sheet>>], defined_names: &'a [DefinedName]) -> Self {
+        Self {
+            sheets,
+            names: sheets
+                .iter()
+                .map(|s| s.read().get_name().to_lowercase())
+                .collect(),
+            dimensions: sheets
+                .iter()
+                .map(|s| {
+                    let guard = s.read();
+                    (guard.get_max_row().max(1), guard.get_max_column().max(1))
+                })
+                .collect(),
+            defined_names,
+            cache: HashMap::default(),
+            visiting: HashSet::default(),
+            visiting_names: HashSet::default(),
+            depth: 0,
+            deferred: None,
+        }
+    }
+
+    /// Индекс листа по имени (без учета регистра)
+    fn sheet_index(&self, name: &str) -> Option<usize> {
+        let name = name.to_lowercase();
+        self.names.iter().position(|n| *n == name)
+    }
+
+    /// Вычисление имени: сначала имя листа, затем имя книги.
+    /// Ссылки без имени листа относятся к листу области действия имени.
+    fn eval_name(&mut self, name: &str, current: usize) -> Value {
+        let scoped = self.defined_names.iter().position(|n| {
+            n.get_scope()
+                .is_some_and(|s| self.sheet_index(s) == Some(current))
+                && n.is(name, n.get_scope())
+        });
+        let idx = scoped.or_else(|| self.defined_names.iter().position(|n| n.is(name, None)));
+
+        let Some(idx) = idx else {
+            return Value::Error(FormulaError::Name);
+        };
+
+        // Циклическая ссылка между именами
+        if !self.visiting_names.insert(idx) {
+            return Value::Error(FormulaError::Ref);
+        }
+
+        let defined_name = &self.defined_names[idx];
+        let sheet = defined_name
+            .get_scope()
+            .and_then(|s| self.sheet_index(s))
+            .unwrap_or(current);
+
+        let value = match parse(defined_name.get_reference()) {
+            Ok(expr) => self.eval(&expr, sheet),
+            Err(_) => Value::Error(FormulaError::Name),
+        };
+
+        self.visiting_names.remove(&idx);
+
+        value
+    }
+
+    fn find_cell(&self, sheet: usize, row: u32, col: u16) -> Option<Arc<RwLock<Cell>>> {
+        let guard = self.sheets[sheet].read();
+        guard
+            .find_cell_by_coords(row, col)
+            .ok()
+            .flatten()
+            .map(Arc::clone)
+    }
+
+    /// Значение ячейки; формулы вычисляются по мере необходимости
+    pub(crate) fn cell_value(&mut self, sheet: usize, row: u32, col: u16) -> Value {
+        if let Some(value) = self.cache.get(&(sheet, row, col)) {
+            return value.clone();
+        }
+
+        let Some(cell) = self.find_cell(sheet, row, col) else {
+            return Value::Empty;
+        };
+
+        let (formula, raw_value) = {
+            let guard = cell.read();
+            let formula = guard.get_formula().filter(|_| guard.is_formula());
+            (formula, guard.get_raw_value().clone())
+        };
+
+        match formula {
+            Some(formula) => self.formula_value((sheet, row, col), &formula),
+            None => Value::from(&raw_value),
+        }
+    }
+
+    /// Вычисление формулы ячейки.
+    /// Если цепочка зависимостей глубже `MAX_DEPTH`, сначала вычисляются
+    /// отложенные глубокие ячейки, затем вычисление повторяется.
+    pub(crate) fn eval_formula(
+        &mut self,
+        sheet: usize,
+        row: u32,
+        col: u16,
+        formula: &str,
+    ) -> Value {
+        let root = (sheet, row, col);
+        let mut pending: Vec<CellKey> = Vec::new();
+
+        loop {
+            let value = match pending.last() {
+                Some(&(sheet, row, col)) => self.cell_value(sheet, row, col),
+                None => self.formula_value(root, formula),
+            };
+
+            match self.deferred.take() {
+                // Отложенная ячейка уже ждет вычисления - циклическая ссылка
+                Some(key) if key == root || pending.contains(&key) => {
+                    self.cache.insert(key, Value::Error(FormulaError::Ref));
+                }
+                Some(key) => pending.push(key),
+                None => {
+                    if pending.pop().is_none() {
+                        return value;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Рекурсивное вычисление формулы ячейки с ограничением глубины
+    fn formula_value(&mut self, key: CellKey, formula: &str) -> Value {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+
+        // Вычисление прервано, результат будет получен повторно
+        if self.deferred.is_some() {
+            return Value::Error(FormulaError::Ref);
+        }
+
+        // Циклическая ссылка
+        if self.visiting.contains(&key) {
+            return Value::Error(FormulaError::Ref);
+        }
+
+        if self.depth >= MAX_DEPTH {
+            self.deferred = Some(key);
+            return Value::Error(FormulaError::Ref);
+        }
+
+        self.visiting.insert(key);
+        self.depth += 1;
+
+        let value = match parse(formula) {
+            Ok(expr) => match self.eval(&expr, key.0) {
+                Value::Array(m) if m.values.len() == 1 => m.values[0].clone(),
+                Value::Array(_) => Value::Error(FormulaError::Value),
+                value => value,
+            },
+            Err(_) => Value::Error(FormulaError::Name),
+        };
+
+        self.depth -= 1;
+        self.visiting.remove(&key);
+
+        // Результат прерванного вычисления не кэшируем
+        if self.deferred.is_none() {
+            self.cache.insert(key, value.clone());
+        }
+
+        value
+    }
+
+    /// Значения диапазона ячеек листа.
+    /// Диапазон ограничивается заполненной частью листа.
+    fn range_values(
+        &mut self,
+        sheet: usize,
+        (start_row, end_row): (u32, u32),
+        (start_col, end_col): (u16, u16),
+    ) -> Value {
+        let (max_row, max_col) = self.dimensions[sheet];
+
+        let (start_row, end_row) = (start_row.min(end_row), start_row.max(end_row));
+        let (start_col, end_col) = (start_col.min(end_col), start_col.max(end_col));
+        let end_row = end_row.min(max_row).max(start_row);
+        let end_col = end_col.min(max_col).max(start_col);
+
+        let rows = (end_row - start_row + 1) as usize;
+        let cols = (end_col - start_col + 1) as usize;
+
+        let mut values = Vec::with_capacity(rows * cols);
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                values.push(self.cell_value(sheet, row, col));
+            }
+        }
+
+        Value::Array(Matrix { rows, cols, values })
+    }
+
+    fn eval_reference(&mut self, reference: &Reference, current: usize) -> Value {
+        let sheet = match &reference.sheet {
+            Some(name) => match self.sheet_index(name) {
+                Some(idx) => idx,
+                None => return Value::Error(FormulaError::Ref),
+            },
+            None => current,
+        };
+
+        match &reference.kind {
+            RefKind::Cell(addr) => self.cell_value(sheet, addr.row, addr.col),
+            RefKind::Range(start, end) => {
+                self.range_values(sheet, (start.row, end.row), (start.col, end.col))
+            }
+            RefKind::Cols { start, end, .. } => {
+                self.range_values(sheet, (1, MAX_ROW), (*start, *end))
+            }
+            RefKind::Rows { start, end, .. } => {
+                self.range_values(sheet, (*start, *end), (1, MAX_COL))
+            }
+        }
+    }
+
+    /// Вычисление выражения в контексте листа
+    pub(crate) fn eval(&mut self, expr: &Expr, sheet: usize) -> Value {
+        match expr {
+            Expr::Number(n) => Value::Number(*n),
+            Expr::Text(s) => Value::Text(s.clone()),
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Error(e) => Value::Error(*e),
+            Expr::Missing => Value::Empty,
+            Expr::Name(name) => self.eval_name(name, sheet),
+            Expr::Reference(r) => self.eval_reference(r, sheet),
+            Expr::Neg(e) => match self.eval_scalar(e, sheet).to_number() {
+                Ok(n) => Value::Number(-n),
+                Err(e) => Value::Error(e),
+            },
+            Expr::Percent(e) => match self.eval_scalar(e, sheet).to_number() {
+                Ok(n) => Value::Number(n / 100.0),
+                Err(e) => Value::Error(e),
+            },
+            Expr::Binary(op, left, right) => {
+                let left = self.eval_scalar(left, sheet);
+                let right = self.eval_scalar(right, sheet);
+                binary(*op, &left, &right)
+            }
+            Expr::Call(name, args) => call_function(self, name, args, sheet),
+        }
+    }
+
+    /// Вычисление выражения, где ожидается одно значение
+    pub(crate) fn eval_scalar(&mut self, expr: &Expr, sheet: usize) -> Value {
+        match self.eval(expr, sheet) {
+            Value::Array(m) if m.values.len() == 1 => m.values[0].clone(),
+            Value::Array(_) => Value::Error(FormulaError::Value),
+            value => value,
+        }
+    }
+}
+
+/// Применение бинарного оператора
+fn binary(op: Op, left: &Value, right: &Value) -> Value {
+    let result = match op {
+        Op::Concat => left
+            .to_text()
+            .and_then(|l| right.to_text().map(|r| Value::Text(l + &r))),
+        Op::Eq | Op::Ne | Op::Lt | Op::Gt | Op::Le | Op::Ge => {
+            if let Value::Error(e) = left {
+                return Value::Error(*e);
+            }
+            if let Value::Error(e) = right {
+                return Value::Error(*e);
+            }
+
+            let ord = compare_values(left, right);
+            Ok(Value::Bool(match op {
+                Op::Eq => ord == Ordering::Equal,
+                Op::Ne => ord != Ordering::Equal,
+                Op::Lt => ord == Ordering::Less,
+                Op::Gt => ord == Ordering::Greater,
+                Op::Le => ord != Ordering::Greater,
+                _ => ord != Ordering::Less,
+            }))
+        }
+        _ => left.to_number().and_then(|l| {
+            let r = right.to_number()?;
+            let n = match op {
+                Op::Add => l + r,
+                Op::Sub => l - r,
+                Op::Mul => l * r,
+                Op::Div if r == 0.0 => return Err(FormulaError::Div0),
+                Op::Div => l / r,
+                Op::Pow if l == 0.0 && r < 0.0 => return Err(FormulaError::Div0),
+                _ => l.powf(r),
+            };
+
+            if n.is_finite() {
+                Ok(Value::Number(n))
+            } else {
+                Err(FormulaError::Num)
+            }
+        }),
+    };
+
+    result.unwrap_or_else(Value::Error)
+}
+
+/// Пересчет всех формул книги с сохранением результатов в ячейках
+pub(crate) fn calculate_sheets(sheets: &[Arc<RwLock<Sheet>>], defined_names: &[DefinedName]) {
+    let mut evaluator = Evaluator::new(sheets, defined_names);
+    let mut results = Vec::new();
+
+    for (idx, sheet) in sheets.iter().enumerate() {
+        let formulas: Vec<_> = {
+            let guard = sheet.read();
+            guard
+                .get_cell_collection_sorted()
+                .into_iter()
+                .filter_map(|cell| {
+                    let c = cell.read();
+                    c.get_formula()
+                        .filter(|_| c.is_formula())
+                        .map(|f| (Arc::clone(cell), c.get_coordinate().clone(), f))
+                })
+                .collect()
+        };
+
+        for (cell, coord, formula) in formulas {
+            let value = evaluator.eval_formula(idx, coord.row, coord.column, &formula);
+            results.push((cell, value));
+        }
+    }
+
+    for (cell, value) in results {
+        cell.write().set_formula_result(value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::WriteableSheet;
+    use crate::{structs::coordinate::Coordinate, traits::WriteableCell};
+
+    fn sheets() -> Vec<Arc<RwLock<Sheet>>> {
+        let mut data = Sheet::new("Данные", "visible");
+        for row in 1..=3 {
+            data.cell(Coordinate::new(row, 1), Some(&(row * 10).to_string()));
+        }
+
+        let mut calc = Sheet::new("Итог", "visible");
+        calc.cell(Coordinate::new(1, 1), None)
+            .write()
+            .set_formula("=SUM(Данные!A1:A3)");
+        calc.cell(Coordinate::new(1, 2), None)
+            .write()
+            .set_formula("A1*2");
+        calc.cell(Coordinate::new(1, 3), None)
+            .write()
+            .set_formula("=C1+1");
+        calc.cell(Coordinate::new(1, 4), None)
+            .write()
+            .set_formula("=1/0");
+
+        vec![Arc::new(RwLock::new(data)), Arc::new(RwLock::new(calc))]
+    }
+
+    fn eval(formula: &str) -> Value {
+        let sheets = sheets();
+        let mut evaluator = Evaluator::new(&sheets, &[]);
+
+        evaluator.eval_formula(0, 100, 100, formula)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval("=1+2*3"), Value::Number(7.0));
+        assert_eq!(eval("=-2^2"), Value::Number(4.0));
+        assert_eq!(eval("=50%"), Value::Number(0.5));
+        assert_eq!(eval("=\"2\"+A1"), Value::Number(12.0));
+        assert_eq!(eval("=A1&\"р.\""), Value::Text("10р.".to_string()));
+        assert_eq!(eval("=0.1+0.2&\"\""), Value::Text("0.3".to_string()));
+        assert_eq!(eval("=\"a\"+1"), Value::Error(FormulaError::Value));
+        assert_eq!(eval("=A1/Z9"), Value::Error(FormulaError::Div0));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(eval("=A1>5"), Value::Bool(true));
+        assert_eq!(eval("=\"abc\"=\"ABC\""), Value::Bool(true));
+        assert_eq!(eval("=Z9=0"), Value::Bool(true));
+        assert_eq!(eval("=1<\"a\""), Value::Bool(true));
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(eval("=Итог!B1"), Value::Number(120.0));
+        assert_eq!(eval("='Нет листа'!A1"), Value::Error(FormulaError::Ref));
+        assert_eq!(eval("=A1:A3"), Value::Error(FormulaError::Value));
+        assert_eq!(eval("=SUM(A:A)"), Value::Number(60.0));
+        assert_eq!(eval("=Итог!C1"), Value::Error(FormulaError::Ref));
+        assert_eq!(eval("=UnknownName"), Value::Error(FormulaError::Name));
+        assert_eq!(eval("=1+"), Value::Error(FormulaError::Name));
+    }
+
+    #[test]
+    fn defined_names() {
+        let sheets = sheets();
+        let names = [
+            DefinedName::new("Доходы", "Данные!$A$1:$A$3", None).unwrap(),
+            DefinedName::new("Ставка", "0.5", None).unwrap(),
+            DefinedName::new("Ставка", "2", Some("Итог")).unwrap(),
+            DefinedName::new("Первая", "$A$1", Some("Данные")).unwrap(),
+            DefinedName::new("Цикл", "Цикл+1", None).unwrap(),
+        ];
+        let mut evaluator = Evaluator::new(&sheets, &names);
+
+        assert_eq!(
+            evaluator.eval_formula(0, 100, 100, "=SUM(Доходы)*ставка"),
+            Value::Number(30.0)
+        );
+        assert_eq!(
+            evaluator.eval_formula(1, 100, 100, "=SUM(Доходы)*Ставка"),
+            Value::Number(120.0)
+        );
+        assert_eq!(
+            evaluator.eval_formula(1, 100, 101, "=Первая"),
+            Value::Error(FormulaError::Name)
+        );
+        assert_eq!(
+            evaluator.eval_formula(0, 100, 101, "=Первая"),
+            Value::Number(10.0)
+        );
+        assert_eq!(
+            evaluator.eval_formula(0, 100, 102, "=Цикл"),
+            Value::Error(FormulaError::Ref)
+        );
+    }
+
+    #[test]
+    fn long_chain() {
+        // A1 = A2 + 1, ..., A10000 = 1
+        let mut sheet = Sheet::new("Цепочка", "visible");
+        for row in 1..10_000 {
+            sheet
+                .cell(Coordinate::new(row, 1), None)
+                .write()
+                .set_formula(&format!("=A{}+1", row + 1));
+        }
+        sheet
+            .cell(Coordinate::new(10_000, 1), None)
+            .write()
+            .set_formula("=1");
+
+        // B1 = 1, B2 = B1 + 1, ...
+        sheet.cell(Coordinate::new(1, 2), Some("1"));
+        for row in 2..=10_000 {
+            sheet
+                .cell(Coordinate::new(row, 2), None)
+                .write()
+                .set_formula(&format!("=B{}+1", row - 1));
+        }
+
+        // Цикл длиннее предельной глубины
+        for row in 1..=600 {
+            sheet
+                .cell(Coordinate::new(row, 3), None)
+                .write()
+                .set_formula(&format!("=C{}", row % 600 + 1));
+        }
+
+        let sheets = vec![Arc::new(RwLock::new(sheet))];
+        let mut evaluator = Evaluator::new(&sheets, &[]);
+        assert_eq!(
+            evaluator.eval_formula(0, 1, 4, "=B10000"),
+            Value::Number(10_000.0)
+        );
+
+        calculate_sheets(&sheets, &[]);
+
+        let sheet = sheets[0].read();
+        assert_eq!(sheet.get_cell_value((1, 1)), "10000");
+        assert_eq!(sheet.get_cell_value((10_000, 2)), "10000");
+        assert_eq!(sheet.get_cell_value((1, 3)), "#REF!");
+        assert_eq!(sheet.get_cell_value((300, 3)), "#REF!");
+    }
+
+    #[test]
+    fn large_range() {
+        assert_eq!(eval("=SUM(Данные!A1:Z1048576)"), Value::Number(60.0));
+        assert_eq!(eval("=COUNTA(Данные!A1:XFD1048576)"), Value::Number(3.0));
+    }
+
+    #[test]
+    fn calculate() {
+        let sheets = sheets();
+        calculate_sheets(&sheets, &[]);
+
+        let calc = sheets[1].read();
+        assert_eq!(calc.get_cell_value((1, 1)), "60");
+        assert_eq!(calc.get_cell_value((1, 2)), "120");
+        assert_eq!(calc.get_cell_value((1, 3)), "#REF!");
+        assert_eq!(calc.get_cell_value((1, 4)), "#DIV/0!");
+
+        let cell = calc.find_cell_by_coords(1, 1).unwrap().unwrap();
+        assert!(cell.read().is_formula());
+        assert_eq!(cell.read().get_data_type(), "f");
+    }
+}
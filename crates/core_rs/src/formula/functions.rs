@@ -0,0 +1,883 @@
+use std::cmp::Ordering;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::{
+    FormulaError,
+    eval::{Evaluator, Matrix, Value, compare_values, round_significant},
+    lexer::{Op, RefKind},
+    parser::Expr,
+};
+use crate::utils::{datetime_to_excel_serial, excel_serial_to_datetime};
+
+type FnResult = Result<Value, FormulaError>;
+
+/// Предел числа знаков округления: дальше результат в f64 не меняется
+const MAX_DIGITS: f64 = 308.0;
+
+/// Вызов встроенной функции. Неизвестные функции дают `#NAME?`.
+pub(crate) fn call_function(ev: &mut Evaluator, name: &str, args: &[Expr], sheet: usize) -> Value {
+    // Функции с ленивым вычислением аргументов
+    let result = match name {
+        "IF" => if_fn(ev, args, sheet),
+        "IFERROR" => iferror(ev, args, sheet, |_| true),
+        "IFNA" => iferror(ev, args, sheet, |e| e == FormulaError::NA),
+        _ => {
+            let values: Vec<Value> = args.iter().map(|a| eval_arg(ev, a, sheet)).collect();
+            call_eager(name, &values)
+        }
+    };
+
+    result.unwrap_or_else(Value::Error)
+}
+
+/// Вычисление аргумента: ссылка на ячейку остается диапазоном 1x1,
+/// чтобы отличать значения из ячеек от литералов.
+fn eval_arg(ev: &mut Evaluator, expr: &Expr, sheet: usize) -> Value {
+    match expr {
+        Expr::Reference(r) if matches!(r.kind, RefKind::Cell(_)) => match ev.eval(expr, sheet) {
+            Value::Error(e) => Value::Error(e),
+            value => Value::Array(Matrix {
+                rows: 1,
+                cols: 1,
+                values: vec![value],
+            }),
+        },
+        _ => ev.eval(expr, sheet),
+    }
+}
+
+fn call_eager(name: &str, args: &[Value]) -> FnResult {
+    match name {
+        "SUM" => Ok(Value::Number(numbers(args)?.iter().sum())),
+        "AVERAGE" => average(&numbers(args)?),
+        "MIN" => Ok(Value::Number(
+            numbers(args)?.into_iter().reduce(f64::min).unwrap_or(0.0),
+        )),
+        "MAX" => Ok(Value::Number(
+            numbers(args)?.into_iter().reduce(f64::max).unwrap_or(0.0),
+        )),
+        "PRODUCT" => {
+            let nums = numbers(args)?;
+            Ok(Value::Number(if nums.is_empty() {
+                0.0
+            } else {
+                nums.iter().product()
+            }))
+        }
+        "COUNT" => Ok(Value::Number(count(args) as f64)),
+        "COUNTA" => Ok(Value::Number(
+            args.iter()
+                .flat_map(Value::flatten)
+                .filter(|v| **v != Value::Empty)
+                .count() as f64,
+        )),
+        "COUNTBLANK" => Ok(Value::Number(
+            args.iter()
+                .flat_map(Value::flatten)
+                .filter(|v| {
+                    matches!(v, Value::Empty) || matches!(v, Value::Text(s) if s.is_empty())
+                })
+                .count() as f64,
+        )),
+        "SUMPRODUCT" => sumproduct(args),
+        "SUMIF" => {
+            arity(args, 2, 3)?;
+            let (sum, _) = conditional(&args[0], &args[1], args.get(2))?;
+            Ok(Value::Number(sum))
+        }
+        "AVERAGEIF" => {
+            arity(args, 2, 3)?;
+            let (sum, cnt) = conditional(&args[0], &args[1], args.get(2))?;
+            if cnt == 0 {
+                return Err(FormulaError::Div0);
+            }
+            Ok(Value::Number(sum / cnt as f64))
+        }
+        "COUNTIF" => {
+            arity(args, 2, 2)?;
+            let criteria = Criteria::new(&scalar(args, 1)?);
+            Ok(Value::Number(
+                args[0]
+                    .flatten()
+                    .iter()
+                    .filter(|v| criteria.matches(v))
+                    .count() as f64,
+            ))
+        }
+        "AND" | "OR" => logical(name, args),
+        "NOT" => {
+            arity(args, 1, 1)?;
+            Ok(Value::Bool(!scalar(args, 0)?.to_bool()?))
+        }
+        "TRUE" => Ok(Value::Bool(true)),
+        "FALSE" => Ok(Value::Bool(false)),
+        "ROUND" | "ROUNDUP" | "ROUNDDOWN" => {
+            arity(args, 1, 2)?;
+            let n = number(args, 0)?;
+            let digits = number(args, 1)?.trunc().clamp(-MAX_DIGITS, MAX_DIGITS) as i32;
+            Ok(Value::Number(round(name, n, digits)))
+        }
+        "INT" => Ok(Value::Number(single_number(args)?.floor())),
+        "ABS" => Ok(Value::Number(single_number(args)?.abs())),
+        "SQRT" => {
+            let n = single_number(args)?;
+            if n < 0.0 {
+                return Err(FormulaError::Num);
+            }
+            Ok(Value::Number(n.sqrt()))
+        }
+        "MOD" => {
+            arity(args, 2, 2)?;
+            let (n, d) = (number(args, 0)?, number(args, 1)?);
+            if d == 0.0 {
+                return Err(FormulaError::Div0);
+            }
+            Ok(Value::Number(n - d * (n / d).floor()))
+        }
+        "POWER" => {
+            arity(args, 2, 2)?;
+            let n = number(args, 0)?.powf(number(args, 1)?);
+            if !n.is_finite() {
+                return Err(FormulaError::Num);
+            }
+            Ok(Value::Number(n))
+        }
+        "PI" => Ok(Value::Number(std::f64::consts::PI)),
+        "VLOOKUP" | "HLOOKUP" => lookup(name == "VLOOKUP", args),
+        "INDEX" => index(args),
+        "MATCH" => match_fn(args),
+        "CONCATENATE" => {
+            let mut out = String::new();
+            for idx in 0..args.len() {
+                out.push_str(&scalar(args, idx)?.to_text()?);
+            }
+            Ok(Value::Text(out))
+        }
+        "CONCAT" => {
+            let mut out = String::new();
+            for v in args.iter().flat_map(Value::flatten) {
+                out.push_str(&v.to_text()?);
+            }
+            Ok(Value::Text(out))
+        }
+        "LEN" => Ok(Value::Number(single_text(args)?.chars().count() as f64)),
+        "UPPER" => Ok(Value::Text(single_text(args)?.to_uppercase())),
+        "LOWER" => Ok(Value::Text(single_text(args)?.to_lowercase())),
+        "TRIM" => Ok(Value::Text(
+            single_text(args)?
+                .split(' ')
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        )),
+        "LEFT" | "RIGHT" => {
+            arity(args, 1, 2)?;
+            let text = text(args, 0)?;
+            let n = match args.get(1) {
+                Some(Value::Empty) | None => 1,
+                Some(_) => non_negative(number(args, 1)?)?,
+            };
+            let len = text.chars().count();
+            let value = if name == "LEFT" {
+                text.chars().take(n).collect()
+            } else {
+                text.chars().skip(len.saturating_sub(n)).collect()
+            };
+            Ok(Value::Text(value))
+        }
+        "MID" => {
+            arity(args, 3, 3)?;
+            let text = text(args, 0)?;
+            let start = number(args, 1)?.trunc();
+            if start < 1.0 {
+                return Err(FormulaError::Value);
+            }
+            let n = non_negative(number(args, 2)?)?;
+            Ok(Value::Text(
+                text.chars().skip(start as usize - 1).take(n).collect(),
+            ))
+        }
+        "ISBLANK" => Ok(Value::Bool(matches!(single(args)?, Value::Empty))),
+        "ISNUMBER" => Ok(Value::Bool(matches!(single(args)?, Value::Number(_)))),
+        "ISTEXT" => Ok(Value::Bool(matches!(single(args)?, Value::Text(_)))),
+        "ISERROR" => Ok(Value::Bool(matches!(single(args)?, Value::Error(_)))),
+        "ISNA" => Ok(Value::Bool(matches!(
+            single(args)?,
+            Value::Error(FormulaError::NA)
+        ))),
+        "NA" => Err(FormulaError::NA),
+        "DATE" => date(args),
+        "YEAR" | "MONTH" | "DAY" => {
+            let dt = excel_serial_to_datetime(single_number(args)?).ok_or(FormulaError::Num)?;
+            Ok(Value::Number(match name {
+                "YEAR" => dt.year() as f64,
+                "MONTH" => dt.month() as f64,
+                _ => dt.day() as f64,
+            }))
+        }
+        _ => Err(FormulaError::Name),
+    }
+}
+
+/// Проверка количества аргументов
+fn arity(args: &[Value], min: usize, max: usize) -> Result<(), FormulaError> {
+    if args.len() < min || args.len() > max {
+        return Err(FormulaError::Value);
+    }
+
+    Ok(())
+}
+
+/// Одиночное значение аргумента; отсутствующий аргумент - пустое значение
+fn scalar(args: &[Value], idx: usize) -> FnResult {
+    match args.get(idx) {
+        None => Ok(Value::Empty),
+        Some(Value::Array(m)) if m.values.len() == 1 => Ok(m.values[0].clone()),
+        Some(Value::Array(_)) => Err(FormulaError::Value),
+        Some(v) => Ok(v.clone()),
+    }
+}
+
+fn single(args: &[Value]) -> FnResult {
+    arity(args, 1, 1)?;
+    scalar(args, 0)
+}
+
+fn number(args: &[Value], idx: usize) -> Result<f64, FormulaError> {
+    scalar(args, idx)?.to_number()
+}
+
+fn single_number(args: &[Value]) -> Result<f64, FormulaError> {
+    single(args)?.to_number()
+}
+
+fn text(args: &[Value], idx: usize) -> Result<String, FormulaError> {
+    scalar(args, idx)?.to_text()
+}
+
+fn single_text(args: &[Value]) -> Result<String, FormulaError> {
+    single(args)?.to_text()
+}
+
+fn non_negative(n: f64) -> Result<usize, FormulaError> {
+    if n < 0.0 {
+        return Err(FormulaError::Value);
+    }
+
+    Ok(n.trunc() as usize)
+}
+
+/// Числа из аргументов: в диапазонах учитываются только числа,
+/// одиночные значения приводятся к числу.
+fn numbers(args: &[Value]) -> Result<Vec<f64>, FormulaError> {
+    let mut out = Vec::new();
+
+    for arg in args {
+        match arg {
+            Value::Array(m) => {
+                for v in &m.values {
+                    match v {
+                        Value::Number(n) => out.push(*n),
+                        Value::Error(e) => return Err(*e),
+                        _ => {}
+                    }
+                }
+            }
+            Value::Empty => {}
+            v => out.push(v.to_number()?),
+        }
+    }
+
+    Ok(out)
+}
+
+fn average(nums: &[f64]) -> FnResult {
+    if nums.is_empty() {
+        return Err(FormulaError::Div0);
+    }
+
+    Ok(Value::Number(nums.iter().sum::<f64>() / nums.len() as f64))
+}
+
+fn count(args: &[Value]) -> usize {
+    args.iter()
+        .map(|arg| match arg {
+            Value::Array(m) => m
+                .values
+                .iter()
+                .filter(|v| matches!(v, Value::Number(_)))
+                .count(),
+            Value::Empty | Value::Error(_) => 0,
+            v => v.to_number().is_ok() as usize,
+        })
+        .sum()
+}
+
+fn sumproduct(args: &[Value]) -> FnResult {
+    if args.is_empty() {
+        return Err(FormulaError::Value);
+    }
+
+    let len = args[0].flatten().len();
+    if args.iter().any(|a| a.flatten().len() != len) {
+        return Err(FormulaError::Value);
+    }
+
+    let mut total = 0.0;
+    for idx in 0..len {
+        let mut product = 1.0;
+        for arg in args {
+            match &arg.flatten()[idx] {
+                Value::Number(n) => product *= n,
+                Value::Error(e) => return Err(*e),
+                _ => product = 0.0,
+            }
+        }
+        total += product;
+    }
+
+    Ok(Value::Number(total))
+}
+
+fn logical(name: &str, args: &[Value]) -> FnResult {
+    let mut values = Vec::new();
+
+    for arg in args {
+        match arg {
+            Value::Array(m) => {
+                for v in &m.values {
+                    match v {
+                        Value::Bool(b) => values.push(*b),
+                        Value::Number(n) => values.push(*n != 0.0),
+                        Value::Error(e) => return Err(*e),
+                        _ => {}
+                    }
+                }
+            }
+            v => values.push(v.to_bool()?),
+        }
+    }
+
+    if values.is_empty() {
+        return Err(FormulaError::Value);
+    }
+
+    Ok(Value::Bool(if name == "AND" {
+        values.iter().all(|b| *b)
+    } else {
+        values.iter().any(|b| *b)
+    }))
+}
+
+fn if_fn(ev: &mut Evaluator, args: &[Expr], sheet: usize) -> FnResult {
+    if args.is_empty() || args.len() > 3 {
+        return Err(FormulaError::Value);
+    }
+
+    let condition = ev.eval_scalar(&args[0], sheet).to_bool()?;
+    let branch = if condition { args.get(1) } else { args.get(2) };
+
+    match branch {
+        Some(expr) => Ok(ev.eval(expr, sheet)),
+        // Отсутствующая ветка возвращает логическое значение условия
+        None => Ok(Value::Bool(condition)),
+    }
+}
+
+fn iferror(
+    ev: &mut Evaluator,
+    args: &[Expr],
+    sheet: usize,
+    catch: fn(FormulaError) -> bool,
+) -> FnResult {
+    if args.len() != 2 {
+        return Err(FormulaError::Value);
+    }
+
+    match ev.eval_scalar(&args[0], sheet) {
+        Value::Error(e) if catch(e) => Ok(ev.eval(&args[1], sheet)),
+        value => Ok(value),
+    }
+}
+
+/// Округление по правилам Excel (половина - от нуля)
+fn round(name: &str, n: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits.abs());
+    let value = if digits >= 0 { n * factor } else { n / factor };
+    if !value.is_finite() {
+        return n;
+    }
+    let value = round_significant(value);
+
+    let rounded = match name {
+        "ROUNDUP" => value.signum() * value.abs().ceil(),
+        "ROUNDDOWN" => value.trunc(),
+        _ => value.round(),
+    };
+
+    if digits >= 0 {
+        rounded / factor
+    } else {
+        rounded * factor
+    }
+}
+
+/// Сопоставление текста с шаблоном Excel (`*`, `?`, `~`) без учета регистра
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match p.first() {
+            None => t.is_empty(),
+            Some('*') => (0..=t.len()).any(|i| matches(&p[1..], &t[i..])),
+            Some('?') => !t.is_empty() && matches(&p[1..], &t[1..]),
+            Some('~') if p.len() > 1 => t.first() == Some(&p[1]) && matches(&p[2..], &t[1..]),
+            Some(c) => t.first() == Some(c) && matches(&p[1..], &t[1..]),
+        }
+    }
+
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+
+    matches(&p, &t)
+}
+
+fn has_wildcards(value: &str) -> bool {
+    value.contains(['*', '?'])
+}
+
+/// Условие функций SUMIF/COUNTIF/AVERAGEIF
+struct Criteria {
+    op: Op,
+    value: Value,
+}
+
+impl Criteria {
+    fn new(value: &Value) -> Self {
+        let Value::Text(s) = value else {
+            return Self {
+                op: Op::Eq,
+                value: value.clone(),
+            };
+        };
+
+        let (op, rest) = [
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<>", Op::Ne),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+            ("=", Op::Eq),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Eq, s.as_str()));
+
+        let value = if let Ok(n) = rest.trim().parse::<f64>() {
+            Value::Number(n)
+        } else {
+            match rest.to_uppercase().as_str() {
+                "TRUE" => Value::Bool(true),
+                "FALSE" => Value::Bool(false),
+                _ => Value::Text(rest.to_string()),
+            }
+        };
+
+        Self { op, value }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        let equal = match (&self.value, value) {
+            (Value::Text(p), Value::Empty) => p.is_empty(),
+            (Value::Text(p), Value::Text(t)) => wildcard_match(p, t),
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            _ => false,
+        };
+
+        match self.op {
+            Op::Eq => equal,
+            Op::Ne => !equal,
+            op => {
+                let comparable = matches!(
+                    (&self.value, value),
+                    (Value::Number(_), Value::Number(_))
+                        | (Value::Text(_), Value::Text(_))
+                        | (Value::Bool(_), Value::Bool(_))
+                );
+                if !comparable {
+                    return false;
+                }
+
+                let ord = compare_values(value, &self.value);
+                match op {
+                    Op::Lt => ord == Ordering::Less,
+                    Op::Gt => ord == Ordering::Greater,
+                    Op::Le => ord != Ordering::Greater,
+                    _ => ord != Ordering::Less,
+                }
+            }
+        }
+    }
+}
+
+/// Сумма и количество подходящих значений для SUMIF/AVERAGEIF
+fn conditional(
+    range: &Value,
+    criteria: &Value,
+    sum_range: Option<&Value>,
+) -> Result<(f64, usize), FormulaError> {
+    let criteria = Criteria::new(&scalar(std::slice::from_ref(criteria), 0)?);
+    let range = as_matrix(range);
+    let sum_range = sum_range.map(as_matrix).unwrap_or_else(|| range.clone());
+
+    let mut sum = 0.0;
+    let mut cnt = 0;
+    for row in 0..range.rows {
+        for col in 0..range.cols {
+            if !criteria.matches(range.get(row, col)) {
+                continue;
+            }
+
+            if row < sum_range.rows && col < sum_range.cols {
+                match sum_range.get(row, col) {
+                    Value::Number(n) => {
+                        sum += n;
+                        cnt += 1;
+                    }
+                    Value::Error(e) => return Err(*e),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok((sum, cnt))
+}
+
+fn as_matrix(value: &Value) -> Matrix {
+    match value {
+        Value::Array(m) => m.clone(),
+        v => Matrix {
+            rows: 1,
+            cols: 1,
+            values: vec![v.clone()],
+        },
+    }
+}
+
+/// Совпадение значений при точном поиске
+fn lookup_equal(item: &Value, target: &Value) -> bool {
+    match (item, target) {
+        (Value::Text(i), Value::Text(t)) if has_wildcards(t) => wildcard_match(t, i),
+        (Value::Text(i), Value::Text(t)) => i.to_lowercase() == t.to_lowercase(),
+        (Value::Number(i), Value::Number(t)) => i == t,
+        (Value::Bool(i), Value::Bool(t)) => i == t,
+        _ => false,
+    }
+}
+
+/// Позиция значения в списке. `mode`: 0 - точное совпадение,
+/// 1 - наибольшее значение <= искомого, -1 - наименьшее значение >= искомого.
+fn find_position(items: &[&Value], target: &Value, mode: i32) -> Option<usize> {
+    if mode == 0 {
+        return items.iter().position(|item| lookup_equal(item, target));
+    }
+
+    let same_kind = |item: &Value| std::mem::discriminant(item) == std::mem::discriminant(target);
+
+    let mut found = None;
+    for (idx, item) in items.iter().enumerate() {
+        if !same_kind(item) {
+            continue;
+        }
+
+        let ord = compare_values(item, target);
+        let fits = if mode > 0 {
+            ord != Ordering::Greater
+        } else {
+            ord != Ordering::Less
+        };
+
+        if fits {
+            found = Some(idx);
+            if ord == Ordering::Equal {
+                break;
+            }
+        } else {
+            break;
+        }
+    }
+
+    found
+}
+
+fn lookup(vertical: bool, args: &[Value]) -> FnResult {
+    arity(args, 3, 4)?;
+
+    let target = scalar(args, 0)?;
+    if let Value::Error(e) = target {
+        return Err(e);
+    }
+
+    let table = as_matrix(&args[1]);
+    let index = number(args, 2)?.trunc();
+    let approximate = match args.get(3) {
+        None | Some(Value::Empty) => true,
+        Some(_) => scalar(args, 3)?.to_bool()?,
+    };
+
+    let (lines, width) = if vertical {
+        (table.rows, table.cols)
+    } else {
+        (table.cols, table.rows)
+    };
+
+    if index < 1.0 {
+        return Err(FormulaError::Value);
+    }
+    let index = index as usize - 1;
+    if index >= width {
+        return Err(FormulaError::Ref);
+    }
+
+    let cell = |line: usize, pos: usize| {
+        if vertical {
+            table.get(line, pos)
+        } else {
+            table.get(pos, line)
+        }
+    };
+
+    let keys: Vec<&Value> = (0..lines).map(|line| cell(line, 0)).collect();
+    let found =
+        find_position(&keys, &target, if approximate { 1 } else { 0 }).ok_or(FormulaError::NA)?;
+
+    Ok(cell(found, index).clone())
+}
+
+fn index(args: &[Value]) -> FnResult {
+    arity(args, 2, 3)?;
+
+    let table = as_matrix(&args[0]);
+    let first = number(args, 1)?.trunc();
+    let second = match args.get(2) {
+        None | Some(Value::Empty) => None,
+        Some(_) => Some(number(args, 2)?.trunc()),
+    };
+
+    // Для одной строки единственный индекс - номер колонки
+    let (row, col) = match second {
+        Some(col) => (first, col),
+        None if table.rows == 1 => (1.0, first),
+        None => (first, 1.0),
+    };
+
+    if row < 1.0 || col < 1.0 {
+        return Err(FormulaError::Value);
+    }
+
+    let (row, col) = (row as usize - 1, col as usize - 1);
+    if row >= table.rows || col >= table.cols {
+        return Err(FormulaError::Ref);
+    }
+
+    Ok(table.get(row, col).clone())
+}
+
+fn match_fn(args: &[Value]) -> FnResult {
+    arity(args, 2, 3)?;
+
+    let target = scalar(args, 0)?;
+    if let Value::Error(e) = target {
+        return Err(e);
+    }
+
+    let table = as_matrix(&args[1]);
+    if table.rows > 1 && table.cols > 1 {
+        return Err(FormulaError::NA);
+    }
+
+    let mode = match args.get(2) {
+        None | Some(Value::Empty) => 1,
+        Some(_) => match number(args, 2)? {
+            n if n > 0.0 => 1,
+            n if n < 0.0 => -1,
+            _ => 0,
+        },
+    };
+
+    let items: Vec<&Value> = table.values.iter().collect();
+    let pos = find_position(&items, &target, mode).ok_or(FormulaError::NA)?;
+
+    Ok(Value::Number((pos + 1) as f64))
+}
+
+fn date(args: &[Value]) -> FnResult {
+    arity(args, 3, 3)?;
+
+    let year = number(args, 0)?.trunc();
+    let month = number(args, 1)?.trunc() as i64;
+    let day = number(args, 2)?.trunc() as i64;
+
+    // Excel принимает годы 0..9999, годы 0..1899 отсчитываются от 1900
+    if !(0.0..10_000.0).contains(&year) {
+        return Err(FormulaError::Num);
+    }
+    let year = if year < 1900.0 { year + 1900.0 } else { year } as i64;
+
+    let months = (year * 12)
+        .checked_add(month)
+        .and_then(|m| m.checked_sub(1))
+        .ok_or(FormulaError::Num)?;
+    let first = i32::try_from(months.div_euclid(12))
+        .ok()
+        .and_then(|y| NaiveDate::from_ymd_opt(y, months.rem_euclid(12) as u32 + 1, 1))
+        .ok_or(FormulaError::Num)?;
+    let date = day
+        .checked_sub(1)
+        .and_then(Duration::try_days)
+        .and_then(|days| first.checked_add_signed(days))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .ok_or(FormulaError::Num)?;
+
+    let serial = datetime_to_excel_serial(&date);
+    if serial < 0.0 {
+        return Err(FormulaError::Num);
+    }
+
+    Ok(Value::Number(serial))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parking_lot::RwLock;
+
+    use super::*;
+    use crate::{
+        structs::{coordinate::Coordinate, sheet::Sheet},
+        traits::WriteableSheet,
+    };
+
+    fn eval(formula: &str) -> Value {
+        let mut sheet = Sheet::new("Лист", "visible");
+        let rows = [
+            ["Яблоки", "10", "1.5"],
+            ["Груши", "20", "2.25"],
+            ["Сливы", "", "3"],
+            ["Яблоки", "5", "abc"],
+        ];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                if !value.is_empty() {
+                    sheet.cell(Coordinate::new(r as u32 + 1, c as u16 + 1), Some(value));
+                }
+            }
+        }
+
+        let sheets = vec![Arc::new(RwLock::new(sheet))];
+        let mut evaluator = Evaluator::new(&sheets, &[]);
+
+        evaluator.eval_formula(0, 100, 100, formula)
+    }
+
+    #[test]
+    fn aggregates() {
+        assert_eq!(eval("SUM(B1:C4)"), Value::Number(41.75));
+        assert_eq!(eval("SUM(B1,\"2\",TRUE)"), Value::Number(13.0));
+        assert_eq!(eval("SUM(\"x\")"), Value::Error(FormulaError::Value));
+        assert_eq!(eval("AVERAGE(B1:B4)"), Value::Number(35.0 / 3.0));
+        assert_eq!(eval("MIN(B1:B4)"), Value::Number(5.0));
+        assert_eq!(eval("MAX(B1:C4)"), Value::Number(20.0));
+        assert_eq!(eval("COUNT(B1:C4)"), Value::Number(6.0));
+        assert_eq!(eval("COUNTA(A1:C4)"), Value::Number(11.0));
+        assert_eq!(eval("COUNTBLANK(A1:C4)"), Value::Number(1.0));
+        assert_eq!(eval("SUMPRODUCT(B1:B2,C1:C2)"), Value::Number(60.0));
+        assert_eq!(eval("AVERAGE(D1:D4)"), Value::Error(FormulaError::Div0));
+    }
+
+    #[test]
+    fn conditions() {
+        assert_eq!(eval("SUMIF(A1:A4,\"Яблоки\",B1:B4)"), Value::Number(15.0));
+        assert_eq!(eval("SUMIF(B1:B4,\">=10\")"), Value::Number(30.0));
+        assert_eq!(eval("COUNTIF(A1:A4,\"я*\")"), Value::Number(2.0));
+        assert_eq!(eval("COUNTIF(B1:B4,\"\")"), Value::Number(1.0));
+        assert_eq!(
+            eval("AVERAGEIF(A1:A4,\"<>Груши\",B1:B4)"),
+            Value::Number(7.5)
+        );
+        assert_eq!(
+            eval("IF(B1>5,\"да\",\"нет\")"),
+            Value::Text("да".to_string())
+        );
+        assert_eq!(eval("IF(B1>50,1)"), Value::Bool(false));
+        assert_eq!(eval("IF(TRUE,1,1/0)"), Value::Number(1.0));
+        assert_eq!(eval("IFERROR(1/0,-1)"), Value::Number(-1.0));
+        assert_eq!(eval("IFNA(1/0,-1)"), Value::Error(FormulaError::Div0));
+        assert_eq!(eval("AND(B1:B2,TRUE)"), Value::Bool(true));
+        assert_eq!(eval("OR(B3>1,NOT(TRUE))"), Value::Bool(false));
+    }
+
+    #[test]
+    fn math_and_text() {
+        assert_eq!(eval("ROUND(2.675,2)"), Value::Number(2.68));
+        assert_eq!(eval("ROUND(-2.5,0)"), Value::Number(-3.0));
+        assert_eq!(eval("ROUND(1234,-2)"), Value::Number(1200.0));
+        assert_eq!(eval("ROUNDUP(1.21,1)"), Value::Number(1.3));
+        assert_eq!(eval("ROUNDDOWN(-1.29,1)"), Value::Number(-1.2));
+        assert_eq!(eval("ROUND(1234.5,-1e10)"), Value::Number(0.0));
+        assert_eq!(eval("ROUND(1234.5,1e10)"), Value::Number(1234.5));
+        assert_eq!(eval("ROUNDUP(1e300,300)"), Value::Number(1e300));
+        assert_eq!(eval("INT(-1.5)"), Value::Number(-2.0));
+        assert_eq!(eval("MOD(-3,2)"), Value::Number(1.0));
+        assert_eq!(eval("SQRT(-1)"), Value::Error(FormulaError::Num));
+        assert_eq!(
+            eval("CONCATENATE(A1,\" \",B1)"),
+            Value::Text("Яблоки 10".to_string())
+        );
+        assert_eq!(
+            eval("LEFT(A1,3)&RIGHT(A2)"),
+            Value::Text("Ябли".to_string())
+        );
+        assert_eq!(eval("MID(A3,2,3)"), Value::Text("лив".to_string()));
+        assert_eq!(eval("LEN(TRIM(\"  a  b \"))"), Value::Number(3.0));
+        assert_eq!(eval("ISBLANK(B3)"), Value::Bool(true));
+        assert_eq!(eval("ISTEXT(C4)"), Value::Bool(true));
+        assert_eq!(eval("YEAR(DATE(2024,14,1))"), Value::Number(2025.0));
+        assert_eq!(eval("DATE(1e300,1,1)"), Value::Error(FormulaError::Num));
+        assert_eq!(eval("DATE(10000,1,1)"), Value::Error(FormulaError::Num));
+        assert_eq!(eval("DATE(2020,1,1e18)"), Value::Error(FormulaError::Num));
+        assert_eq!(eval("DATE(2020,-1e18,1)"), Value::Error(FormulaError::Num));
+        assert_eq!(eval("UNKNOWN(1)"), Value::Error(FormulaError::Name));
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(
+            eval("VLOOKUP(\"груши\",A1:C4,3,FALSE)"),
+            Value::Number(2.25)
+        );
+        assert_eq!(
+            eval("VLOOKUP(\"Персики\",A1:C4,2,0)"),
+            Value::Error(FormulaError::NA)
+        );
+        assert_eq!(
+            eval("VLOOKUP(\"Груши\",A1:C4,4,0)"),
+            Value::Error(FormulaError::Ref)
+        );
+        assert_eq!(eval("VLOOKUP(15,B1:C2,2)"), Value::Number(1.5));
+        assert_eq!(
+            eval("HLOOKUP(\"Яблоки\",A1:C2,2,FALSE)"),
+            Value::Text("Груши".to_string())
+        );
+        assert_eq!(eval("INDEX(A1:C4,2,3)"), Value::Number(2.25));
+        assert_eq!(eval("INDEX(A1:C1,2)"), Value::Number(10.0));
+        assert_eq!(eval("MATCH(\"Сливы\",A1:A4,0)"), Value::Number(3.0));
+        assert_eq!(eval("MATCH(12,B1:B2)"), Value::Number(1.0));
+        assert_eq!(
+            eval("INDEX(C1:C4,MATCH(\"Г*\",A1:A4,0))"),
+            Value::Number(2.25)
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use anyhow::{Result, bail};
+
+use super::FormulaError;
+use crate::{MAX_COL, MAX_ROW};
+
+/// Адрес ячейки в формуле с признаками абсолютной ссылки.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CellAddr {
+    pub row: u32,
+    pub col: u16,
+    pub row_abs: bool,
+    pub col_abs: bool,
+}
+
+/// Вид ссылки в формуле.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum RefKind {
+    Cell(CellAddr),
+    Range(CellAddr, CellAddr),
+    Cols {
+        start: u16,
+        end: u16,
+        start_abs: bool,
+        end_abs: bool,
+    },
+    Rows {
+        start: u32,
+        end: u32,
+        start_abs: bool,
+        end_abs: bool,
+    },
+}
+
+/// Ссылка на ячейку или диапазон, возможно на другом листе.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Reference {
+    pub sheet: Option<String>,
+    pub kind: RefKind,
+}
+
+/// Бинарные операторы формул.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Concat,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TokenKind {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(FormulaError),
+    Reference(Reference),
+    Name(String),
+    Function(String),
+    Op(Op),
+    Percent,
+    LParen,
+    RParen,
+    Sep,
+}
+
+/// Лексема формулы с позицией в исходной строке (в байтах).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Преобразование букв колонки в номер
+fn column_index(letters: &str) -> Option<u16> {
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+
+    let mut col: u32 = 0;
+    for ch in letters.chars() {
+        if !ch.is_ascii_alphabetic() {
+            return None;
+        }
+        col = col * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+
+    if col == 0 || col > MAX_COL as u32 {
+        return None;
+    }
+
+    Some(col as u16)
+}
+
+/// Разбор номера строки
+fn row_index(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits
+        .parse::<u32>()
+        .ok()
+        .filter(|r| *r >= 1 && *r <= MAX_ROW)
+}
+
+/// Отделение признака абсолютной ссылки `$`
+fn split_abs(value: &str) -> (bool, &str) {
+    match value.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    }
+}
+
+/// Разбор адреса ячейки вида `$A$1`
+pub(crate) fn parse_cell_addr(value: &str) -> Option<CellAddr> {
+    let (col_abs, rest) = split_abs(value);
+    let letters_end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (letters, rest) = rest.split_at(letters_end);
+    let (row_abs, digits) = split_abs(rest);
+
+    Some(CellAddr {
+        row: row_index(digits)?,
+        col: column_index(letters)?,
+        row_abs,
+        col_abs,
+    })
+}
+
+/// Разбор ссылки без имени листа: `A1`, `A1:B2`, `A:C`, `1:3`
+pub(crate) fn parse_ref_kind(value: &str) -> Option<RefKind> {
+    match value.split_once(':') {
+        None => parse_cell_addr(value).map(RefKind::Cell),
+        Some((start, end)) => {
+            if let (Some(s), Some(e)) = (parse_cell_addr(start), parse_cell_addr(end)) {
+                return Some(RefKind::Range(s, e));
+            }
+
+            let (start_abs, s) = split_abs(start);
+            let (end_abs, e) = split_abs(end);
+
+            if let (Some(start), Some(end)) = (column_index(s), column_index(e)) {
+                return Some(RefKind::Cols {
+                    start,
+                    end,
+                    start_abs,
+                    end_abs,
+                });
+            }
+
+            if let (Some(start), Some(end)) = (row_index(s), row_index(e)) {
+                return Some(RefKind::Rows {
+                    start,
+                    end,
+                    start_abs,
+                    end_abs,
+                });
+            }
+
+            None
+        }
+    }
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.src[self.pos..].chars().nth(offset)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+
+        Some(ch)
+    }
+
+    /// Чтение "слова": имени, адреса или имени листа
+    fn word(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(ch) = self.peek() {
+            if ch.is_alphanumeric() || matches!(ch, '_' | '.' | '$' | '\\') {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+
+        &self.src[start..self.pos]
+    }
+
+    /// Чтение строки в кавычках с удвоением кавычки внутри
+    fn quoted(&mut self, quote: char) -> Result<String> {
+        self.bump();
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(ch) if ch == quote => {
+                    if self.peek() == Some(quote) {
+                        self.bump();
+                        value.push(quote);
+                    } else {
+                        return Ok(value);
+                    }
+                }
+                Some(ch) => value.push(ch),
+                None => bail!("Unterminated quoted value in formula"),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || c == '.') {
+            self.bump();
+        }
+
+        if matches!(self.peek(), Some('e' | 'E'))
+            && matches!(self.peek_at(1), Some(c) if c.is_ascii_digit() || c == '+' || c == '-')
+        {
+            self.bump();
+            self.bump();
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                self.bump();
+            }
+        }
+
+        let value = &self.src[start..self.pos];
+        match value.parse::<f64>() {
+            Ok(n) => Ok(n),
+            Err(_) => bail!("Invalid number in formula: {value}"),
+        }
+    }
+
+    fn error(&mut self) -> Result<FormulaError> {
+        let rest = &self.src[self.pos..];
+        for literal in [
+            "#DIV/0!", "#N/A", "#NAME?", "#NULL!", "#NUM!", "#REF!", "#VALUE!",
+        ] {
+            if rest.len() >= literal.len()
+                && rest.is_char_boundary(literal.len())
+                && rest[..literal.len()].eq_ignore_ascii_case(literal)
+            {
+                self.pos += literal.len();
+                return literal.parse();
+            }
+        }
+
+        bail!("Unknown error literal in formula")
+    }
+
+    /// Попытка прочитать ссылку, начиная с текущей позиции
+    fn reference_body(&mut self) -> Option<RefKind> {
+        let start = self.pos;
+        let first = self.word();
+
+        if self.peek() == Some(':') {
+            let colon = self.pos;
+            self.bump();
+            let second = self.word();
+
+            if let Some(kind) = parse_ref_kind(&format!("{first}:{second}")) {
+                return Some(kind);
+            }
+            self.pos = colon;
+        }
+
+        match parse_ref_kind(first) {
+            Some(kind) => Some(kind),
+            None => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// Ссылка после имени листа: `Лист!A1`, `Лист!#REF!`
+    fn sheet_reference(&mut self, sheet: String) -> Result<TokenKind> {
+        // Пропускаем "!"
+        self.bump();
+
+        if self.peek() == Some('#') {
+            return Ok(TokenKind::Error(self.error()?));
+        }
+
+        match self.reference_body() {
+            Some(kind) => Ok(TokenKind::Reference(Reference {
+                sheet: Some(sheet),
+                kind,
+            })),
+            None => bail!("Invalid reference to sheet {sheet}"),
+        }
+    }
+
+    fn next_kind(&mut self) -> Result<TokenKind> {
+        let ch = match self.peek() {
+            Some(ch) => ch,
+            None => bail!("Unexpected end of formula"),
+        };
+
+        let kind = match ch {
+            '"' => TokenKind::Text(self.quoted('"')?),
+            '\'' => {
+                let sheet = self.quoted('\'')?;
+                if self.peek() != Some('!') {
+                    bail!("Expected '!' after sheet name {sheet}");
+                }
+                self.sheet_reference(sheet)?
+            }
+            '#' => TokenKind::Error(self.error()?),
+            '(' => {
+                self.bump();
+                TokenKind::LParen
+            }
+            ')' => {
+                self.bump();
+                TokenKind::RParen
+            }
+            ',' | ';' => {
+                self.bump();
+                TokenKind::Sep
+            }
+            '%' => {
+                self.bump();
+                TokenKind::Percent
+            }
+            '+' | '-' | '*' | '/' | '^' | '&' | '=' => {
+                self.bump();
+                TokenKind::Op(match ch {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    '/' => Op::Div,
+                    '^' => Op::Pow,
+                    '&' => Op::Concat,
+                    _ => Op::Eq,
+                })
+            }
+            '<' => {
+                self.bump();
+                match self.peek() {
+                    Some('=') => {
+                        self.bump();
+                        TokenKind::Op(Op::Le)
+                    }
+                    Some('>') => {
+                        self.bump();
+                        TokenKind::Op(Op::Ne)
+                    }
+                    _ => TokenKind::Op(Op::Lt),
+                }
+            }
+            '>' => {
+                self.bump();
+                if self.peek() == Some('=') {
+                    self.bump();
+                    TokenKind::Op(Op::Ge)
+                } else {
+                    TokenKind::Op(Op::Gt)
+                }
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                // Диапазон строк вида 1:3
+                if let Some(kind) = self.reference_body() {
+                    return Ok(TokenKind::Reference(Reference { sheet: None, kind }));
+                }
+                TokenKind::Number(self.number()?)
+            }
+            c if c.is_alphabetic() || matches!(c, '_' | '$' | '\\') => {
+                let start = self.pos;
+                let word = self.word();
+
+                match self.peek() {
+                    Some('!') => self.sheet_reference(word.to_string())?,
+                    Some('(') => TokenKind::Function(word.to_uppercase()),
+                    _ => {
+                        self.pos = start;
+                        if let Some(kind) = self.reference_body() {
+                            TokenKind::Reference(Reference { sheet: None, kind })
+                        } else {
+                            let word = self.word();
+                            match word.to_uppercase().as_str() {
+                                "TRUE" => TokenKind::Bool(true),
+                                "FALSE" => TokenKind::Bool(false),
+                                _ => TokenKind::Name(word.to_string()),
+                            }
+                        }
+                    }
+                }
+            }
+            c => bail!("Unexpected character in formula: {c}"),
+        };
+
+        Ok(kind)
+    }
+}
+
+/// Разбор формулы на лексемы. Ведущий знак `=` допускается и пропускается.
+pub(crate) fn tokenize(formula: &str) -> Result<Vec<Token>> {
+    let mut lexer = Lexer {
+        src: formula,
+        pos: 0,
+    };
+
+    let mut tokens = Vec::new();
+    let mut leading = true;
+
+    loop {
+        while matches!(lexer.peek(), Some(c) if c.is_whitespace()) {
+            lexer.bump();
+        }
+
+        if lexer.peek().is_none() {
+            break;
+        }
+
+        if leading && lexer.peek() == Some('=') {
+            lexer.bump();
+            leading = false;
+            continue;
+        }
+        leading = false;
+
+        let start = lexer.pos;
+        let kind = lexer.next_kind()?;
+        tokens.push(Token {
+            kind,
+            start,
+            end: lexer.pos,
+        });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(formula: &str) -> Vec<TokenKind> {
+        tokenize(formula)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    fn cell(row: u32, col: u16) -> CellAddr {
+        CellAddr {
+            row,
+            col,
+            row_abs: false,
+            col_abs: false,
+        }
+    }
+
+    #[test]
+    fn tokenize_arithmetic() {
+        assert_eq!(
+            kinds("=1+2.5*-3%"),
+            vec![
+                TokenKind::Number(1.0),
+                TokenKind::Op(Op::Add),
+                TokenKind::Number(2.5),
+                TokenKind::Op(Op::Mul),
+                TokenKind::Op(Op::Sub),
+                TokenKind::Number(3.0),
+                TokenKind::Percent,
+            ]
+        );
+        assert_eq!(
+            kinds("\"a\"\"b\"<>TRUE"),
+            vec![
+                TokenKind::Text("a\"b".to_string()),
+                TokenKind::Op(Op::Ne),
+                TokenKind::Bool(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_references() {
+        assert_eq!(
+            kinds("SUM($A$1:B2;'Лист 1'!C3)"),
+            vec![
+                TokenKind::Function("SUM".to_string()),
+                TokenKind::LParen,
+                TokenKind::Reference(Reference {
+                    sheet: None,
+                    kind: RefKind::Range(
+                        CellAddr {
+                            row: 1,
+                            col: 1,
+                            row_abs: true,
+                            col_abs: true,
+                        },
+                        cell(2, 2)
+                    ),
+                }),
+                TokenKind::Sep,
+                TokenKind::Reference(Reference {
+                    sheet: Some("Лист 1".to_string()),
+                    kind: RefKind::Cell(cell(3, 3)),
+                }),
+                TokenKind::RParen,
+            ]
+        );
+        assert_eq!(
+            kinds("Data!A:B"),
+            vec![TokenKind::Reference(Reference {
+                sheet: Some("Data".to_string()),
+                kind: RefKind::Cols {
+                    start: 1,
+                    end: 2,
+                    start_abs: false,
+                    end_abs: false,
+                },
+            })]
+        );
+        assert_eq!(
+            kinds("2:3"),
+            vec![TokenKind::Reference(Reference {
+                sheet: None,
+                kind: RefKind::Rows {
+                    start: 2,
+                    end: 3,
+                    start_abs: false,
+                    end_abs: false,
+                },
+            })]
+        );
+    }
+
+    #[test]
+    fn tokenize_names_and_errors() {
+        assert_eq!(
+            kinds("LOG10(Total)+#N/A+Лист!#REF!"),
+            vec![
+                TokenKind::Function("LOG10".to_string()),
+                TokenKind::LParen,
+                TokenKind::Name("Total".to_string()),
+                TokenKind::RParen,
+                TokenKind::Op(Op::Add),
+                TokenKind::Error(FormulaError::NA),
+                TokenKind::Op(Op::Add),
+                TokenKind::Error(FormulaError::Ref),
+            ]
+        );
+        assert!(tokenize("\"abc").is_err());
+        assert!(tokenize("A1 @ B1").is_err());
+    }
+
+    #[test]
+    fn token_spans() {
+        let formula = "= A1 + Лист!B2";
+        let tokens = tokenize(formula).unwrap();
+
+        assert_eq!(&formula[tokens[0].start..tokens[0].end], "A1");
+        assert_eq!(&formula[tokens[2].start..tokens[2].end], "Лист!B2");
+    }
+}
@@ -0,0 +1,266 @@
+use anyhow::{Result, bail};
+
+use super::{
+    FormulaError,
+    lexer::{Op, Reference, Token, TokenKind, tokenize},
+};
+
+/// Синтаксическое дерево формулы.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Expr {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(FormulaError),
+    Reference(Reference),
+    Name(String),
+    /// Пропущенный аргумент функции, например `IF(A1,,1)`
+    Missing,
+    Neg(Box<Expr>),
+    Percent(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// Максимальная вложенность выражений, как в Excel
+const MAX_NESTING: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Текущая вложенность скобок, функций и унарных операторов
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn next(&mut self) -> Option<TokenKind> {
+        let token = self.tokens.get(self.pos).map(|t| t.kind.clone());
+        self.pos += 1;
+
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<()> {
+        match self.next() {
+            Some(ref k) if *k == kind => Ok(()),
+            other => bail!("Expected {kind:?}, found {other:?}"),
+        }
+    }
+
+    /// Разбор вложенного выражения с ограничением глубины
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= MAX_NESTING {
+            bail!("Formula exceeds {MAX_NESTING} levels of nesting");
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Разбор бинарных операторов одного уровня приоритета
+    fn binary(&mut self, ops: &[Op], next: fn(&mut Self) -> Result<Expr>) -> Result<Expr> {
+        let mut left = next(self)?;
+
+        while let Some(TokenKind::Op(op)) = self.peek() {
+            let op = *op;
+            if !ops.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+
+            let right = next(self)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        self.binary(
+            &[Op::Eq, Op::Ne, Op::Lt, Op::Gt, Op::Le, Op::Ge],
+            Self::concat,
+        )
+    }
+
+    fn concat(&mut self) -> Result<Expr> {
+        self.binary(&[Op::Concat], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        self.binary(&[Op::Add, Op::Sub], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        self.binary(&[Op::Mul, Op::Div], Self::power)
+    }
+
+    fn power(&mut self) -> Result<Expr> {
+        self.binary(&[Op::Pow], Self::percent)
+    }
+
+    fn percent(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&TokenKind::Percent) {
+            self.pos += 1;
+            expr = Expr::Percent(Box::new(expr));
+        }
+
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(TokenKind::Op(Op::Sub)) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.nested(Self::unary)?)))
+            }
+            Some(TokenKind::Op(Op::Add)) => {
+                self.pos += 1;
+                self.nested(Self::unary)
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let expr = match self.next() {
+            Some(TokenKind::Number(n)) => Expr::Number(n),
+            Some(TokenKind::Text(s)) => Expr::Text(s),
+            Some(TokenKind::Bool(b)) => Expr::Bool(b),
+            Some(TokenKind::Error(e)) => Expr::Error(e),
+            Some(TokenKind::Reference(r)) => Expr::Reference(r),
+            Some(TokenKind::Name(n)) => Expr::Name(n),
+            Some(TokenKind::Function(name)) => {
+                self.expect(TokenKind::LParen)?;
+                Expr::Call(name, self.nested(Self::arguments)?)
+            }
+            Some(TokenKind::LParen) => {
+                let expr = self.nested(Self::comparison)?;
+                self.expect(TokenKind::RParen)?;
+                expr
+            }
+            other => bail!("Unexpected token in formula: {other:?}"),
+        };
+
+        Ok(expr)
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>> {
+        let mut args = Vec::new();
+
+        if self.peek() == Some(&TokenKind::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+
+        loop {
+            match self.peek() {
+                Some(TokenKind::Sep) | Some(TokenKind::RParen) => args.push(Expr::Missing),
+                _ => args.push(self.comparison()?),
+            }
+
+            match self.next() {
+                Some(TokenKind::Sep) => continue,
+                Some(TokenKind::RParen) => return Ok(args),
+                other => bail!("Expected ',' or ')', found {other:?}"),
+            }
+        }
+    }
+}
+
+/// Разбор формулы в синтаксическое дерево
+pub(crate) fn parse(formula: &str) -> Result<Expr> {
+    let tokens = tokenize(formula)?;
+    if tokens.is_empty() {
+        bail!("Empty formula");
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.comparison()?;
+
+    if parser.pos < parser.tokens.len() {
+        bail!(
+            "Unexpected token at the end of formula: {:?}",
+            parser.peek()
+        );
+    }
+
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn num(n: f64) -> Box<Expr> {
+        Box::new(Expr::Number(n))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("=1+2*3").unwrap(),
+            Expr::Binary(
+                Op::Add,
+                num(1.0),
+                Box::new(Expr::Binary(Op::Mul, num(2.0), num(3.0)))
+            )
+        );
+        // Унарный минус приоритетнее возведения в степень, как в Excel
+        assert_eq!(
+            parse("-2^2").unwrap(),
+            Expr::Binary(Op::Pow, Box::new(Expr::Neg(num(2.0))), num(2.0))
+        );
+        assert_eq!(
+            parse("1&2=\"12\"").unwrap(),
+            Expr::Binary(
+                Op::Eq,
+                Box::new(Expr::Binary(Op::Concat, num(1.0), num(2.0))),
+                Box::new(Expr::Text("12".to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn function_calls() {
+        assert_eq!(
+            parse("IF(TRUE,,1)").unwrap(),
+            Expr::Call(
+                "IF".to_string(),
+                vec![Expr::Bool(true), Expr::Missing, Expr::Number(1.0)]
+            )
+        );
+        assert_eq!(parse("PI()").unwrap(), Expr::Call("PI".to_string(), vec![]));
+    }
+
+    #[test]
+    fn invalid_formulas() {
+        assert!(parse("").is_err());
+        assert!(parse("=1+").is_err());
+        assert!(parse("SUM(1,2").is_err());
+        assert!(parse("(1+2))").is_err());
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |open: &str, n: usize| format!("={}1{}", open.repeat(n), ")".repeat(n));
+
+        assert!(parse(&nested("(", MAX_NESTING)).is_ok());
+        assert!(parse(&nested("ABS(", MAX_NESTING)).is_ok());
+        assert!(parse(&nested("(", MAX_NESTING + 1)).is_err());
+        assert!(parse(&nested("(", 20_000)).is_err());
+        assert!(parse(&format!("={}1", "-".repeat(50_000))).is_err());
+        assert!(parse("=--A1").is_ok());
+    }
+}
@@ -0,0 +1,552 @@
+use super::lexer::{CellAddr, RefKind, TokenKind, parse_ref_kind, tokenize};
+use crate::{MAX_COL, MAX_ROW, utils::index_to_alpha};
+
+/// Направление изменения структуры листа.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Axis {
+    Row,
+    Col,
+}
+
+/// Вид изменения структуры листа.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShiftKind {
+    Insert,
+    Delete,
+}
+
+/// Вставка или удаление строк/колонок листа, влияющие на координаты и ссылки в формулах.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Shift<'a> {
+    pub kind: ShiftKind,
+    pub axis: Axis,
+    pub idx: u32,
+    pub amount: u32,
+    /// Имя измененного листа для ссылок вида `Лист!A1`
+    pub sheet: Option<&'a str>,
+    /// Относятся ли ссылки без имени листа к измененному листу
+    pub local: bool,
+}
+
+impl Shift<'_> {
+    /// Сдвиг отрезка `start..=end` по оси. `None` - отрезок удален целиком.
+    pub(crate) fn span(&self, start: u32, end: u32) -> Option<(u32, u32)> {
+        let (start, end) = (start.min(end), start.max(end));
+
+        if self.kind == ShiftKind::Insert {
+            // Вставка внутри отрезка расширяет его, как в Excel
+            let max = match self.axis {
+                Axis::Row => MAX_ROW,
+                Axis::Col => MAX_COL as u32,
+            };
+            let shift = |pos: u32| {
+                if pos >= self.idx {
+                    pos.saturating_add(self.amount)
+                } else {
+                    pos
+                }
+            };
+
+            let (start, end) = (shift(start), shift(end));
+            if start > max {
+                return None;
+            }
+
+            return Some((start, end.min(max)));
+        }
+
+        let last = self.idx + self.amount - 1;
+
+        if end < self.idx {
+            return Some((start, end));
+        }
+        if start > last {
+            return Some((start - self.amount, end - self.amount));
+        }
+        if start >= self.idx && end <= last {
+            return None;
+        }
+
+        let new_start = start.min(self.idx);
+        let new_end = if end > last {
+            end - self.amount
+        } else {
+            self.idx - 1
+        };
+
+        Some((new_start, new_end))
+    }
+
+    /// Новая позиция строки или колонки. `None` - позиция удалена.
+    #[inline]
+    pub(crate) fn position(&self, pos: u32) -> Option<u32> {
+        self.span(pos, pos).map(|(p, _)| p)
+    }
+
+    /// Новые координаты диапазона ячеек
+    fn cells(&self, start: &CellAddr, end: &CellAddr) -> Option<(CellAddr, CellAddr)> {
+        let (mut start, mut end) = (start.clone(), end.clone());
+
+        match self.axis {
+            Axis::Row => {
+                let (s, e) = self.span(start.row, end.row)?;
+                start.row = s;
+                end.row = e;
+            }
+            Axis::Col => {
+                let (s, e) = self.span(start.col as u32, end.col as u32)?;
+                start.col = s as u16;
+                end.col = e as u16;
+            }
+        }
+
+        Some((start, end))
+    }
+
+    /// Новая ссылка после изменения структуры листа
+    fn apply(&self, kind: &RefKind) -> Option<RefKind> {
+        match kind {
+            RefKind::Cell(addr) => self.cells(addr, addr).map(|(a, _)| RefKind::Cell(a)),
+            RefKind::Range(start, end) => self.cells(start, end).map(|(s, e)| RefKind::Range(s, e)),
+            RefKind::Cols {
+                start,
+                end,
+                start_abs,
+                end_abs,
+            } if self.axis == Axis::Col => {
+                self.span(*start as u32, *end as u32)
+                    .map(|(s, e)| RefKind::Cols {
+                        start: s as u16,
+                        end: e as u16,
+                        start_abs: *start_abs,
+                        end_abs: *end_abs,
+                    })
+            }
+            RefKind::Rows {
+                start,
+                end,
+                start_abs,
+                end_abs,
+            } if self.axis == Axis::Row => self.span(*start, *end).map(|(s, e)| RefKind::Rows {
+                start: s,
+                end: e,
+                start_abs: *start_abs,
+                end_abs: *end_abs,
+            }),
+            kind => Some(kind.clone()),
+        }
+    }
+
+    /// Новая ссылка формулы, заданной относительно левой верхней ячейки диапазона.
+    /// Абсолютные части пересчитываются как обычно, относительные сохраняют
+    /// смещение от левой верхней ячейки, которая сдвинулась на `delta`.
+    fn apply_anchored(&self, kind: &RefKind, delta: i64) -> Option<RefKind> {
+        let max = match self.axis {
+            Axis::Row => MAX_ROW,
+            Axis::Col => MAX_COL as u32,
+        };
+        let moved = |pos: u32, abs: bool| {
+            if abs {
+                return self.position(pos);
+            }
+
+            let pos = pos as i64 + delta;
+            (1..=max as i64).contains(&pos).then_some(pos as u32)
+        };
+        let span = |(start, start_abs): (u32, bool), (end, end_abs): (u32, bool)| {
+            if start_abs && end_abs {
+                self.span(start, end)
+            } else {
+                Some((moved(start, start_abs)?, moved(end, end_abs)?))
+            }
+        };
+
+        let mut kind = kind.clone();
+        match (&mut kind, self.axis) {
+            (RefKind::Cell(addr), Axis::Row) => addr.row = moved(addr.row, addr.row_abs)?,
+            (RefKind::Cell(addr), Axis::Col) => {
+                addr.col = moved(addr.col as u32, addr.col_abs)? as u16;
+            }
+            (RefKind::Range(start, end), Axis::Row) => {
+                (start.row, end.row) = span((start.row, start.row_abs), (end.row, end.row_abs))?;
+            }
+            (RefKind::Range(start, end), Axis::Col) => {
+                let (s, e) = span(
+                    (start.col as u32, start.col_abs),
+                    (end.col as u32, end.col_abs),
+                )?;
+                (start.col, end.col) = (s as u16, e as u16);
+            }
+            (
+                RefKind::Cols {
+                    start,
+                    end,
+                    start_abs,
+                    end_abs,
+                },
+                Axis::Col,
+            ) => {
+                let (s, e) = span((*start as u32, *start_abs), (*end as u32, *end_abs))?;
+                (*start, *end) = (s as u16, e as u16);
+            }
+            (
+                RefKind::Rows {
+                    start,
+                    end,
+                    start_abs,
+                    end_abs,
+                },
+                Axis::Row,
+            ) => (*start, *end) = span((*start, *start_abs), (*end, *end_abs))?,
+            _ => {}
+        }
+
+        Some(kind)
+    }
+
+    /// Относится ли ссылка к измененному листу
+    fn affects(&self, sheet: Option<&str>) -> bool {
+        match (sheet, self.sheet) {
+            (None, _) => self.local,
+            (Some(name), Some(target)) => name.to_lowercase() == target.to_lowercase(),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+fn abs(flag: bool) -> &'static str {
+    if flag { "$" } else { "" }
+}
+
+fn render_addr(addr: &CellAddr) -> String {
+    format!(
+        "{}{}{}{}",
+        abs(addr.col_abs),
+        index_to_alpha(addr.col),
+        abs(addr.row_abs),
+        addr.row
+    )
+}
+
+/// Запись ссылки в стиле A1
+pub(crate) fn render_ref(kind: &RefKind) -> String {
+    match kind {
+        RefKind::Cell(addr) => render_addr(addr),
+        RefKind::Range(start, end) => format!("{}:{}", render_addr(start), render_addr(end)),
+        RefKind::Cols {
+            start,
+            end,
+            start_abs,
+            end_abs,
+        } => format!(
+            "{}{}:{}{}",
+            abs(*start_abs),
+            index_to_alpha(*start),
+            abs(*end_abs),
+            index_to_alpha(*end)
+        ),
+        RefKind::Rows {
+            start,
+            end,
+            start_abs,
+            end_abs,
+        } => format!("{}{start}:{}{end}", abs(*start_abs), abs(*end_abs)),
+    }
+}
+
+/// Пересчет ссылок формулы после вставки или удаления строк или колонок.
+/// Возвращает новую формулу, если она изменилась.
+/// Ссылки на удаленные ячейки заменяются на `#REF!`, как в Excel.
+pub(crate) fn shift_formula(formula: &str, shift: &Shift) -> Option<String> {
+    if shift.amount == 0 {
+        return None;
+    }
+
+    rewrite_refs(
+        formula,
+        |sheet| shift.affects(sheet),
+        |kind| shift.apply(kind),
+    )
+}
+
+/// Пересчет ссылок формулы условного форматирования или проверки данных,
+/// где относительные ссылки заданы от левой верхней ячейки диапазона.
+/// `anchor` и `new_anchor` - левая верхняя ячейка (row, col) до и после изменения.
+pub(crate) fn shift_anchored_formula(
+    formula: &str,
+    shift: &Shift,
+    anchor: (u32, u16),
+    new_anchor: (u32, u16),
+) -> Option<String> {
+    if shift.amount == 0 {
+        return None;
+    }
+
+    let delta = match shift.axis {
+        Axis::Row => new_anchor.0 as i64 - anchor.0 as i64,
+        Axis::Col => new_anchor.1 as i64 - anchor.1 as i64,
+    };
+
+    rewrite_refs(
+        formula,
+        |sheet| shift.affects(sheet),
+        |kind| shift.apply_anchored(kind, delta),
+    )
+}
+
+/// Перенос формулы на `rows` строк и `cols` колонок, как при копировании ячейки:
+/// относительные ссылки сдвигаются, абсолютные остаются на месте.
+/// Возвращает новую формулу, если она изменилась.
+pub(crate) fn offset_formula(formula: &str, rows: i64, cols: i64) -> Option<String> {
+    // Пустой сдвиг не меняет абсолютные части ссылок
+    let keep = |axis| Shift {
+        kind: ShiftKind::Insert,
+        axis,
+        idx: 1,
+        amount: 0,
+        sheet: None,
+        local: true,
+    };
+    let (by_row, by_col) = (keep(Axis::Row), keep(Axis::Col));
+
+    rewrite_refs(
+        formula,
+        |_| true,
+        |kind| {
+            by_row
+                .apply_anchored(kind, rows)
+                .and_then(|kind| by_col.apply_anchored(&kind, cols))
+        },
+    )
+}
+
+/// Замена ссылок формулы на листы, для которых `affects` возвращает `true`.
+/// `apply` возвращает новую ссылку, `None` - ссылка удалена.
+fn rewrite_refs<A, F>(formula: &str, affects: A, apply: F) -> Option<String>
+where
+    A: Fn(Option<&str>) -> bool,
+    F: Fn(&RefKind) -> Option<RefKind>,
+{
+    // Формулы, которые не удалось разобрать, оставляем как есть
+    let tokens = tokenize(formula).ok()?;
+
+    let mut out = String::with_capacity(formula.len());
+    let mut last = 0;
+    let mut changed = false;
+
+    for token in &tokens {
+        let TokenKind::Reference(reference) = &token.kind else {
+            continue;
+        };
+        if !affects(reference.sheet.as_deref()) {
+            continue;
+        }
+
+        let text = &formula[token.start..token.end];
+        let prefix = text.rsplit_once('!').map(|(sheet, _)| sheet);
+
+        let body = match apply(&reference.kind) {
+            Some(kind) => render_ref(&kind),
+            None => "#REF!".to_string(),
+        };
+        let new_text = match prefix {
+            Some(sheet) => format!("{sheet}!{body}"),
+            None => body,
+        };
+
+        if new_text != text {
+            out.push_str(&formula[last..token.start]);
+            out.push_str(&new_text);
+            last = token.end;
+            changed = true;
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+
+    out.push_str(&formula[last..]);
+    Some(out)
+}
+
+/// Имя листа для ссылки в формуле, в кавычках при необходимости
+pub(crate) fn sheet_prefix(name: &str) -> String {
+    let plain = name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && parse_ref_kind(name).is_none();
+
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+/// Замена имени листа в ссылках формулы после переименования листа.
+/// Возвращает новую формулу, если она изменилась.
+pub(crate) fn rename_sheet(formula: &str, old: &str, new: &str) -> Option<String> {
+    let tokens = tokenize(formula).ok()?;
+    let old = old.to_lowercase();
+    let prefix = sheet_prefix(new);
+
+    let mut out = String::with_capacity(formula.len());
+    let mut last = 0;
+
+    for token in &tokens {
+        let TokenKind::Reference(reference) = &token.kind else {
+            continue;
+        };
+        if reference.sheet.as_ref().map(|s| s.to_lowercase()) != Some(old.clone()) {
+            continue;
+        }
+
+        let text = &formula[token.start..token.end];
+        let Some((_, body)) = text.rsplit_once('!') else {
+            continue;
+        };
+
+        out.push_str(&formula[last..token.start]);
+        out.push_str(&format!("{prefix}!{body}"));
+        last = token.end;
+    }
+
+    if last == 0 {
+        return None;
+    }
+
+    out.push_str(&formula[last..]);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(formula: &str, idx: u32, amount: u32) -> String {
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx,
+            amount,
+            sheet: Some("Лист"),
+            local: true,
+        };
+
+        shift_formula(formula, &shift).unwrap_or_else(|| formula.to_string())
+    }
+
+    fn cols(formula: &str, idx: u32, amount: u32) -> String {
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Col,
+            idx,
+            amount,
+            sheet: Some("Лист"),
+            local: true,
+        };
+
+        shift_formula(formula, &shift).unwrap_or_else(|| formula.to_string())
+    }
+
+    #[test]
+    fn delete_rows() {
+        assert_eq!(rows("=A1+A5", 2, 2), "=A1+A3");
+        assert_eq!(rows("=A2*2", 2, 2), "=#REF!*2");
+        assert_eq!(rows("=SUM(A1:A10)", 2, 3), "=SUM(A1:A7)");
+        assert_eq!(rows("=SUM(A3:A4)", 2, 3), "=SUM(#REF!)");
+        assert_eq!(rows("=SUM($A$3:$B$10)", 2, 3), "=SUM($A$2:$B$7)");
+        assert_eq!(rows("=SUM(A1:A3)", 2, 5), "=SUM(A1:A1)");
+        assert_eq!(rows("=SUM(2:10)+SUM(A:A)", 1, 1), "=SUM(1:9)+SUM(A:A)");
+    }
+
+    #[test]
+    fn delete_cols() {
+        assert_eq!(cols("=C1&\" C1 \"&D1", 2, 1), "=B1&\" C1 \"&C1");
+        assert_eq!(cols("=SUM(A1:E1)", 2, 2), "=SUM(A1:C1)");
+        assert_eq!(cols("=B1", 2, 2), "=#REF!");
+        assert_eq!(cols("=SUM(C:D)", 1, 1), "=SUM(B:C)");
+    }
+
+    #[test]
+    fn insert() {
+        let shift = Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Row,
+            idx: 3,
+            amount: 2,
+            sheet: None,
+            local: true,
+        };
+
+        assert_eq!(
+            shift_formula("=A2+A3+SUM(A1:A5)+SUM(A1:A2)", &shift),
+            Some("=A2+A5+SUM(A1:A7)+SUM(A1:A2)".to_string())
+        );
+        assert_eq!(
+            shift_formula("=A1048575", &shift),
+            Some("=#REF!".to_string())
+        );
+
+        let shift = Shift {
+            axis: Axis::Col,
+            idx: 1,
+            amount: 1,
+            ..shift
+        };
+        assert_eq!(
+            shift_formula("=SUM($A1:B1)+SUM(C:C)", &shift),
+            Some("=SUM($B1:C1)+SUM(D:D)".to_string())
+        );
+    }
+
+    #[test]
+    fn sheet_references() {
+        assert_eq!(rows("=Лист!A5+'Лист'!A5", 1, 1), "=Лист!A4+'Лист'!A4");
+        assert_eq!(rows("=Другой!A5+A5", 1, 1), "=Другой!A5+A4");
+        assert_eq!(rows("=Лист!A1", 1, 1), "=Лист!#REF!");
+
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx: 1,
+            amount: 1,
+            sheet: Some("Лист"),
+            local: false,
+        };
+        assert_eq!(
+            shift_formula("=A5+лист!A5", &shift),
+            Some("=A5+лист!A4".to_string())
+        );
+        assert_eq!(shift_formula("=SUM(A1", &shift), None);
+    }
+
+    #[test]
+    fn rename_sheets() {
+        assert_eq!(
+            rename_sheet("=SUM(Лист!A1:A3)+'лист'!B1+A1", "Лист", "Отчет 2024").unwrap(),
+            "=SUM('Отчет 2024'!A1:A3)+'Отчет 2024'!B1+A1"
+        );
+        assert_eq!(
+            rename_sheet("='Old'!$A$1", "old", "New").unwrap(),
+            "=New!$A$1"
+        );
+        assert_eq!(rename_sheet("=Другой!A1", "Лист", "New"), None);
+        assert_eq!(sheet_prefix("A1"), "'A1'");
+        assert_eq!(sheet_prefix("O'Neil"), "'O''Neil'");
+    }
+
+    #[test]
+    fn offset_formulas() {
+        assert_eq!(
+            offset_formula("C2/$C$5+SUM(A$1:B2)", 2, 1).unwrap(),
+            "D4/$C$5+SUM(B$1:C4)"
+        );
+        assert_eq!(
+            offset_formula("'Лист 2'!A1+$A1", 0, 3).unwrap(),
+            "'Лист 2'!D1+$A1"
+        );
+        assert_eq!(offset_formula("A2-1", -2, 0).unwrap(), "#REF!-1");
+        assert_eq!(offset_formula("$A$1*2", 5, 5), None);
+    }
+}
@@ -1,8 +1,11 @@
 pub mod datatype;
+pub mod formula;
 pub mod funcs;
+pub mod numfmt;
 pub mod structs;
 pub mod traits;
 pub mod utils;
+pub mod xlsx;
 
 /// Функция для получения версии.
 pub fn version() -> &'static str {
@@ -11,6 +14,10 @@ pub fn version() -> &'static str {
 
 const MAX_COL: u16 = 16_384;
 const MAX_ROW: u32 = 1_048_576;
+const MAX_OUTLINE_LEVEL: u32 = 7;
+// Каждая строка группы хранится отдельно, поэтому длина группы строк ограничена
+const MAX_OUTLINE_ROWS: u32 = 65_536;
+const MAX_SHEET_NAME_LEN: usize = 31;
 
 // Стандартные размеры как в Excel
 pub const DEFAULT_COLUMN_WIDTH: f64 = 22.5; // ~ 140 пикселей
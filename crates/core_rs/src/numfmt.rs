@@ -0,0 +1,1244 @@
+use chrono::{Datelike, Timelike};
+
+use crate::{
+    datatype::CellRawValue,
+    formula::eval::round_significant,
+    utils::{datetime_to_excel_serial, excel_serial_to_datetime},
+};
+
+const MONTHS_EN: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const MONTHS_RU: [&str; 12] = [
+    "Январь",
+    "Февраль",
+    "Март",
+    "Апрель",
+    "Май",
+    "Июнь",
+    "Июль",
+    "Август",
+    "Сентябрь",
+    "Октябрь",
+    "Ноябрь",
+    "Декабрь",
+];
+/// Названия месяцев в родительном падеже, например `1 января 2024`
+const MONTHS_RU_GENITIVE: [&str; 12] = [
+    "января",
+    "февраля",
+    "марта",
+    "апреля",
+    "мая",
+    "июня",
+    "июля",
+    "августа",
+    "сентября",
+    "октября",
+    "ноября",
+    "декабря",
+];
+const MONTHS_RU_SHORT: [&str; 12] = [
+    "янв", "фев", "мар", "апр", "май", "июн", "июл", "авг", "сен", "окт", "ноя", "дек",
+];
+const DAYS_EN: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const DAYS_RU: [&str; 7] = [
+    "воскресенье",
+    "понедельник",
+    "вторник",
+    "среда",
+    "четверг",
+    "пятница",
+    "суббота",
+];
+const DAYS_RU_SHORT: [&str; 7] = ["Вс", "Пн", "Вт", "Ср", "Чт", "Пт", "Сб"];
+
+/// Первый день после 31.12.9999 - наибольшей даты Excel.
+const MAX_DATE_SERIAL: f64 = 2_958_466.0;
+
+/// Элемент секции числового формата.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    /// Место под цифру: `0`, `#` или `?`
+    Digit(char),
+    Point,
+    Comma,
+    /// Разделитель групп разрядов
+    Group,
+    /// Деление на 1000 (запятая в конце целой части)
+    Scale,
+    Percent,
+    /// Экспонента, `true` для `E+`
+    Exponent(bool),
+    Text,
+    General,
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    SubSecond(usize),
+    /// `AM/PM` или `A/P`
+    AmPm(bool),
+    /// Прошедшее время: `[h]`, `[mm]`, `[ss]`
+    Elapsed(char, usize),
+}
+
+impl Token {
+    fn is_date(&self) -> bool {
+        matches!(
+            self,
+            Token::Year(_)
+                | Token::Month(_)
+                | Token::Day(_)
+                | Token::Hour(_)
+                | Token::Minute(_)
+                | Token::Second(_)
+                | Token::SubSecond(_)
+                | Token::AmPm(_)
+                | Token::Elapsed(..)
+        )
+    }
+}
+
+/// Условие секции формата, например `[>=100]`.
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    op: String,
+    value: f64,
+}
+
+impl Condition {
+    fn matches(&self, v: f64) -> bool {
+        match self.op.as_str() {
+            "<" => v < self.value,
+            "<=" => v <= self.value,
+            ">" => v > self.value,
+            ">=" => v >= self.value,
+            "<>" => v != self.value,
+            _ => v == self.value,
+        }
+    }
+
+    /// Условие описывает только отрицательные числа, знак выводить не нужно
+    fn is_negative(&self) -> bool {
+        matches!(self.op.as_str(), "<" | "<=") && self.value <= 0.0
+    }
+}
+
+/// Секция формата (формат разделяется на секции символом `;`).
+#[derive(Clone, Debug, Default)]
+struct Section {
+    tokens: Vec<Token>,
+    condition: Option<Condition>,
+    russian: bool,
+    decimal_sep: char,
+    group_sep: char,
+}
+
+impl Section {
+    fn is_date(&self) -> bool {
+        self.tokens.iter().any(Token::is_date)
+    }
+
+    fn has_text(&self) -> bool {
+        self.tokens.contains(&Token::Text)
+    }
+
+    /// Позиция `/` дроби, например в `# ?/?` или `?/8`
+    fn fraction_slash(&self) -> Option<usize> {
+        let is_fixed = |t: Option<&Token>| matches!(t, Some(Token::Literal(l)) if l.chars().all(|c| c.is_ascii_digit()));
+
+        self.tokens.iter().enumerate().position(|(i, t)| {
+            *t == Token::Literal("/".to_string())
+                && i > 0
+                && matches!(self.tokens[i - 1], Token::Digit(_))
+                && (matches!(self.tokens.get(i + 1), Some(Token::Digit(_)))
+                    || is_fixed(self.tokens.get(i + 1)))
+        })
+    }
+}
+
+/// Разделение формата на секции с учетом кавычек и экранирования
+fn split_sections(code: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut in_quotes = false;
+    let mut chars = code.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '\\' | '_' | '*' if !in_quotes => {
+                let last = sections.last_mut().unwrap();
+                last.push(ch);
+                if let Some(next) = chars.next() {
+                    last.push(next);
+                }
+                continue;
+            }
+            ';' if !in_quotes => {
+                sections.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+
+        sections.last_mut().unwrap().push(ch);
+    }
+
+    sections
+}
+
+/// Разбор содержимого квадратных скобок
+fn parse_bracket(content: &str, section: &mut Section) {
+    let lower = content.to_lowercase();
+
+    if let Some(op_end) = content.find(|c: char| !matches!(c, '<' | '>' | '='))
+        && op_end > 0
+    {
+        if let Ok(value) = content[op_end..].trim().parse::<f64>() {
+            section.condition = Some(Condition {
+                op: content[..op_end].to_string(),
+                value,
+            });
+        }
+        return;
+    }
+
+    if let Some(currency) = content.strip_prefix('$') {
+        let (symbol, locale) = currency.split_once('-').unwrap_or((currency, ""));
+        if !symbol.is_empty() {
+            section.tokens.push(Token::Literal(symbol.to_string()));
+        }
+
+        let locale = locale.to_lowercase();
+        if locale.ends_with("419") || locale == "ru-ru" || locale == "ru" {
+            section.russian = true;
+        }
+        return;
+    }
+
+    if !lower.is_empty() && lower.chars().all(|c| c == lower.chars().next().unwrap()) {
+        let ch = lower.chars().next().unwrap();
+        if matches!(ch, 'h' | 'm' | 's') {
+            section
+                .tokens
+                .push(Token::Elapsed(ch, lower.chars().count()));
+        }
+    }
+
+    // Цвета ([Red], [Color10]) на отображаемый текст не влияют
+}
+
+/// Разбор одной секции формата
+fn parse_section(code: &str) -> Section {
+    let mut section = Section {
+        decimal_sep: '.',
+        group_sep: ',',
+        ..Default::default()
+    };
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+
+    let count_same = |i: usize, lower: char| {
+        chars[i..]
+            .iter()
+            .take_while(|c| c.to_ascii_lowercase() == lower)
+            .count()
+    };
+    let starts_with = |i: usize, pattern: &str| {
+        let rest: String = chars[i..].iter().take(pattern.chars().count()).collect();
+        rest.eq_ignore_ascii_case(pattern)
+    };
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        match ch {
+            '"' => {
+                let text: String = chars[i + 1..].iter().take_while(|c| **c != '"').collect();
+                i += text.chars().count() + 2;
+                section.tokens.push(Token::Literal(text));
+                continue;
+            }
+            '\\' => {
+                if let Some(next) = chars.get(i + 1) {
+                    section.tokens.push(Token::Literal(next.to_string()));
+                }
+                i += 2;
+                continue;
+            }
+            '_' => {
+                section.tokens.push(Token::Literal(" ".to_string()));
+                i += 2;
+                continue;
+            }
+            '*' => {
+                // Заполнение ширины ячейки повторяющимся символом не отображаем
+                i += 2;
+                continue;
+            }
+            '[' => {
+                let content: String = chars[i + 1..].iter().take_while(|c| **c != ']').collect();
+                i += content.chars().count() + 2;
+                parse_bracket(&content, &mut section);
+                continue;
+            }
+            '0' | '#' | '?' => section.tokens.push(Token::Digit(ch)),
+            '.' => {
+                let zeros = count_same(i + 1, '0');
+                let after_seconds = section
+                    .tokens
+                    .iter()
+                    .rev()
+                    .find(|t| t.is_date())
+                    .is_some_and(|t| matches!(t, Token::Second(_) | Token::Elapsed('s', _)));
+
+                if after_seconds && zeros > 0 {
+                    section.tokens.push(Token::Literal(".".to_string()));
+                    section.tokens.push(Token::SubSecond(zeros));
+                    i += zeros + 1;
+                    continue;
+                }
+                section.tokens.push(Token::Point);
+            }
+            ',' => section.tokens.push(Token::Comma),
+            '%' => section.tokens.push(Token::Percent),
+            '@' => section.tokens.push(Token::Text),
+            'E' | 'e' if matches!(chars.get(i + 1), Some('+') | Some('-')) => {
+                section.tokens.push(Token::Exponent(chars[i + 1] == '+'));
+                i += 2;
+                continue;
+            }
+            _ if starts_with(i, "general") => {
+                section.tokens.push(Token::General);
+                i += 7;
+                continue;
+            }
+            _ if starts_with(i, "am/pm") => {
+                section.tokens.push(Token::AmPm(false));
+                i += 5;
+                continue;
+            }
+            _ if starts_with(i, "a/p") => {
+                section.tokens.push(Token::AmPm(true));
+                i += 3;
+                continue;
+            }
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                let lower = ch.to_ascii_lowercase();
+                let n = count_same(i, lower);
+                let token = match lower {
+                    'y' => Token::Year(n),
+                    'm' => Token::Month(n),
+                    'd' => Token::Day(n),
+                    'h' => Token::Hour(n),
+                    _ => Token::Second(n),
+                };
+                section.tokens.push(token);
+                i += n;
+                continue;
+            }
+            _ => section.tokens.push(Token::Literal(ch.to_string())),
+        }
+
+        i += 1;
+    }
+
+    resolve_minutes(&mut section.tokens);
+    resolve_separators(&mut section);
+
+    section
+}
+
+/// `m` и `mm` после часов или перед секундами означают минуты
+fn resolve_minutes(tokens: &mut [Token]) {
+    let dates: Vec<usize> = (0..tokens.len()).filter(|i| tokens[*i].is_date()).collect();
+
+    for (pos, &idx) in dates.iter().enumerate() {
+        let Token::Month(n) = tokens[idx] else {
+            continue;
+        };
+        if n > 2 {
+            continue;
+        }
+
+        let after_hour = pos > 0
+            && matches!(
+                tokens[dates[pos - 1]],
+                Token::Hour(_) | Token::Elapsed('h', _)
+            );
+        let before_second = dates
+            .get(pos + 1)
+            .is_some_and(|i| matches!(tokens[*i], Token::Second(_) | Token::Elapsed('s', _)));
+
+        if after_hour || before_second {
+            tokens[idx] = Token::Minute(n);
+        }
+    }
+}
+
+/// Определение роли запятых и пробелов в числовой секции
+fn resolve_separators(section: &mut Section) {
+    let tokens = &mut section.tokens;
+    let is_digit = |t: Option<&Token>| matches!(t, Some(Token::Digit(_)));
+
+    // Формат вида `# ##0,00`: запятая - десятичный разделитель, если перед ней
+    // есть группировка пробелом или после нее не ровно три знака, как в `#,##0`
+    let is_space = |t: &Token| matches!(t, Token::Literal(s) if s == " " || s == "\u{a0}");
+    let has_point = tokens.contains(&Token::Point);
+    let decimal_comma = (!has_point)
+        .then(|| {
+            (1..tokens.len()).rfind(|&i| {
+                if tokens[i] != Token::Comma || !is_digit(tokens.get(i + 1)) {
+                    return false;
+                }
+
+                let before = &tokens[..i];
+                if !before.iter().any(|t| matches!(t, Token::Digit(_))) {
+                    return false;
+                }
+
+                let space_group = (1..i).any(|j| {
+                    is_space(&tokens[j])
+                        && is_digit(tokens.get(j - 1))
+                        && is_digit(tokens.get(j + 1))
+                });
+                let after = tokens[i + 1..]
+                    .iter()
+                    .take_while(|t| matches!(t, Token::Digit(_)))
+                    .count();
+
+                space_group || after != 3
+            })
+        })
+        .flatten();
+
+    if let Some(idx) = decimal_comma {
+        section.decimal_sep = ',';
+        tokens[idx] = Token::Point;
+    }
+
+    for i in 0..tokens.len() {
+        if tokens[i] != Token::Comma {
+            continue;
+        }
+
+        let after_digit = i > 0 && matches!(tokens[i - 1], Token::Digit(_) | Token::Scale);
+        tokens[i] = if decimal_comma.is_some() {
+            Token::Literal(",".to_string())
+        } else if after_digit && is_digit(tokens.get(i + 1)) {
+            Token::Group
+        } else if after_digit {
+            Token::Scale
+        } else {
+            Token::Literal(",".to_string())
+        };
+    }
+
+    // Пробел между цифрами целой части - разделитель разрядов (`# ##0`),
+    // но не в дробях вида `# ?/?`
+    let fraction = tokens.contains(&Token::Literal("/".to_string()));
+    if !tokens.contains(&Token::Group) && !fraction {
+        let int_end = tokens
+            .iter()
+            .position(|t| matches!(t, Token::Point | Token::Exponent(_)))
+            .unwrap_or(tokens.len());
+
+        for i in 1..int_end {
+            if is_space(&tokens[i]) && is_digit(tokens.get(i - 1)) && is_digit(tokens.get(i + 1)) {
+                tokens[i] = Token::Group;
+                section.group_sep = ' ';
+            }
+        }
+    }
+}
+
+/// Округление неотрицательного числа до `decimals` знаков после запятой,
+/// как в Excel: по 15 значащим цифрам и половина вверх.
+/// Возвращает цифры целой части (без ведущих нулей) и дробной части.
+fn round_decimal(v: f64, decimals: usize) -> (String, String) {
+    if v == 0.0 || !v.is_finite() {
+        return (String::new(), "0".repeat(decimals));
+    }
+
+    let sci = format!("{v:.14e}");
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+
+    let mut digits: Vec<u8> = mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|b| b - b'0')
+        .collect();
+
+    let mut point = exp + 1;
+    if point <= 0 {
+        let pad = (1 - point) as usize;
+        digits.splice(0..0, std::iter::repeat_n(0, pad));
+        point = 1;
+    }
+    let mut point = point as usize;
+
+    let keep = point + decimals;
+    if digits.len() > keep {
+        let round_up = digits[keep] >= 5;
+        digits.truncate(keep);
+
+        if round_up {
+            let mut i = keep;
+            loop {
+                if i == 0 {
+                    digits.insert(0, 1);
+                    point += 1;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == 9 {
+                    digits[i] = 0;
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+    } else {
+        digits.resize(keep, 0);
+    }
+
+    let to_str = |d: &[u8]| d.iter().map(|d| (b'0' + d) as char).collect::<String>();
+    let int = to_str(&digits[..point]).trim_start_matches('0').to_string();
+
+    (int, to_str(&digits[point..]))
+}
+
+/// Отображение числа в общем формате (General)
+fn general(v: f64) -> String {
+    let abs = v.abs();
+    if v == 0.0 {
+        return "0".to_string();
+    }
+    if !v.is_finite() {
+        return "#NUM!".to_string();
+    }
+
+    if !(1e-9..1e11).contains(&abs) {
+        let exp = abs.log10().floor() as i32;
+        let (int, frac) = round_decimal(abs / 10f64.powi(exp), 5);
+        // Мантисса могла округлиться до 10
+        let (exp, int, frac) = if int.len() > 1 {
+            let (int, frac) = round_decimal(abs / 10f64.powi(exp + 1), 5);
+            (exp + 1, int, frac)
+        } else {
+            (exp, int, frac)
+        };
+
+        let frac = frac.trim_end_matches('0');
+        let sign = if v < 0.0 { "-" } else { "" };
+        let exp_sign = if exp < 0 { '-' } else { '+' };
+        let point = if frac.is_empty() { "" } else { "." };
+
+        return format!("{sign}{int}{point}{frac}E{exp_sign}{:02}", exp.abs());
+    }
+
+    // Не более 10 значащих цифр, как в ячейке стандартной ширины
+    let rounded: f64 = format!("{v:.9e}").parse().unwrap_or(v);
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        rounded.to_string()
+    }
+}
+
+/// Форматирование числа по числовой секции
+fn format_number(section: &Section, v: f64) -> String {
+    let tokens = &section.tokens;
+    let (decimal_sep, group_sep) = (section.decimal_sep, section.group_sep);
+
+    let mut v = v;
+    for token in tokens {
+        match token {
+            Token::Percent => v *= 100.0,
+            Token::Scale => v /= 1000.0,
+            _ => {}
+        }
+    }
+
+    let exp_pos = tokens.iter().position(|t| matches!(t, Token::Exponent(_)));
+    let number_end = exp_pos.unwrap_or(tokens.len());
+    let point_pos = tokens[..number_end].iter().position(|t| *t == Token::Point);
+
+    let int_end = point_pos.unwrap_or(number_end);
+    let int_tokens = &tokens[..int_end];
+    let frac_tokens = point_pos.map_or(&tokens[0..0], |p| &tokens[p + 1..number_end]);
+    let exp_tokens = exp_pos.map_or(&tokens[0..0], |p| &tokens[p + 1..]);
+
+    let frac_count = frac_tokens
+        .iter()
+        .filter(|t| matches!(t, Token::Digit(_)))
+        .count();
+    let group = int_tokens.contains(&Token::Group);
+
+    let (int_digits, frac_digits, exponent) = match exp_pos {
+        Some(_) => {
+            let int_count = int_tokens
+                .iter()
+                .filter(|t| matches!(t, Token::Digit(_)))
+                .count()
+                .max(1) as i32;
+            let mut exp = if v == 0.0 {
+                0
+            } else {
+                v.log10().floor() as i32
+            };
+            exp -= exp.rem_euclid(int_count);
+
+            let (mut int, mut frac) = round_decimal(v / 10f64.powi(exp), frac_count);
+            if int.len() as i32 > int_count {
+                exp += int_count;
+                (int, frac) = round_decimal(v / 10f64.powi(exp), frac_count);
+            }
+
+            (int, frac, Some(exp))
+        }
+        None => {
+            let (int, frac) = round_decimal(v, frac_count);
+            (int, frac, None)
+        }
+    };
+
+    let mut out = String::new();
+
+    // Целая часть заполняется справа налево
+    let mut pending: Vec<char> = int_digits.chars().collect();
+    let leftmost = int_tokens.iter().position(|t| matches!(t, Token::Digit(_)));
+    let mut pieces: Vec<String> = Vec::new();
+    let mut emitted = 0;
+
+    let mut push_digit = |piece: &mut String, c: char| {
+        if group && emitted > 0 && emitted % 3 == 0 {
+            piece.push(group_sep);
+        }
+        piece.push(c);
+        emitted += 1;
+    };
+
+    if leftmost.is_none() {
+        let mut piece = String::new();
+        while let Some(c) = pending.pop() {
+            push_digit(&mut piece, c);
+        }
+        pieces.push(piece);
+    }
+
+    for (i, token) in int_tokens.iter().enumerate().rev() {
+        let mut piece = String::new();
+
+        match token {
+            Token::Digit(p) => {
+                let take = if Some(i) == leftmost {
+                    pending.len()
+                } else {
+                    1
+                };
+
+                if pending.is_empty() {
+                    match p {
+                        '0' => push_digit(&mut piece, '0'),
+                        '?' => piece.push(' '),
+                        _ => {}
+                    }
+                } else {
+                    for _ in 0..take {
+                        if let Some(c) = pending.pop() {
+                            push_digit(&mut piece, c);
+                        }
+                    }
+                }
+            }
+            other => piece = render_literal(other, "").chars().rev().collect(),
+        }
+
+        pieces.push(piece);
+    }
+    for piece in pieces.iter().rev() {
+        out.extend(piece.chars().rev());
+    }
+
+    if point_pos.is_some() {
+        out.push(decimal_sep);
+
+        let frac: Vec<char> = frac_digits.chars().collect();
+        let placeholders: Vec<char> = frac_tokens
+            .iter()
+            .filter_map(|t| match t {
+                Token::Digit(p) => Some(*p),
+                _ => None,
+            })
+            .collect();
+
+        // Незначащие нули в конце для `#` и `?` не выводятся
+        let mut visible = frac.len();
+        while visible > 0 && frac[visible - 1] == '0' && placeholders[visible - 1] != '0' {
+            visible -= 1;
+        }
+
+        let mut idx = 0;
+        for token in frac_tokens {
+            match token {
+                Token::Digit(p) => {
+                    if idx < visible {
+                        out.push(frac[idx]);
+                    } else if *p == '?' {
+                        out.push(' ');
+                    }
+                    idx += 1;
+                }
+                other => out.push_str(&render_literal(other, "")),
+            }
+        }
+    }
+
+    if let (Some(exp), Some(pos)) = (exponent, exp_pos) {
+        let Token::Exponent(plus) = tokens[pos] else {
+            unreachable!()
+        };
+        out.push('E');
+        if exp < 0 {
+            out.push('-');
+        } else if plus {
+            out.push('+');
+        }
+
+        let width = exp_tokens
+            .iter()
+            .filter(|t| **t == Token::Digit('0'))
+            .count();
+        let mut placed = false;
+        for token in exp_tokens {
+            match token {
+                Token::Digit(_) if !placed => {
+                    out.push_str(&format!("{:0width$}", exp.abs()));
+                    placed = true;
+                }
+                Token::Digit(_) => {}
+                other => out.push_str(&render_literal(other, "")),
+            }
+        }
+    }
+
+    out
+}
+
+/// Форматирование неотрицательного числа простой дробью: `# ?/?`, `# ??/??`, `?/8`
+fn format_fraction(section: &Section, v: f64, slash: usize) -> String {
+    let tokens = &section.tokens;
+
+    // Числитель - цифры непосредственно перед `/`
+    let num_start = tokens[..slash]
+        .iter()
+        .rposition(|t| !matches!(t, Token::Digit(_)))
+        .map_or(0, |i| i + 1);
+    let num_tokens = &tokens[num_start..slash];
+    let int_tokens = &tokens[..num_start];
+    let int_end = int_tokens
+        .iter()
+        .rposition(|t| matches!(t, Token::Digit(_)))
+        .map(|i| i + 1);
+
+    // Знаменатель - заполнители или фиксированное число
+    let den_end = tokens[slash + 1..]
+        .iter()
+        .position(|t| match t {
+            Token::Digit(_) => false,
+            Token::Literal(l) => !l.chars().all(|c| c.is_ascii_digit()),
+            _ => true,
+        })
+        .map_or(tokens.len(), |i| slash + 1 + i);
+    let den_tokens = &tokens[slash + 1..den_end];
+    let den_text: String = den_tokens
+        .iter()
+        .map(|t| match t {
+            Token::Digit(c) => *c,
+            Token::Literal(l) => l.chars().next().unwrap_or('0'),
+            _ => '0',
+        })
+        .collect();
+    let fixed = den_tokens
+        .iter()
+        .any(|t| matches!(t, Token::Literal(_)))
+        .then(|| den_text.parse::<u64>().ok())
+        .flatten()
+        .filter(|d| *d > 0);
+
+    let (mut int, frac) = match int_end {
+        Some(_) => (v.trunc() as u64, v.fract()),
+        None => (0, v),
+    };
+
+    let (mut num, den) = match fixed {
+        Some(den) => ((frac * den as f64).round() as u64, den),
+        None => {
+            let max_den = 10u64.pow(den_tokens.len().min(4) as u32) - 1;
+            (1..=max_den)
+                .map(|den| ((frac * den as f64).round() as u64, den))
+                .min_by(|a, b| {
+                    let err = |(n, d): (u64, u64)| (frac - n as f64 / d as f64).abs();
+                    err(*a).total_cmp(&err(*b))
+                })
+                .unwrap_or((0, 1))
+        }
+    };
+    if int_end.is_some() && num == den {
+        int += 1;
+        num = 0;
+    }
+
+    let placeholder = |t: &Token| match t {
+        Token::Digit(c) => Some(*c),
+        _ => None,
+    };
+    let pad = |value: String, tokens: &[Token], left: bool| {
+        let width = tokens.len();
+        let fill = match tokens.iter().find_map(placeholder) {
+            Some('0') => '0',
+            Some('?') => ' ',
+            _ => return value,
+        };
+        let padding: String =
+            std::iter::repeat_n(fill, width.saturating_sub(value.len())).collect();
+
+        if left {
+            padding + &value
+        } else {
+            value + &padding
+        }
+    };
+
+    let mut out = String::new();
+    if let Some(int_end) = int_end {
+        let int_text = match int {
+            0 if num > 0 => String::new(),
+            0 => "0".to_string(),
+            n => n.to_string(),
+        };
+        let int_digits: Vec<Token> = int_tokens[..int_end]
+            .iter()
+            .filter(|t| matches!(t, Token::Digit(_)))
+            .cloned()
+            .collect();
+
+        for token in &int_tokens[..int_end] {
+            if !matches!(token, Token::Digit(_)) {
+                out.push_str(&render_literal(token, ""));
+            }
+        }
+        out.push_str(&pad(int_text.clone(), &int_digits, true));
+
+        // Разделитель между целой частью и дробью
+        if !int_text.is_empty() {
+            for token in &int_tokens[int_end..] {
+                out.push_str(&render_literal(token, ""));
+            }
+        }
+
+        if num == 0 {
+            // Целое число: место под дробь заполняется пробелами
+            let width = num_tokens.len() + 1 + den_tokens.len();
+            out.push_str(&" ".repeat(width));
+            return out;
+        }
+    } else {
+        for token in int_tokens {
+            out.push_str(&render_literal(token, ""));
+        }
+    }
+
+    out.push_str(&pad(num.to_string(), num_tokens, true));
+    out.push('/');
+    match fixed {
+        Some(den) => out.push_str(&den.to_string()),
+        None => out.push_str(&pad(den.to_string(), den_tokens, false)),
+    }
+    for token in &tokens[den_end..] {
+        out.push_str(&render_literal(token, ""));
+    }
+
+    out
+}
+
+/// Отображение литералов и текста секции
+fn render_literal(token: &Token, text: &str) -> String {
+    match token {
+        Token::Literal(s) => s.clone(),
+        Token::Percent => "%".to_string(),
+        Token::Text => text.to_string(),
+        Token::Point => ".".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Форматирование даты и времени по секции
+fn format_date(section: &Section, serial: f64) -> String {
+    let sub_digits = section
+        .tokens
+        .iter()
+        .find_map(|t| match t {
+            Token::SubSecond(n) => Some(*n),
+            _ => None,
+        })
+        .unwrap_or(0)
+        .min(3);
+
+    // Округление до отображаемой точности секунд, как в Excel
+    let scale = 86_400.0 * 10f64.powi(sub_digits as i32);
+    let serial = (serial * scale).round() / scale;
+
+    let Some(dt) = excel_serial_to_datetime(serial) else {
+        return general(serial);
+    };
+
+    let twelve_hours = section.tokens.iter().any(|t| matches!(t, Token::AmPm(_)));
+    let day_before = |idx: usize| {
+        section.tokens[..idx]
+            .iter()
+            .any(|t| matches!(t, Token::Day(1) | Token::Day(2)))
+    };
+
+    let mut out = String::new();
+    for (idx, token) in section.tokens.iter().enumerate() {
+        match token {
+            Token::Year(n) if *n <= 2 => out.push_str(&format!("{:02}", dt.year() % 100)),
+            Token::Year(_) => out.push_str(&format!("{:04}", dt.year())),
+            Token::Month(n) => {
+                let m = dt.month0() as usize;
+                let name = match (*n, section.russian) {
+                    (1, _) => (m + 1).to_string(),
+                    (2, _) => format!("{:02}", m + 1),
+                    (3, false) => MONTHS_EN[m][..3].to_string(),
+                    (3, true) => MONTHS_RU_SHORT[m].to_string(),
+                    (5, false) => MONTHS_EN[m][..1].to_string(),
+                    (5, true) => MONTHS_RU[m].chars().take(1).collect(),
+                    (_, false) => MONTHS_EN[m].to_string(),
+                    (_, true) if day_before(idx) => MONTHS_RU_GENITIVE[m].to_string(),
+                    (_, true) => MONTHS_RU[m].to_string(),
+                };
+                out.push_str(&name);
+            }
+            Token::Day(n) => {
+                let w = dt.weekday().num_days_from_sunday() as usize;
+                let name = match (*n, section.russian) {
+                    (1, _) => dt.day().to_string(),
+                    (2, _) => format!("{:02}", dt.day()),
+                    (3, false) => DAYS_EN[w][..3].to_string(),
+                    (3, true) => DAYS_RU_SHORT[w].to_string(),
+                    (_, false) => DAYS_EN[w].to_string(),
+                    (_, true) => DAYS_RU[w].to_string(),
+                };
+                out.push_str(&name);
+            }
+            Token::Hour(n) => {
+                let mut h = dt.hour();
+                if twelve_hours {
+                    h %= 12;
+                    if h == 0 {
+                        h = 12;
+                    }
+                }
+                out.push_str(&pad(h as u64, *n));
+            }
+            Token::Minute(n) => out.push_str(&pad(dt.minute() as u64, *n)),
+            Token::Second(n) => out.push_str(&pad(dt.second() as u64, *n)),
+            Token::SubSecond(n) => {
+                let millis = dt.nanosecond() / 1_000_000;
+                let value = format!("{millis:03}");
+                out.push_str(&value[..(*n).min(3)]);
+            }
+            Token::AmPm(short) => {
+                let pm = dt.hour() >= 12;
+                out.push_str(match (short, pm) {
+                    (false, false) => "AM",
+                    (false, true) => "PM",
+                    (true, false) => "A",
+                    (true, true) => "P",
+                });
+            }
+            Token::Elapsed(unit, n) => {
+                let seconds = (serial * 86_400.0).floor() as u64;
+                let value = match unit {
+                    'h' => seconds / 3600,
+                    'm' => seconds / 60,
+                    _ => seconds,
+                };
+                out.push_str(&pad(value, *n));
+            }
+            Token::General => out.push_str(&general(serial)),
+            other => out.push_str(&render_literal(other, "")),
+        }
+    }
+
+    out
+}
+
+fn pad(value: u64, width: usize) -> String {
+    format!("{value:0width$}", width = width.min(2))
+}
+
+/// Выбор секции для числа и признак вывода знака минус
+fn pick_section(sections: &[Section], v: f64) -> Option<(&Section, bool)> {
+    let numeric = &sections[..sections.len().min(3)];
+
+    if numeric.iter().any(|s| s.condition.is_some()) {
+        let section = numeric.iter().find(|s| match &s.condition {
+            Some(c) => c.matches(v),
+            None => true,
+        })?;
+        let sign = v < 0.0
+            && !section
+                .condition
+                .as_ref()
+                .is_some_and(Condition::is_negative);
+
+        return Some((section, sign));
+    }
+
+    match (numeric.len(), v) {
+        (n, v) if v < 0.0 && n >= 2 => Some((&numeric[1], false)),
+        (n, v) if v == 0.0 && n >= 3 => Some((&numeric[2], false)),
+        (_, v) => Some((&numeric[0], v < 0.0)),
+    }
+}
+
+/// Форматирование числа по коду числового формата
+fn format_numeric(v: f64, sections: &[Section]) -> String {
+    if !v.is_finite() {
+        return "#NUM!".to_string();
+    }
+
+    let Some((section, sign)) = pick_section(sections, v) else {
+        return general(v);
+    };
+    if section.tokens.is_empty() {
+        return String::new();
+    }
+
+    let abs = v.abs();
+    let body = if section.is_date() {
+        if !(0.0..MAX_DATE_SERIAL).contains(&v) {
+            // Отрицательные даты и даты после 9999 года Excel не отображает
+            return "#".repeat(10);
+        }
+        format_date(section, abs)
+    } else if let Some(slash) = section.fraction_slash() {
+        format_fraction(section, abs, slash)
+    } else if section.tokens.contains(&Token::General)
+        && !section.tokens.iter().any(|t| matches!(t, Token::Digit(_)))
+    {
+        section
+            .tokens
+            .iter()
+            .map(|t| match t {
+                Token::General => general(abs),
+                other => render_literal(other, ""),
+            })
+            .collect()
+    } else if !section.tokens.iter().any(|t| matches!(t, Token::Digit(_))) {
+        // Секция без цифр выводит только литералы
+        section
+            .tokens
+            .iter()
+            .map(|t| render_literal(t, &general(abs)))
+            .collect()
+    } else {
+        format_number(section, abs)
+    };
+
+    if sign { format!("-{body}") } else { body }
+}
+
+/// Форматирование текста по текстовой секции формата
+fn format_text(text: &str, sections: &[Section]) -> String {
+    let section = match sections.get(3) {
+        Some(section) => Some(section),
+        None => sections.last().filter(|s| s.has_text()),
+    };
+
+    match section {
+        Some(section) => section
+            .tokens
+            .iter()
+            .map(|t| render_literal(t, text))
+            .collect(),
+        None => text.to_string(),
+    }
+}
+
+/// Отображаемое значение, как его показал бы Excel для данного числового формата.
+/// Поддерживаются разделители разрядов, проценты, фиксированное число знаков,
+/// экспоненциальная запись, даты и время, секции для отрицательных чисел,
+/// нуля и текста, а также условия вида `[>=100]`.
+pub fn format_value(value: &CellRawValue, code: &str) -> String {
+    // Пустой формат равнозначен общему
+    let code = if code.trim().is_empty() {
+        "General"
+    } else {
+        code
+    };
+    let sections: Vec<Section> = split_sections(code)
+        .iter()
+        .map(|s| parse_section(s))
+        .collect();
+
+    match value {
+        CellRawValue::Empty => String::new(),
+        CellRawValue::String(s) => format_text(s, &sections),
+        CellRawValue::RichText(r) => format_text(&r.get_text(), &sections),
+        CellRawValue::Bool(b) => b.to_string().to_uppercase(),
+        CellRawValue::Integer(i) => format_numeric(*i as f64, &sections),
+        CellRawValue::Numeric(n) => format_numeric(round_significant(*n), &sections),
+        CellRawValue::Datetime(dt) => format_numeric(datetime_to_excel_serial(dt), &sections),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn num(v: f64, code: &str) -> String {
+        format_value(&CellRawValue::Numeric(v), code)
+    }
+
+    #[test]
+    fn general_format() {
+        assert_eq!(num(0.1 + 0.2, "General"), "0.3");
+        assert_eq!(num(-42.0, "General"), "-42");
+        assert_eq!(num(std::f64::consts::PI, "General"), "3.141592654");
+        assert_eq!(num(123456789012.0, "General"), "1.23457E+11");
+        assert_eq!(num(0.0000000001234, "General"), "1.234E-10");
+        assert_eq!(num(5.0, "General\" шт.\""), "5 шт.");
+    }
+
+    #[test]
+    fn fixed_and_grouping() {
+        assert_eq!(num(1234.5, "0.00"), "1234.50");
+        assert_eq!(num(2.675, "0.00"), "2.68");
+        assert_eq!(num(1234567.891, "#,##0.00"), "1,234,567.89");
+        assert_eq!(num(1234567.891, "# ##0,00"), "1 234 567,89");
+        assert_eq!(num(-1234.5, "# ##0,00"), "-1 234,50");
+        assert_eq!(num(0.5, "#.##"), ".5");
+        assert_eq!(num(5.0, "000"), "005");
+        assert_eq!(num(1.5, "0.0?"), "1.5 ");
+        assert_eq!(num(1234567.0, "#,##0,"), "1,235");
+        assert_eq!(num(999.999, "0.00"), "1000.00");
+        assert_eq!(num(1234.5, "0,00"), "1234,50");
+        assert_eq!(num(1234.5, "#,##0.00\" руб.\""), "1,234.50 руб.");
+    }
+
+    #[test]
+    fn percent_and_scientific() {
+        assert_eq!(num(0.256, "0.0%"), "25.6%");
+        assert_eq!(num(0.05, "0%"), "5%");
+        assert_eq!(num(12345.0, "0.00E+00"), "1.23E+04");
+        assert_eq!(num(0.00012, "0.0E+0"), "1.2E-4");
+    }
+
+    #[test]
+    fn sections() {
+        let code = "#,##0.00;[Red](#,##0.00);\"-\";\"Текст: \"@";
+
+        assert_eq!(num(1234.5, code), "1,234.50");
+        assert_eq!(num(-1234.5, code), "(1,234.50)");
+        assert_eq!(num(0.0, code), "-");
+        assert_eq!(
+            format_value(&CellRawValue::String("abc".into()), code),
+            "Текст: abc"
+        );
+        assert_eq!(
+            format_value(&CellRawValue::String("abc".into()), "0.00"),
+            "abc"
+        );
+        assert_eq!(num(5.0, "0;-0;;@"), "5");
+        assert_eq!(num(0.0, "0;-0;;@"), "");
+        assert_eq!(num(150.0, "[>=100]\"много\";[<0]\"минус\";0"), "много");
+        assert_eq!(num(-3.0, "[>=100]\"много\";[<0]0;0"), "3");
+        assert_eq!(num(7.0, "\"₽\"\\ #,##0_)"), "₽ 7 ");
+        assert_eq!(num(7.0, "[$€-407] 0.00"), "€ 7.00");
+    }
+
+    #[test]
+    fn dates() {
+        let dt = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_milli_opt(14, 7, 9, 250)
+            .unwrap();
+        let value = CellRawValue::Datetime(dt);
+
+        assert_eq!(format_value(&value, "dd.mm.yyyy"), "05.03.2024");
+        assert_eq!(format_value(&value, "mm-dd-yy"), "03-05-24");
+        assert_eq!(format_value(&value, "d mmm yyyy"), "5 Mar 2024");
+        assert_eq!(format_value(&value, "dddd, mmmm d"), "Tuesday, March 5");
+        assert_eq!(format_value(&value, "hh:mm:ss"), "14:07:09");
+        assert_eq!(format_value(&value, "h:mm AM/PM"), "2:07 PM");
+        assert_eq!(format_value(&value, "hh:mm:ss.00"), "14:07:09.25");
+        assert_eq!(format_value(&value, "[$-419]d mmmm yyyy"), "5 марта 2024");
+        assert_eq!(format_value(&value, "[$-419]mmmm yyyy"), "Март 2024");
+        assert_eq!(num(1.5, "[h]:mm"), "36:00");
+        assert_eq!(num(45356.0, "dd.mm.yyyy"), "05.03.2024");
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(num(0.5, "# ?/?"), "1/2");
+        assert_eq!(num(1.25, "# ?/?"), "1 1/4");
+        assert_eq!(num(-1.25, "# ?/?"), "-1 1/4");
+        assert_eq!(num(2.0, "# ?/?"), "2    ");
+        assert_eq!(num(0.3, "# ??/??"), " 3/10");
+        assert_eq!(num(5.7, "# ??/??"), "5  7/10");
+        assert_eq!(num(0.33, "?/?"), "1/3");
+        assert_eq!(num(2.5, "?/?"), "5/2");
+        assert_eq!(num(1.3, "# ?/8"), "1 2/8");
+        assert_eq!(num(0.5, "0/100"), "50/100");
+    }
+
+    #[test]
+    fn empty_and_invalid() {
+        assert_eq!(num(0.1 + 0.2, ""), "0.3");
+        assert_eq!(num(1234.5, " "), "1234.5");
+        assert_eq!(num(3e9, "dd.mm.yyyy"), "##########");
+        assert_eq!(num(2_958_465.0, "dd.mm.yyyy"), "31.12.9999");
+        assert_eq!(num(-1.0, "dd.mm.yyyy"), "##########");
+    }
+
+    #[test]
+    fn other_values() {
+        assert_eq!(format_value(&CellRawValue::Empty, "0.00"), "");
+        assert_eq!(format_value(&CellRawValue::Bool(true), "0.00"), "TRUE");
+        assert_eq!(format_value(&CellRawValue::Integer(1500), "# ##0"), "1 500");
+        assert_eq!(num(1234567.0, "# ##0"), "1 234 567");
+        assert_eq!(num(1234567.0, "#,##0;(#,##0)"), "1,234,567");
+        assert_eq!(format_value(&CellRawValue::String("x".into()), "@"), "x");
+        assert_eq!(num(12.0, "@"), "12");
+    }
+}
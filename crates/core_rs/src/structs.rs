@@ -4,11 +4,20 @@ pub mod cells;
 pub mod column;
 pub mod columns;
 pub mod comment;
+pub mod conditional_format;
 pub mod coordinate;
+pub mod data_validation;
+pub mod defined_name;
 pub mod finder;
+pub mod hyperlink;
 pub mod merge_cells;
+pub mod page_setup;
+pub mod protection;
 pub mod range;
+pub mod rich_text;
 pub mod row;
 pub mod rows;
 pub mod sheet;
+pub mod sheet_view;
 pub mod style;
+pub mod toc;
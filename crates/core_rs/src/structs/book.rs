@@ -1,17 +1,46 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufWriter, Seek, Write},
+    path::Path,
+    sync::Arc,
+};
 
 use anyhow::{Result, bail};
 use parking_lot::RwLock;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::traits::{ReadableSheet, WriteableSheet};
-
-use super::sheet::Sheet;
-
-#[derive(Clone, Debug, Default, Serialize)]
+use crate::{
+    formula::{
+        eval::calculate_sheets,
+        shift::{Axis, Shift, ShiftKind, rename_sheet, sheet_prefix, shift_formula},
+    },
+    traits::{ReadableCell, ReadableSheet, WriteableCell, WriteableSheet},
+    xlsx::writer::write_book,
+};
+
+use super::{
+    coordinate::Coordinate,
+    defined_name::DefinedName,
+    protection::WorkbookProtection,
+    sheet::{Sheet, validate_sheet_name},
+    style::Format,
+    toc::TocOptions,
+};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Book {
     pub(crate) sheets: Vec<Arc<RwLock<Sheet>>>,
+    /// Реестр именованных стилей книги
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) styles: BTreeMap<String, Format>,
+    /// Именованные диапазоны книги и листов
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) defined_names: Vec<DefinedName>,
+    /// Защита структуры книги
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) protection: Option<WorkbookProtection>,
 }
 
 impl Book {
@@ -20,30 +49,41 @@ impl Book {
     }
 
     #[inline]
-    pub fn add_sheet(&mut self, name: &str, sheet_state: &str) -> Arc<RwLock<Sheet>> {
+    pub fn add_sheet(&mut self, name: &str, sheet_state: &str) -> Result<Arc<RwLock<Sheet>>> {
+        self.check_sheet_name(name, None)?;
+
         let sheet = Arc::new(RwLock::new(Sheet::new(name, sheet_state)));
         self.sheets.push(Arc::clone(&sheet));
 
-        sheet
+        Ok(sheet)
     }
 
     #[inline]
     pub fn copy_sheet(&mut self, sheet: Arc<RwLock<Sheet>>) -> Arc<RwLock<Sheet>> {
         let new_sheet = Arc::new(RwLock::new((*sheet.read()).clone()));
 
-        let mut guard = new_sheet.write();
-        guard.set_name(&format!("Sheet {}", self.sheets.len() + 1));
-        drop(guard);
+        // Первое свободное имя вида «Sheet N»
+        let name = (self.sheets.len() + 1..)
+            .map(|n| format!("Sheet {n}"))
+            .find(|name| self.check_sheet_name(name, None).is_ok())
+            .expect("unique sheet name");
+        {
+            let mut guard = new_sheet.write();
+            guard.set_name(&name);
+            guard.set_toc(false);
+        }
 
         self.sheets.push(Arc::clone(&new_sheet));
         new_sheet
     }
 
-    #[inline]
+    /// Метод для получения листа по имени без учета регистра, как в Excel
     pub fn get_sheet_name(&self, name: &str) -> Option<&Arc<RwLock<Sheet>>> {
+        let name = name.to_lowercase();
+
         self.sheets
             .iter()
-            .find(|sheet| sheet.read().get_name() == name)
+            .find(|sheet| sheet.read().get_name().to_lowercase() == name)
     }
 
     #[inline]
@@ -56,6 +96,331 @@ impl Book {
         &self.sheets
     }
 
+    /// Метод для регистрации именованного стиля.
+    /// Стиль с тем же именем заменяется.
+    pub fn register_style(&mut self, name: &str, format: Format) -> Result<()> {
+        if name.trim().is_empty() {
+            bail!("Style name must not be empty");
+        }
+
+        self.styles.insert(name.to_string(), format);
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_style(&self, name: &str) -> Option<&Format> {
+        self.styles.get(name)
+    }
+
+    #[inline]
+    pub fn get_styles(&self) -> &BTreeMap<String, Format> {
+        &self.styles
+    }
+
+    /// Метод для получения отображаемого значения ячейки листа
+    /// с учетом именованных стилей книги
+    pub fn get_display_value<T: Into<Coordinate>>(
+        &self,
+        sheet_name: &str,
+        coordinate: T,
+    ) -> Option<String> {
+        let Coordinate { row, column } = coordinate.into();
+        let sheet = self.get_sheet_name(sheet_name)?.read();
+        let cell = sheet.find_cell_by_coords(row, column).ok().flatten()?;
+
+        Some(cell.read().get_display_value_with_styles(&self.styles))
+    }
+
+    /// Метод для установки защиты структуры книги. `None` снимает защиту
+    pub fn set_protection(&mut self, value: Option<WorkbookProtection>) -> Result<()> {
+        if let Some(protection) = &value {
+            protection.validate()?;
+        }
+        self.protection = value;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_protection(&self) -> Option<&WorkbookProtection> {
+        self.protection.as_ref()
+    }
+
+    /// Метод для удаления строк листа с пересчетом ссылок в формулах всей книги
+    pub fn delete_rows(&mut self, sheet_name: &str, idx: u32, amount: u32) -> Result<()> {
+        self.shift(sheet_name, ShiftKind::Delete, Axis::Row, idx, amount)
+    }
+
+    /// Метод для удаления колонок листа с пересчетом ссылок в формулах всей книги
+    pub fn delete_cols(&mut self, sheet_name: &str, idx: u16, amount: u16) -> Result<()> {
+        self.shift(
+            sheet_name,
+            ShiftKind::Delete,
+            Axis::Col,
+            idx as u32,
+            amount as u32,
+        )
+    }
+
+    /// Метод для вставки строк листа с пересчетом ссылок в формулах всей книги
+    pub fn insert_rows(&mut self, sheet_name: &str, idx: u32, amount: u32) -> Result<()> {
+        self.shift(sheet_name, ShiftKind::Insert, Axis::Row, idx, amount)
+    }
+
+    /// Метод для вставки колонок листа с пересчетом ссылок в формулах всей книги
+    pub fn insert_cols(&mut self, sheet_name: &str, idx: u16, amount: u16) -> Result<()> {
+        self.shift(
+            sheet_name,
+            ShiftKind::Insert,
+            Axis::Col,
+            idx as u32,
+            amount as u32,
+        )
+    }
+
+    fn shift(
+        &mut self,
+        sheet_name: &str,
+        kind: ShiftKind,
+        axis: Axis,
+        idx: u32,
+        amount: u32,
+    ) -> Result<()> {
+        let Some(sheet) = self.get_sheet_name(sheet_name) else {
+            bail!("Sheet not found: {}", sheet_name);
+        };
+
+        {
+            let mut guard = sheet.write();
+            match (kind, axis) {
+                (ShiftKind::Insert, Axis::Row) => guard.insert_rows(idx, amount),
+                (ShiftKind::Insert, Axis::Col) => guard.insert_cols(idx as u16, amount as u16),
+                (ShiftKind::Delete, Axis::Row) => guard.delete_rows(idx, amount),
+                (ShiftKind::Delete, Axis::Col) => guard.delete_cols(idx as u16, amount as u16),
+            }
+        }
+
+        // Ссылки на измененный лист из остальных листов книги
+        let shift = Shift {
+            kind,
+            axis,
+            idx,
+            amount,
+            sheet: Some(sheet_name),
+            local: false,
+        };
+        for other in self.sheets.iter().filter(|s| !Arc::ptr_eq(s, sheet)) {
+            other.write().shift_formulas(&shift);
+        }
+
+        // Ссылки без имени листа в именах этого листа относятся к нему
+        for name in self.defined_names.iter_mut() {
+            let local = name
+                .get_scope()
+                .is_some_and(|s| s.to_lowercase() == sheet_name.to_lowercase());
+
+            if let Some(reference) = shift_formula(name.get_reference(), &Shift { local, ..shift })
+            {
+                name.set_reference(reference);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Метод для переименования листа с заменой имени в формулах и именах книги
+    pub fn rename_sheet(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        let Some(sheet) = self.get_sheet_name(old_name).map(Arc::clone) else {
+            bail!("Sheet not found: {}", old_name);
+        };
+
+        self.check_sheet_name(new_name, Some(&sheet))?;
+
+        sheet.write().set_name(new_name);
+
+        for s in self.sheets.iter() {
+            let mut s = s.write();
+            s.update_formulas(|f| rename_sheet(f, old_name, new_name));
+            s.update_hyperlinks(|l| rename_sheet(l, old_name, new_name));
+        }
+
+        for name in self.defined_names.iter_mut() {
+            if let Some(reference) = rename_sheet(name.get_reference(), old_name, new_name) {
+                name.set_reference(reference);
+            }
+            if name
+                .get_scope()
+                .is_some_and(|s| s.to_lowercase() == old_name.to_lowercase())
+            {
+                name.set_scope(Some(new_name.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Проверка имени листа на допустимость и уникальность в книге.
+    /// `current` - переименовываемый лист.
+    fn check_sheet_name(&self, name: &str, current: Option<&Arc<RwLock<Sheet>>>) -> Result<()> {
+        validate_sheet_name(name)?;
+
+        let lower = name.to_lowercase();
+        if self.sheets.iter().any(|s| {
+            !current.is_some_and(|c| Arc::ptr_eq(s, c))
+                && s.read().get_name().to_lowercase() == lower
+        }) {
+            bail!("Sheet already exists: {}", name);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для добавления имени (именованного диапазона).
+    /// `scope` - имя листа, в пределах которого действует имя, `None` - вся книга.
+    /// Имя с той же областью действия заменяется.
+    pub fn define_name(&mut self, name: &str, reference: &str, scope: Option<&str>) -> Result<()> {
+        if let Some(scope) = scope
+            && self.get_sheet_name(scope).is_none()
+        {
+            bail!("Sheet not found: {}", scope);
+        }
+
+        let defined_name = DefinedName::new(name, reference, scope)?;
+
+        match self.defined_names.iter_mut().find(|n| n.is(name, scope)) {
+            Some(existing) => *existing = defined_name,
+            None => self.defined_names.push(defined_name),
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_defined_names(&self) -> &[DefinedName] {
+        &self.defined_names
+    }
+
+    /// Метод для получения имени с учетом области действия:
+    /// имя листа `scope` приоритетнее имени книги
+    pub fn get_defined_name(&self, name: &str, scope: Option<&str>) -> Option<&DefinedName> {
+        scope
+            .and_then(|scope| self.defined_names.iter().find(|n| n.is(name, Some(scope))))
+            .or_else(|| self.defined_names.iter().find(|n| n.is(name, None)))
+    }
+
+    /// Метод для построения листа оглавления со ссылками на видимые листы книги.
+    /// Лист оглавления ставится первым, прежнее оглавление с тем же именем заменяется.
+    /// Лист с данными с тем же именем не заменяется, а вызывает ошибку.
+    pub fn build_toc(&mut self, options: &TocOptions) -> Result<Arc<RwLock<Sheet>>> {
+        options.validate()?;
+
+        let toc_name = options.sheet_name.to_lowercase();
+        let sheets: Vec<_> = self
+            .sheets
+            .iter()
+            .filter(|s| {
+                let s = s.read();
+                s.get_sheet_state() == "visible" && s.get_name().to_lowercase() != toc_name
+            })
+            .map(Arc::clone)
+            .collect();
+
+        // Заменяется только оглавление, построенное ранее, но не лист с данными
+        if let Some(existing) = self.get_sheet_name(&options.sheet_name)
+            && !existing.read().is_toc()
+        {
+            bail!(
+                "Sheet '{}' already exists and is not a table of contents",
+                options.sheet_name
+            );
+        }
+
+        let back_target = format!("{}!A1", sheet_prefix(&options.sheet_name));
+        let Coordinate { row, column } = options.back_link_cell;
+
+        // Проверка до изменений книги: ссылка на оглавление
+        // не должна затирать данные листа
+        if let Some(text) = &options.back_link {
+            for sheet in sheets.iter() {
+                let sheet = sheet.read();
+                if let Some(cell) = sheet.find_cell_by_coords(row, column)? {
+                    let cell = cell.read();
+                    let is_back_link = cell.get_value() == *text
+                        && cell
+                            .get_hyperlink()
+                            .is_some_and(|l| l.get_target() == back_target);
+
+                    if !is_back_link && !cell.is_value_empty() {
+                        bail!(
+                            "Cell {} on sheet '{}' is not empty",
+                            cell.get_letter(),
+                            sheet.get_name()
+                        );
+                    }
+                }
+            }
+        }
+
+        self.sheets
+            .retain(|s| s.read().get_name().to_lowercase() != toc_name);
+
+        let toc = Arc::new(RwLock::new(Sheet::new(&options.sheet_name, "visible")));
+        self.sheets.insert(0, Arc::clone(&toc));
+
+        let mut guard = toc.write();
+        guard.set_toc(true);
+        let mut toc_row = 1;
+
+        if let Some(title) = &options.title {
+            guard
+                .cell(Coordinate::new(toc_row, 1), Some(title))
+                .write()
+                .set_style(TocOptions::title_format());
+            toc_row += 2;
+        }
+
+        let mut name_width = 0;
+        let mut description_width = 0;
+
+        for sheet in sheets.iter() {
+            let name = sheet.read().get_name();
+
+            guard
+                .cell(Coordinate::new(toc_row, 1), Some(&name))
+                .write()
+                .set_hyperlink(&format!("{}!A1", sheet_prefix(&name)), None)
+                .set_style(TocOptions::link_format());
+            name_width = name_width.max(name.chars().count());
+
+            if let Some(description) = options.descriptions.get(&name) {
+                guard.cell(Coordinate::new(toc_row, 2), Some(description));
+                description_width = description_width.max(description.chars().count());
+            }
+
+            toc_row += 1;
+        }
+
+        guard.set_width_column(1, (name_width + 2).clamp(10, 60) as f64);
+        if description_width > 0 {
+            guard.set_width_column(2, (description_width + 2).clamp(10, 100) as f64);
+        }
+        drop(guard);
+
+        if let Some(text) = &options.back_link {
+            for sheet in sheets.iter() {
+                sheet
+                    .write()
+                    .cell(options.back_link_cell.clone(), Some(text))
+                    .write()
+                    .set_hyperlink(&back_target, None)
+                    .set_style(TocOptions::link_format());
+            }
+        }
+
+        Ok(toc)
+    }
+
     #[inline]
     pub fn to_json(&self) -> Result<String> {
         match serde_json::to_string(self) {
@@ -66,6 +431,40 @@ impl Book {
         }
     }
 
+    /// Метод для пересчета всех формул книги.
+    /// Результаты сохраняются в значениях ячеек с формулами.
+    #[inline]
+    pub fn calculate(&self) {
+        calculate_sheets(&self.sheets, &self.defined_names)
+    }
+
+    /// Метод для восстановления книги из JSON
+    #[inline]
+    pub fn from_json(value: &str) -> Result<Self> {
+        match serde_json::from_str(value) {
+            Ok(book) => Ok(book),
+            Err(e) => {
+                bail!("Failed to convert from JSON: {}", e);
+            }
+        }
+    }
+
+    /// Метод для записи книги в формате XLSX
+    #[inline]
+    pub fn write_xlsx<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        write_book(self, writer)
+    }
+
+    /// Метод для сохранения книги в файл XLSX
+    #[inline]
+    pub fn save_xlsx<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_xlsx(&mut writer)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
     #[inline]
     pub fn to_hashmap(&self) -> Result<HashMap<String, Value>> {
         let j = self.to_json()?;
@@ -81,7 +480,7 @@ mod tests {
 
     fn test_book() -> Book {
         let mut book = Book::new();
-        book.add_sheet("ЦП", "visible");
+        book.add_sheet("ЦП", "visible").unwrap();
 
         book
     }
@@ -96,7 +495,7 @@ mod tests {
     #[test]
     fn test_add_sheet() {
         let mut book = Book::new();
-        book.add_sheet("ЦП", "visible");
+        book.add_sheet("ЦП", "visible").unwrap();
 
         assert_eq!(book.sheets.len(), 1);
     }
@@ -110,6 +509,10 @@ mod tests {
 
         assert_eq!(book.sheets.len(), 2);
         assert_eq!(sheet2.read().get_name(), "Sheet 2");
+
+        book.rename_sheet("ЦП", "sheet 3").unwrap();
+        let sheet3 = book.copy_sheet(book.get_sheet_index(0).unwrap().clone());
+        assert_eq!(sheet3.read().get_name(), "Sheet 4");
     }
 
     #[test]
@@ -140,6 +543,424 @@ mod tests {
         assert!(book.to_json().is_ok());
     }
 
+    #[test]
+    fn test_from_json() {
+        let book = test_book();
+        let json = book.to_json().unwrap();
+        let restored = Book::from_json(&json).unwrap();
+
+        assert_eq!(restored.sheets.len(), 1);
+        assert_eq!(restored.get_sheet_index(0).unwrap().read().get_name(), "ЦП");
+        assert_eq!(restored.to_json().unwrap(), json);
+        assert!(Book::from_json("{").is_err());
+    }
+
+    #[test]
+    fn test_register_style() {
+        let mut book = test_book();
+        let header = Format {
+            wrap: true,
+            number_format: Some("0.00".to_string()),
+            ..Default::default()
+        };
+
+        book.register_style("header", header.clone()).unwrap();
+        assert!(book.register_style(" ", Format::default()).is_err());
+        assert_eq!(book.get_style("header"), Some(&header));
+
+        book.get_sheet_index(0)
+            .unwrap()
+            .write()
+            .cell((1, 1).into(), Some("1"))
+            .write()
+            .set_style("header");
+
+        let restored = Book::from_json(&book.to_json().unwrap()).unwrap();
+        assert_eq!(restored.get_styles(), book.get_styles());
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        assert!(restored.write_xlsx(&mut buf).is_ok());
+    }
+
+    #[test]
+    fn test_get_display_value() {
+        let mut book = test_book();
+        let money = Format {
+            number_format: Some("0.00".to_string()),
+            ..Default::default()
+        };
+        book.register_style("money", money).unwrap();
+
+        {
+            let sheet = book.get_sheet_index(0).unwrap();
+            let mut sheet = sheet.write();
+            sheet
+                .cell((1, 1).into(), None)
+                .write()
+                .set_value_number(12.5)
+                .set_style("money");
+            sheet
+                .cell((1, 2).into(), None)
+                .write()
+                .set_value_number(12.5)
+                .set_style("unknown");
+        }
+
+        assert_eq!(
+            book.get_display_value("ЦП", (1, 1)),
+            Some("12.50".to_string())
+        );
+        assert_eq!(
+            book.get_display_value("ЦП", (1, 2)),
+            Some("12.5".to_string())
+        );
+        assert_eq!(book.get_display_value("ЦП", (9, 9)), None);
+        assert_eq!(book.get_display_value("Нет", (1, 1)), None);
+    }
+
+    #[test]
+    fn test_calculate() {
+        let book = test_book();
+        let sheet = book.get_sheet_index(0).unwrap();
+
+        {
+            let mut guard = sheet.write();
+            guard.cell((1, 1).into(), Some("2"));
+            guard.cell((2, 1).into(), Some("3"));
+            guard
+                .cell((3, 1).into(), None)
+                .write()
+                .set_formula("=SUM(A1:A2)");
+        }
+
+        book.calculate();
+
+        assert_eq!(sheet.read().get_cell_value((3, 1)), "5");
+    }
+
+    #[test]
+    fn test_delete_rows() {
+        let mut book = test_book();
+        let data = book.add_sheet("Данные", "visible").unwrap();
+        let sheet = Arc::clone(book.get_sheet_index(0).unwrap());
+
+        {
+            let mut guard = data.write();
+            for row in 1..=5 {
+                guard.cell((row, 1).into(), Some(&row.to_string()));
+            }
+            guard
+                .cell((6, 1).into(), None)
+                .write()
+                .set_formula("=SUM(A1:A5)");
+        }
+        sheet
+            .write()
+            .cell((1, 1).into(), None)
+            .write()
+            .set_formula("=Данные!A6+Данные!A2+A2");
+
+        book.delete_rows("Данные", 2, 2).unwrap();
+        book.calculate();
+
+        let formula = |s: &Arc<RwLock<Sheet>>, row, col| {
+            s.read()
+                .find_cell_by_coords(row, col)
+                .unwrap()
+                .unwrap()
+                .read()
+                .get_formula()
+        };
+        assert_eq!(formula(&data, 4, 1), Some("=SUM(A1:A3)".to_string()));
+        assert_eq!(
+            formula(&sheet, 1, 1),
+            Some("=Данные!A4+Данные!#REF!+A2".to_string())
+        );
+        assert_eq!(data.read().get_cell_value((4, 1)), "10");
+
+        book.delete_cols("Данные", 1, 1).unwrap();
+        assert_eq!(
+            formula(&sheet, 1, 1),
+            Some("=Данные!#REF!+Данные!#REF!+A2".to_string())
+        );
+
+        assert!(book.delete_rows("Нет", 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_defined_names() {
+        let mut book = test_book();
+        book.add_sheet("Данные", "visible").unwrap();
+        let sheet = Arc::clone(book.get_sheet_index(0).unwrap());
+
+        book.define_name("Доходы", "=Данные!$A$1:$A$5", None)
+            .unwrap();
+        book.define_name("Итого", "$B$1", Some("ЦП")).unwrap();
+        assert!(book.define_name("Итого", "$B$1", Some("Нет")).is_err());
+        assert!(book.define_name("B1", "$B$1", None).is_err());
+
+        sheet
+            .write()
+            .cell((1, 2).into(), None)
+            .write()
+            .set_formula("=SUM(Данные!A1:A5)+Итого");
+
+        book.delete_rows("Данные", 1, 2).unwrap();
+        book.delete_cols("ЦП", 1, 1).unwrap();
+        assert_eq!(
+            book.get_defined_name("Доходы", None)
+                .unwrap()
+                .get_reference(),
+            "Данные!$A$1:$A$3"
+        );
+        assert_eq!(
+            book.get_defined_name("итого", Some("ЦП"))
+                .unwrap()
+                .get_reference(),
+            "$A$1"
+        );
+        assert!(book.get_defined_name("Итого", None).is_none());
+
+        book.rename_sheet("Данные", "Доходы 2024").unwrap();
+        book.rename_sheet("ЦП", "Отчет").unwrap();
+        assert!(book.rename_sheet("Отчет", "доходы 2024").is_err());
+        assert!(book.rename_sheet("Нет", "Новый").is_err());
+        assert!(book.delete_rows("доходы 2024", 10, 1).is_ok());
+        assert!(Arc::ptr_eq(
+            book.get_sheet_name("отчет").unwrap(),
+            book.get_sheet_name("ОТЧЕТ").unwrap()
+        ));
+
+        assert_eq!(
+            book.get_defined_name("Доходы", None)
+                .unwrap()
+                .get_reference(),
+            "'Доходы 2024'!$A$1:$A$3"
+        );
+        assert_eq!(
+            book.get_defined_name("Итого", Some("Отчет"))
+                .unwrap()
+                .get_scope(),
+            Some("Отчет")
+        );
+        assert_eq!(
+            sheet
+                .read()
+                .find_cell_by_coords(1, 1)
+                .unwrap()
+                .unwrap()
+                .read()
+                .get_formula(),
+            Some("=SUM('Доходы 2024'!A1:A3)+Итого".to_string())
+        );
+
+        let restored = Book::from_json(&book.to_json().unwrap()).unwrap();
+        assert_eq!(restored.get_defined_names(), book.get_defined_names());
+    }
+
+    #[test]
+    fn test_build_toc() {
+        let mut book = test_book();
+        book.add_sheet("Лист 2", "visible").unwrap();
+        book.add_sheet("Скрытый", "hidden").unwrap();
+
+        let options = TocOptions {
+            descriptions: BTreeMap::from([("ЦП".to_string(), "Сводка".to_string())]),
+            back_link_cell: Coordinate::new(1, 3),
+            ..Default::default()
+        };
+
+        let toc = book.build_toc(&options).unwrap();
+        assert!(Arc::ptr_eq(&toc, book.get_sheet_index(0).unwrap()));
+        assert_eq!(book.sheets.len(), 4);
+
+        let toc = toc.read();
+        assert_eq!(toc.get_cell_value((1, 1)), "Оглавление");
+        assert_eq!(toc.get_cell_value((3, 1)), "ЦП");
+        assert_eq!(toc.get_cell_value((3, 2)), "Сводка");
+        assert_eq!(toc.get_cell_value((4, 1)), "Лист 2");
+        assert_eq!(toc.get_cell_value((5, 1)), "");
+
+        let link = |sheet: &Sheet, row, col| {
+            let cell = sheet.find_cell_by_coords(row, col).unwrap().unwrap();
+            cell.read()
+                .get_hyperlink()
+                .map(|l| l.get_target().to_string())
+        };
+        assert_eq!(link(&toc, 4, 1), Some("'Лист 2'!A1".to_string()));
+
+        let sheet = book.get_sheet_name("ЦП").unwrap().read();
+        assert_eq!(sheet.get_cell_value((1, 3)), "К оглавлению");
+        assert_eq!(link(&sheet, 1, 3), Some("Оглавление!A1".to_string()));
+        assert!(
+            book.get_sheet_name("Скрытый")
+                .unwrap()
+                .read()
+                .find_cell_by_coords(1, 3)
+                .unwrap()
+                .is_none()
+        );
+        drop(sheet);
+        drop(toc);
+
+        // Повторное построение заменяет оглавление
+        book.build_toc(&options).unwrap();
+        assert_eq!(book.sheets.len(), 4);
+
+        // Оглавление из JSON тоже заменяется
+        let mut restored = Book::from_json(&book.to_json().unwrap()).unwrap();
+        assert!(restored.build_toc(&options).is_ok());
+        assert_eq!(restored.sheets.len(), 4);
+        assert_eq!(book.get_sheet_index(1).unwrap().read().get_name(), "ЦП");
+
+        // Ссылка на оглавление не затирает данные листа
+        book.get_sheet_name("Лист 2")
+            .unwrap()
+            .write()
+            .cell(Coordinate::new(1, 3), Some("Данные"));
+        assert!(book.build_toc(&options).is_err());
+        assert_eq!(book.sheets.len(), 4);
+    }
+
+    #[test]
+    fn test_build_toc_name_collision() {
+        let mut book = test_book();
+        let data = Arc::clone(book.get_sheet_index(0).unwrap());
+        book.define_name("Ставка", "0.2", Some("ЦП")).unwrap();
+
+        let options = TocOptions {
+            sheet_name: "цп".to_string(),
+            ..Default::default()
+        };
+        assert!(book.build_toc(&options).is_err());
+        assert_eq!(book.sheets.len(), 1);
+        assert!(Arc::ptr_eq(book.get_sheet_name("ЦП").unwrap(), &data));
+
+        // Копия оглавления - обычный лист
+        let toc = book
+            .build_toc(&TocOptions {
+                back_link: None,
+                ..Default::default()
+            })
+            .unwrap();
+        let copy = book.copy_sheet(toc);
+        assert!(!copy.read().is_toc());
+    }
+
+    #[test]
+    fn test_rename_sheet_hyperlinks() {
+        let mut book = test_book();
+        book.add_sheet("Лист 2", "visible").unwrap();
+        let options = TocOptions {
+            back_link_cell: Coordinate::new(1, 3),
+            ..Default::default()
+        };
+        book.build_toc(&options).unwrap();
+
+        book.get_sheet_name("ЦП")
+            .unwrap()
+            .write()
+            .cell(Coordinate::new(5, 1), None)
+            .write()
+            .set_hyperlink("#'лист 2'!B2", Some("Детали"));
+
+        book.rename_sheet("Лист 2", "Итоги").unwrap();
+        book.rename_sheet("Оглавление", "Содержание").unwrap();
+
+        let link = |sheet: &str, row, col| {
+            let sheet = book.get_sheet_name(sheet).unwrap().read();
+            let cell = sheet.find_cell_by_coords(row, col).unwrap().unwrap();
+            cell.read().get_hyperlink().unwrap()
+        };
+
+        assert_eq!(link("Содержание", 4, 1).get_target(), "Итоги!A1");
+        assert_eq!(link("Содержание", 3, 1).get_target(), "ЦП!A1");
+        assert_eq!(link("Итоги", 1, 3).get_target(), "Содержание!A1");
+        assert_eq!(link("ЦП", 5, 1).get_target(), "#Итоги!B2");
+        assert_eq!(link("ЦП", 5, 1).get_tooltip(), Some("Детали"));
+    }
+
+    #[test]
+    fn test_shift_hyperlinks() {
+        let mut book = test_book();
+        book.add_sheet("Детали", "visible").unwrap();
+
+        let set_link = |book: &Book, sheet: &str, row, target: &str| {
+            book.get_sheet_name(sheet)
+                .unwrap()
+                .write()
+                .cell(Coordinate::new(row, 10), None)
+                .write()
+                .set_hyperlink(target, None);
+        };
+        set_link(&book, "ЦП", 1, "'Детали'!A10");
+        set_link(&book, "ЦП", 2, "Детали!A3");
+        set_link(&book, "ЦП", 3, "A10");
+        set_link(&book, "Детали", 1, "#Детали!B5");
+        set_link(&book, "Детали", 8, "C10");
+
+        book.delete_rows("Детали", 2, 3).unwrap();
+        book.insert_cols("ЦП", 1, 1).unwrap();
+
+        let link = |sheet: &str, row, col| {
+            let sheet = book.get_sheet_name(sheet).unwrap().read();
+            let cell = sheet.find_cell_by_coords(row, col).unwrap().unwrap();
+            cell.read()
+                .get_hyperlink()
+                .unwrap()
+                .get_target()
+                .to_string()
+        };
+
+        assert_eq!(link("ЦП", 1, 11), "'Детали'!A7");
+        assert_eq!(link("ЦП", 2, 11), "Детали!#REF!");
+        assert_eq!(link("ЦП", 3, 11), "B10");
+        assert_eq!(link("Детали", 1, 10), "#Детали!B2");
+        assert_eq!(link("Детали", 5, 10), "C7");
+    }
+
+    #[test]
+    fn test_sheet_name_validation() {
+        let mut book = test_book();
+
+        for name in [
+            "",
+            "Итоги: 2024",
+            "A/B",
+            "A\\B",
+            "A?",
+            "A*",
+            "[A]",
+            "'A",
+            "A'",
+            "Очень длинное имя листа отчета 2024",
+        ] {
+            assert!(book.add_sheet(name, "visible").is_err(), "{name}");
+            assert!(book.rename_sheet("ЦП", name).is_err(), "{name}");
+        }
+        assert!(book.add_sheet("цп", "visible").is_err());
+        let toc = TocOptions {
+            sheet_name: "Оглавление?".to_string(),
+            ..Default::default()
+        };
+        assert!(book.build_toc(&toc).is_err());
+        assert_eq!(book.sheets.len(), 1);
+        assert_eq!(book.get_sheet_index(0).unwrap().read().get_name(), "ЦП");
+
+        assert!(book.add_sheet("Отчет 'за' год", "visible").is_ok());
+        assert!(book.rename_sheet("ЦП", "цп").is_ok());
+        assert!(book.rename_sheet("цп", &"Я".repeat(31)).is_ok());
+    }
+
+    #[test]
+    fn test_write_xlsx() {
+        let book = test_book();
+        let mut buf = std::io::Cursor::new(Vec::new());
+
+        assert!(book.write_xlsx(&mut buf).is_ok());
+        assert!(!buf.into_inner().is_empty());
+    }
+
     #[test]
     fn test_to_hashmap() {
         let book = test_book();
@@ -1,13 +1,23 @@
+use std::collections::BTreeMap;
+
 use chrono::NaiveDateTime;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use super::{coordinate::Coordinate, style::Style};
+use super::{
+    coordinate::Coordinate,
+    hyperlink::Hyperlink,
+    rich_text::RichText,
+    style::{Format, Style},
+};
 use crate::{
-    datatype::CellValue,
+    datatype::{CellRawValue, CellValue},
+    numfmt::format_value,
     traits::{ReadableCell, WriteableCell},
+    utils::_get_number_format_by_datatype,
 };
 
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "CellData")]
 pub struct Cell {
     #[serde(flatten)]
     coordinate: Coordinate,
@@ -19,9 +29,65 @@ pub struct Cell {
     #[serde(flatten)]
     style: Option<Style>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    number_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hyperlink: Option<Hyperlink>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hidden_value: Option<String>,
 }
 
+/// Промежуточное представление ячейки для десериализации.
+#[derive(Deserialize)]
+struct CellData {
+    #[serde(flatten)]
+    coordinate: Coordinate,
+    #[serde(flatten)]
+    value: CellValue,
+    #[serde(default)]
+    formula: Option<String>,
+    #[serde(default)]
+    data_type: String,
+    #[serde(flatten)]
+    style: Option<Style>,
+    #[serde(default)]
+    number_format: Option<String>,
+    #[serde(default)]
+    hyperlink: Option<Hyperlink>,
+    #[serde(default)]
+    hidden_value: Option<String>,
+}
+
+impl From<CellData> for Cell {
+    fn from(data: CellData) -> Self {
+        let mut value = data.value;
+
+        // Дата в JSON хранится строкой, восстанавливаем тип по data_type
+        if let CellRawValue::String(s) = value.get_raw_value()
+            && matches!(data.data_type.as_str(), "d" | "f")
+            && let Ok(dt) = s.parse::<NaiveDateTime>()
+        {
+            value.set_value_datatime(dt);
+        }
+
+        let data_type = if data.data_type.is_empty() {
+            value.get_data_type().to_string()
+        } else {
+            data.data_type
+        };
+
+        Cell {
+            coordinate: data.coordinate,
+            value,
+            formula: data.formula,
+            data_type,
+            style: data.style,
+            number_format: data.number_format,
+            hyperlink: data.hyperlink,
+            hidden_value: data.hidden_value,
+        }
+    }
+}
+
 impl Cell {
     /// Интициализирует ячейку с заданным координатой и значением
     pub fn new(coordinate: Coordinate, value: Option<&str>) -> Self {
@@ -73,6 +139,18 @@ impl Cell {
         }
     }
 
+    /// Метод для получения типизированного значения ячейки
+    #[inline]
+    pub fn get_raw_value(&self) -> &CellRawValue {
+        self.value.get_raw_value()
+    }
+
+    /// Метод для установки вычисленного значения формулы
+    #[inline]
+    pub(crate) fn set_formula_result(&mut self, value: CellRawValue) {
+        self.value.raw_value = value;
+    }
+
     #[inline]
     pub(crate) fn remove_formula(&mut self) {
         self.formula = None;
@@ -80,6 +158,32 @@ impl Cell {
         self.data_type = self.value.get_data_type().to_string();
     }
 
+    /// Метод для получения значения в том виде, как его отобразит Excel.
+    /// Числовой формат берется из ячейки, затем из встроенного стиля,
+    /// затем из именованного стиля `styles`, иначе - по типу данных.
+    pub fn get_display_value_with_styles(&self, styles: &BTreeMap<String, Format>) -> String {
+        let style_format = self.style.as_ref().and_then(|s| {
+            s.get_format()
+                .or_else(|| styles.get(&s.get_id()))
+                .and_then(|f| f.number_format.clone())
+        });
+        let number_format = self
+            .number_format
+            .clone()
+            .or(style_format)
+            .unwrap_or_else(|| {
+                _get_number_format_by_datatype(self.value.get_raw_value().get_date_type())
+            });
+
+        format_value(self.value.get_raw_value(), &number_format)
+    }
+
+    /// Метод для получения ссылки на стиль ячейки
+    #[inline]
+    pub(crate) fn style(&self) -> Option<&Style> {
+        self.style.as_ref()
+    }
+
     #[inline]
     pub(crate) fn set_data_type(&mut self, val: &str) {
         self.data_type = val.to_string()
@@ -132,6 +236,15 @@ impl WriteableCell for Cell {
         self
     }
 
+    /// Метод для установки форматированного текста из фрагментов
+    #[inline]
+    fn set_value_rich_text(&mut self, value: RichText) -> &mut Self {
+        self.value.set_value_rich_text(value);
+        self.remove_formula();
+
+        self
+    }
+
     #[inline]
     fn set_formula(&mut self, value: &str) -> &mut Self {
         self.formula = Some(value.to_string());
@@ -141,8 +254,26 @@ impl WriteableCell for Cell {
     }
 
     #[inline]
-    fn set_style(&mut self, value: &str) -> &mut Self {
-        self.style = Some(Style::new(value));
+    fn set_style<T: Into<Style>>(&mut self, value: T) -> &mut Self {
+        self.style = Some(value.into());
+
+        self
+    }
+
+    /// Метод для установки числового формата, например `# ##0,00` или `0.0%`.
+    /// Пустая строка сбрасывает формат.
+    #[inline]
+    fn set_number_format(&mut self, value: &str) -> &mut Self {
+        self.number_format = (!value.is_empty()).then(|| value.to_string());
+
+        self
+    }
+
+    /// Метод для установки гиперссылки на внешний адрес или место в книге,
+    /// например `'Лист 2'!A1`. Пустой адрес удаляет гиперссылку.
+    #[inline]
+    fn set_hyperlink(&mut self, target: &str, tooltip: Option<&str>) -> &mut Self {
+        self.hyperlink = (!target.is_empty()).then(|| Hyperlink::new(target, tooltip));
 
         self
     }
@@ -171,6 +302,13 @@ impl ReadableCell for Cell {
         self.value.get_value()
     }
 
+    /// Метод для получения значения в том виде, как его отобразит Excel.
+    /// Именованные стили книги не учитываются, см. `Book::get_display_value`.
+    #[inline]
+    fn get_display_value(&self) -> String {
+        self.get_display_value_with_styles(&BTreeMap::new())
+    }
+
     #[inline]
     fn get_formula(&self) -> Option<String> {
         self.formula.clone()
@@ -191,6 +329,26 @@ impl ReadableCell for Cell {
         self.style.clone()
     }
 
+    #[inline]
+    fn get_number_format(&self) -> Option<String> {
+        self.number_format.clone()
+    }
+
+    #[inline]
+    fn get_hyperlink(&self) -> Option<Hyperlink> {
+        self.hyperlink.clone()
+    }
+
+    /// Метод для получения форматированного текста.
+    /// Для остальных значений `None`, текст без форматирования - `get_value`
+    #[inline]
+    fn get_rich_text(&self) -> Option<RichText> {
+        match self.value.get_raw_value() {
+            CellRawValue::RichText(r) => Some(r.clone()),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn is_formula(&self) -> bool {
         self.formula.is_some() && self.data_type == "f"
@@ -225,6 +383,10 @@ impl ReadableCell for Cell {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::structs::{
+        rich_text::TextRun,
+        style::{Font, Format},
+    };
     use chrono::Utc;
 
     fn cell() -> Cell {
@@ -385,6 +547,92 @@ mod tests {
         assert_eq!(cell.get_style().unwrap().get_id(), "A1")
     }
 
+    #[test]
+    fn set_style_inline() {
+        let format = Format {
+            wrap: true,
+            ..Default::default()
+        };
+        let mut cell = cell();
+        cell.set_style(format.clone());
+
+        let restored: Cell = serde_json::from_str(&serde_json::to_string(&cell).unwrap()).unwrap();
+
+        assert_eq!(restored.get_style().unwrap().get_format(), Some(&format));
+        assert_eq!(restored.get_style().unwrap().get_id(), "");
+    }
+
+    #[test]
+    fn set_number_format() {
+        let mut cell = cell();
+        cell.set_number_format("# ##0,00");
+
+        let restored: Cell = serde_json::from_str(&serde_json::to_string(&cell).unwrap()).unwrap();
+        assert_eq!(restored.get_number_format(), Some("# ##0,00".to_string()));
+
+        cell.set_number_format("");
+        assert_eq!(cell.get_number_format(), None);
+    }
+
+    #[test]
+    fn set_hyperlink() {
+        let mut cell = cell();
+        cell.set_hyperlink("'Лист 2'!A1", Some("Детали"));
+
+        let restored: Cell = serde_json::from_str(&serde_json::to_string(&cell).unwrap()).unwrap();
+        assert_eq!(
+            restored.get_hyperlink(),
+            Some(Hyperlink::new("'Лист 2'!A1", Some("Детали")))
+        );
+
+        cell.set_hyperlink("", None);
+        assert_eq!(cell.get_hyperlink(), None);
+    }
+
+    #[test]
+    fn set_value_rich_text() {
+        let mut cell = cell();
+        cell.set_formula("=1+1")
+            .set_value_rich_text(RichText::new(vec![
+                TextRun::new(
+                    "Итого: ",
+                    Some(Font {
+                        bold: true,
+                        ..Default::default()
+                    }),
+                ),
+                TextRun::new("100", None),
+            ]));
+
+        assert!(!cell.is_formula());
+        assert_eq!(cell.get_value(), "Итого: 100");
+        assert_eq!(cell.get_display_value(), "Итого: 100");
+        assert_eq!(cell.get_data_type(), "s");
+
+        let restored: Cell = serde_json::from_str(&serde_json::to_string(&cell).unwrap()).unwrap();
+        assert_eq!(restored.get_rich_text(), cell.get_rich_text());
+
+        cell.set_value_rich_text(RichText::default());
+        assert!(cell.is_value_empty());
+        assert_eq!(cell.get_rich_text(), None);
+    }
+
+    #[test]
+    fn get_display_value() {
+        let mut cell = cell();
+        cell.set_value_number(0.1 + 0.2);
+        assert_eq!(cell.get_display_value(), "0.3");
+
+        cell.set_value_number(1234.5).set_number_format("# ##0,00");
+        assert_eq!(cell.get_display_value(), "1 234,50");
+
+        cell.set_number_format("").set_style(Format {
+            number_format: Some("0.0%".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(cell.get_display_value(), "123450.0%");
+    }
+
     #[test]
     fn set_hidden_value() {
         let val = "Hidden";
@@ -5,22 +5,35 @@ use rayon::{
     iter::{IntoParallelRefIterator, ParallelIterator},
     slice::ParallelSliceMut,
 };
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::sync::Arc;
 
 use super::{cell::Cell, coordinate::Coordinate};
 use crate::{
     MAX_COL, MAX_ROW,
     datatype::CellValue,
+    formula::shift::{Axis, Shift, ShiftKind, shift_formula},
     funcs::{
-        find_cell_by_letter, find_cell_by_regex, find_cell_by_str, find_cells_between_regex,
-        find_cells_by_regex, find_cells_by_str, find_cells_for_cols_by_regex,
-        find_cells_for_rows_by_regex, find_cells_multi_regex, find_cells_range_cols,
-        find_cells_range_rows, find_values_by_col_rows, find_values_by_row_cols,
+        find_cell_by_regex, find_cell_by_str, find_cells_between_regex, find_cells_by_regex,
+        find_cells_by_str, find_cells_for_cols_by_regex, find_cells_for_rows_by_regex,
+        find_cells_multi_regex, find_cells_range_cols, find_cells_range_rows,
+        find_values_by_col_rows, find_values_by_row_cols,
     },
     traits::{ReadableCell, WriteableCell},
 };
 
+type CellMap = HashMap<(u32, u16), Arc<RwLock<Cell>>>;
+
+/// Порядок обхода ячеек при поиске первого совпадения.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOrder {
+    /// Построчно: слева направо, затем сверху вниз
+    #[default]
+    Rows,
+    /// По колонкам: сверху вниз, затем слева направо
+    Cols,
+}
+
 /// Вспомоогательная функция для сериализации HashMap только Value, как вектор.
 fn serialize_cells_to_vec<S>(
     map: &HashMap<(u32, u16), Arc<RwLock<Cell>>>,
@@ -42,9 +55,26 @@ where
     seq.end()
 }
 
-#[derive(Clone, Default, Debug, Serialize)]
+/// Вспомогательная функция для десериализации вектора ячеек в HashMap по координатам.
+fn deserialize_cells_from_vec<'de, D>(deserializer: D) -> Result<CellMap, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let cells = Vec::<Cell>::deserialize(deserializer)?;
+
+    Ok(cells
+        .into_iter()
+        .map(|cell| {
+            let coord = cell.get_coordinate();
+            ((coord.row, coord.column), Arc::new(RwLock::new(cell)))
+        })
+        .collect())
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Cells {
     #[serde(serialize_with = "serialize_cells_to_vec")]
+    #[serde(deserialize_with = "deserialize_cells_from_vec", default)]
     #[serde(rename = "cells")]
     map: HashMap<(u32, u16), Arc<RwLock<Cell>>>,
     #[serde(skip)]
@@ -68,10 +98,18 @@ impl Cells {
     /// Метод для получения коллекции ячеек
     #[inline]
     pub fn get_collection_sorted(&self) -> Vec<&Arc<RwLock<Cell>>> {
+        self.get_collection_ordered(SearchOrder::Rows)
+    }
+
+    /// Метод для получения коллекции ячеек в заданном порядке обхода
+    pub fn get_collection_ordered(&self, order: SearchOrder) -> Vec<&Arc<RwLock<Cell>>> {
         let mut items: Vec<_> = self.map.iter().map(|(key, cell)| (*key, cell)).collect();
 
         // Сортируем по ключу (row, col) - быстрая сортировка примитивов
-        items.par_sort_unstable_by_key(|(key, _)| *key);
+        match order {
+            SearchOrder::Rows => items.par_sort_unstable_by_key(|(key, _)| *key),
+            SearchOrder::Cols => items.par_sort_unstable_by_key(|((row, col), _)| (*col, *row)),
+        }
 
         // Возвращаем только ячейки в отсортированном порядке
         items.into_iter().map(|(_, cell)| cell).collect()
@@ -92,10 +130,10 @@ impl Cells {
     }
 
     #[inline]
-    pub fn get_cell_by_letter(&self, letter: &str) -> Option<&Arc<RwLock<Cell>>> {
-        let Coordinate { row, column } = Coordinate::from(letter);
+    pub fn get_cell_by_letter(&self, letter: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        let Coordinate { row, column } = Coordinate::parse(letter)?;
 
-        self.map.get(&(row, column))
+        Ok(self.map.get(&(row, column)))
     }
 
     /// Метод для получения значения ячейки по координатам
@@ -163,8 +201,108 @@ impl Cells {
         cell
     }
 
+    /// Метод для пересчета ссылок в формулах ячеек
+    pub(crate) fn shift_formulas(&self, shift: &Shift) {
+        self.update_formulas(|f| shift_formula(f, shift));
+    }
+
+    /// Метод для изменения формул ячеек.
+    /// `update` возвращает новую формулу, если она изменилась.
+    pub(crate) fn update_formulas<F: Fn(&str) -> Option<String>>(&self, update: F) {
+        for cell in self.map.values() {
+            let formula = {
+                let guard = cell.read();
+                guard.get_formula().filter(|_| guard.is_formula())
+            };
+
+            if let Some(new_formula) = formula.and_then(|f| update(&f)) {
+                cell.write().set_formula(&new_formula);
+            }
+        }
+    }
+
+    /// Метод для изменения мест внутренних гиперссылок ячеек.
+    /// `update` возвращает новое место, если оно изменилось.
+    pub(crate) fn update_hyperlinks<F: Fn(&str) -> Option<String>>(&self, update: F) {
+        for cell in self.map.values() {
+            let hyperlink = cell.read().get_hyperlink();
+
+            if let Some(hyperlink) = hyperlink
+                && let Some(target) = hyperlink.update_location(&update)
+            {
+                cell.write().set_hyperlink(&target, hyperlink.get_tooltip());
+            }
+        }
+    }
+
+    /// Метод для перемещения ячеек при изменении структуры листа
+    fn move_cells(&mut self, shift: &Shift) {
+        if shift.amount == 0 {
+            return;
+        }
+
+        let new_map: HashMap<_, _> = self
+            .map
+            .drain()
+            .filter_map(|((row, col), cell)| {
+                let (new_row, new_col) = match shift.axis {
+                    Axis::Row => (shift.position(row)?, col),
+                    Axis::Col => (row, shift.position(col as u32)? as u16),
+                };
+
+                if (new_row, new_col) != (row, col) {
+                    cell.write()
+                        .set_coordinate(Coordinate::new(new_row, new_col));
+                }
+                Some(((new_row, new_col), cell))
+            })
+            .collect();
+
+        self.map = new_map;
+        self.shift_formulas(shift);
+    }
+
+    #[inline]
+    pub fn insert_cols(&mut self, idx: u16, amount: u16) {
+        self.insert_cols_in(idx, amount, None);
+    }
+
+    /// Вставка колонок с пересчетом формул листа `sheet`
+    pub(crate) fn insert_cols_in(&mut self, idx: u16, amount: u16, sheet: Option<&str>) {
+        self.move_cells(&Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Col,
+            idx: idx as u32,
+            amount: amount as u32,
+            sheet,
+            local: true,
+        });
+    }
+
+    #[inline]
+    pub fn insert_rows(&mut self, idx: u32, amount: u32) {
+        self.insert_rows_in(idx, amount, None);
+    }
+
+    /// Вставка строк с пересчетом формул листа `sheet`
+    pub(crate) fn insert_rows_in(&mut self, idx: u32, amount: u32, sheet: Option<&str>) {
+        self.move_cells(&Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Row,
+            idx,
+            amount,
+            sheet,
+            local: true,
+        });
+    }
+
     #[inline]
     pub fn delete_cols(&mut self, idx: u16, amount: u16) {
+        self.delete_cols_in(idx, amount, None);
+    }
+
+    /// Удаление колонок с пересчетом формул листа `sheet`
+    pub(crate) fn delete_cols_in(&mut self, idx: u16, amount: u16, sheet: Option<&str>) {
         let new_map: HashMap<_, _> = self
             .map
             .drain()
@@ -187,10 +325,23 @@ impl Cells {
             .collect();
 
         self.map = new_map;
+        self.shift_formulas(&Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Col,
+            idx: idx as u32,
+            amount: amount as u32,
+            sheet,
+            local: true,
+        });
     }
 
     #[inline]
     pub fn delete_rows(&mut self, idx: u32, amount: u32) {
+        self.delete_rows_in(idx, amount, None);
+    }
+
+    /// Удаление строк с пересчетом формул листа `sheet`
+    pub(crate) fn delete_rows_in(&mut self, idx: u32, amount: u32, sheet: Option<&str>) {
         let new_map: HashMap<_, _> = self
             .map
             .drain()
@@ -213,18 +364,36 @@ impl Cells {
             .collect();
 
         self.map = new_map;
+        self.shift_formulas(&Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx,
+            amount,
+            sheet,
+            local: true,
+        });
     }
 
+    /// Метод для поиска первой ячейки, подходящей под регулярное выражение
     #[inline]
-    pub fn find_cell_by_regex(&self, regex: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
-        let cells = self.get_collection();
+    pub fn find_cell_by_regex(
+        &self,
+        regex: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        let cells = self.get_collection_ordered(order);
 
         find_cell_by_regex(regex.into(), cells)
     }
 
+    /// Метод для поиска первой ячейки с заданным значением
     #[inline]
-    pub fn find_cell_by_str(&self, value: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
-        let cells = self.get_collection();
+    pub fn find_cell_by_str(
+        &self,
+        value: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        let cells = self.get_collection_ordered(order);
 
         find_cell_by_str(value.into(), cells)
     }
@@ -236,9 +405,7 @@ impl Cells {
 
     #[inline]
     pub fn find_cell_by_letter(&self, letter: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
-        let cells = self.get_collection();
-
-        find_cell_by_letter(letter.into(), cells)
+        self.get_cell_by_letter(letter)
     }
 
     #[inline]
@@ -1,8 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::DEFAULT_COLUMN_WIDTH;
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Default)]
 pub struct Column {
     col_num: u16,
     width: f64,
@@ -20,6 +20,14 @@ impl Column {
         }
     }
 
+    pub fn get_col_num(&self) -> u16 {
+        self.col_num
+    }
+
+    pub(crate) fn set_col_num(&mut self, val: u16) {
+        self.col_num = val;
+    }
+
     pub fn get_width(&self) -> &f64 {
         &self.width
     }
@@ -28,6 +36,16 @@ impl Column {
         &self.hidden
     }
 
+    /// Уровень группировки, 0 - вне группы
+    pub fn get_level(&self) -> &u32 {
+        &self.level
+    }
+
+    /// Свернута ли группа, итоговой для которой является эта колонка
+    pub fn get_collapsed(&self) -> &bool {
+        &self.collapsed
+    }
+
     pub fn set_width(&mut self, val: f64) -> &mut Self {
         self.width = val;
 
@@ -39,4 +57,16 @@ impl Column {
 
         self
     }
+
+    pub fn set_level(&mut self, val: u32) -> &mut Self {
+        self.level = val;
+
+        self
+    }
+
+    pub fn set_collapsed(&mut self, val: bool) -> &mut Self {
+        self.collapsed = val;
+
+        self
+    }
 }
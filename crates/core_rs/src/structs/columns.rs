@@ -1,14 +1,36 @@
 use ahash::HashMap;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use super::column::Column;
-use crate::DEFAULT_COLUMN_WIDTH;
+use crate::{
+    DEFAULT_COLUMN_WIDTH,
+    formula::shift::{Axis, Shift},
+};
+
+/// Вспомогательная функция для десериализации колонок с восстановлением ключей по номеру колонки.
+fn deserialize_columns<'de, D>(deserializer: D) -> Result<HashMap<u16, Box<Column>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let columns = HashMap::<String, Box<Column>>::deserialize(deserializer)?;
+
+    Ok(columns
+        .into_values()
+        .map(|column| (column.get_col_num(), column))
+        .collect())
+}
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub(crate) struct Columns {
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(
+        skip_serializing_if = "HashMap::is_empty",
+        deserialize_with = "deserialize_columns"
+    )]
     columns: HashMap<u16, Box<Column>>,
     default_width: f64,
+    /// Итоговая колонка группы справа от колонок группы
+    summary_right: bool,
 }
 
 impl Default for Columns {
@@ -16,6 +38,7 @@ impl Default for Columns {
         Self {
             columns: HashMap::default(),
             default_width: DEFAULT_COLUMN_WIDTH,
+            summary_right: true,
         }
     }
 }
@@ -41,6 +64,64 @@ impl Columns {
         row.set_hidden(val);
     }
 
+    #[inline]
+    pub(crate) fn set_level(&mut self, col_num: u16, val: u32) {
+        let column = self
+            .columns
+            .entry(col_num)
+            .or_insert(Box::new(Column::new(col_num)));
+
+        column.set_level(val);
+    }
+
+    #[inline]
+    pub(crate) fn set_collapsed(&mut self, col_num: u16, val: bool) {
+        let column = self
+            .columns
+            .entry(col_num)
+            .or_insert(Box::new(Column::new(col_num)));
+
+        column.set_collapsed(val);
+    }
+
+    #[inline]
+    pub(crate) fn set_summary_right(&mut self, val: bool) {
+        self.summary_right = val;
+    }
+
+    /// Метод для перестроения колонок при вставке или удалении колонок листа
+    pub(crate) fn shift(&mut self, shift: &Shift) {
+        if shift.axis != Axis::Col || shift.amount == 0 {
+            return;
+        }
+
+        self.columns = self
+            .columns
+            .drain()
+            .filter_map(|(_, mut column)| {
+                let col_num = shift.position(column.get_col_num() as u32)? as u16;
+                column.set_col_num(col_num);
+
+                Some((col_num, column))
+            })
+            .collect();
+    }
+
+    /// Метод для получения ширины колонки по умолчанию
+    #[inline]
+    pub(crate) fn get_default_width(&self) -> &f64 {
+        &self.default_width
+    }
+
+    /// Метод для получения коллекции колонок, отсортированной по номеру
+    #[inline]
+    pub(crate) fn get_collection_sorted(&self) -> Vec<&Column> {
+        let mut columns: Vec<_> = self.columns.values().map(|c| c.as_ref()).collect();
+        columns.sort_unstable_by_key(|c| c.get_col_num());
+
+        columns
+    }
+
     #[inline]
     pub(crate) fn get_width(&self, col_num: u16) -> &f64 {
         self.columns
@@ -56,6 +137,37 @@ impl Columns {
             .map(|c| c.get_hidden())
             .unwrap_or_else(|| &false)
     }
+
+    #[inline]
+    pub(crate) fn get_level(&self, col_num: u16) -> &u32 {
+        self.columns
+            .get(&col_num)
+            .map(|c| c.get_level())
+            .unwrap_or_else(|| &0)
+    }
+
+    #[inline]
+    pub(crate) fn get_collapsed(&self, col_num: u16) -> &bool {
+        self.columns
+            .get(&col_num)
+            .map(|c| c.get_collapsed())
+            .unwrap_or_else(|| &false)
+    }
+
+    /// Метод для получения максимального уровня группировки колонок
+    #[inline]
+    pub(crate) fn get_max_level(&self) -> u32 {
+        self.columns
+            .values()
+            .map(|c| *c.get_level())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    pub(crate) fn get_summary_right(&self) -> bool {
+        self.summary_right
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::coordinate::Coordinate;
 
-#[derive(Clone, Default, Debug, Serialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Comment {
     #[serde(flatten)]
     coordinate: Coordinate,
@@ -24,6 +24,11 @@ impl Comment {
         &self.coordinate
     }
 
+    #[inline]
+    pub(crate) fn set_coordinate(&mut self, coordinate: Coordinate) {
+        self.coordinate = coordinate;
+    }
+
     #[inline]
     pub fn get_author(&self) -> &str {
         &self.author
@@ -0,0 +1,466 @@
+use std::fmt::Display;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    merge_cells::{deserialize_vec_range, serialize_vec_range},
+    range::{Range, top_left},
+    style::{Color, Style},
+};
+use crate::formula::shift::{Shift, shift_anchored_formula};
+
+/// Условное форматирование диапазонов листа.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConditionalFormat {
+    #[serde(
+        serialize_with = "serialize_vec_range",
+        deserialize_with = "deserialize_vec_range"
+    )]
+    pub ranges: Vec<Range>,
+    pub rule: ConditionalRule,
+    /// Стиль, применяемый при выполнении условия.
+    /// Для цветовых шкал и гистограмм не используется.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<Style>,
+    /// Не проверять следующие правила, если условие выполнено
+    #[serde(default, skip_serializing_if = "crate::utils::is_false")]
+    pub stop_if_true: bool,
+    /// Приоритет правила на листе, начиная с 1.
+    /// Правила без приоритета проверяются после правил с приоритетом.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+}
+
+impl ConditionalFormat {
+    /// Интициализирует условное форматирование для диапазонов
+    pub fn new(ranges: Vec<Range>, rule: ConditionalRule) -> Self {
+        ConditionalFormat {
+            ranges,
+            rule,
+            style: None,
+            stop_if_true: false,
+            priority: None,
+        }
+    }
+
+    /// Метод для установки стиля правила
+    pub fn with_style<T: Into<Style>>(mut self, style: T) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// Метод для установки приоритета правила
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Метод для проверки корректности правила
+    pub fn validate(&self) -> Result<()> {
+        if self.ranges.is_empty() {
+            bail!("Conditional format requires at least one range");
+        }
+        if self.priority == Some(0) {
+            bail!("Conditional format priority must start from 1");
+        }
+
+        if let ConditionalRule::CellIs {
+            operator, formula2, ..
+        } = &self.rule
+        {
+            let needs_second = matches!(
+                operator,
+                CellIsOperator::Between | CellIsOperator::NotBetween
+            );
+            match (needs_second, formula2) {
+                (true, None) => bail!("Operator '{operator}' requires formula2"),
+                (false, Some(_)) => bail!("Operator '{operator}' does not accept formula2"),
+                _ => {}
+            }
+        }
+
+        if self
+            .formulas()
+            .any(|f| f.trim().trim_start_matches('=').trim().is_empty())
+        {
+            bail!("Conditional format formula must not be empty");
+        }
+
+        Ok(())
+    }
+
+    /// Формулы правила
+    fn formulas(&self) -> impl Iterator<Item = &String> {
+        let formulas = match &self.rule {
+            ConditionalRule::CellIs {
+                formula, formula2, ..
+            } => vec![Some(formula), formula2.as_ref()],
+            ConditionalRule::Expression { formula } => vec![Some(formula)],
+            _ => Vec::new(),
+        };
+
+        formulas.into_iter().flatten()
+    }
+
+    /// Метод для сдвига диапазонов и формул при вставке или удалении строк/колонок.
+    /// Возвращает `false`, если от диапазонов ничего не осталось.
+    pub(crate) fn shift(&mut self, shift: &Shift) -> bool {
+        let anchor = top_left(&self.ranges);
+        self.ranges = self.ranges.iter().filter_map(|r| r.shift(shift)).collect();
+
+        if let (Some(anchor), Some(new_anchor)) = (anchor, top_left(&self.ranges)) {
+            self.update_formulas(&|f| shift_anchored_formula(f, shift, anchor, new_anchor));
+        }
+
+        !self.ranges.is_empty()
+    }
+
+    /// Метод для изменения формул правила.
+    /// `update` возвращает новую формулу, если она изменилась.
+    pub(crate) fn update_formulas<F: Fn(&str) -> Option<String>>(&mut self, update: &F) {
+        let formulas = match &mut self.rule {
+            ConditionalRule::CellIs {
+                formula, formula2, ..
+            } => vec![Some(formula), formula2.as_mut()],
+            ConditionalRule::Expression { formula } => vec![Some(formula)],
+            _ => Vec::new(),
+        };
+
+        for formula in formulas.into_iter().flatten() {
+            if let Some(new_formula) = update(formula) {
+                *formula = new_formula;
+            }
+        }
+    }
+}
+
+/// Условие правила.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ConditionalRule {
+    /// Сравнение значения ячейки, например `< 0`
+    CellIs {
+        operator: CellIsOperator,
+        formula: String,
+        /// Вторая граница для `between` и `not_between`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        formula2: Option<String>,
+    },
+    /// Произвольная формула относительно левой верхней ячейки диапазона
+    Expression { formula: String },
+    /// Цветовая шкала из двух или трех цветов
+    ColorScale {
+        min: ColorStop,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mid: Option<ColorStop>,
+        max: ColorStop,
+    },
+    /// Гистограмма
+    DataBar {
+        color: Color,
+        #[serde(default = "Threshold::min")]
+        min: Threshold,
+        #[serde(default = "Threshold::max")]
+        max: Threshold,
+    },
+    /// Повторяющиеся значения, или уникальные при `unique`
+    DuplicateValues {
+        #[serde(default, skip_serializing_if = "crate::utils::is_false")]
+        unique: bool,
+    },
+    /// Первые или последние N значений (или N процентов)
+    Top {
+        rank: u32,
+        #[serde(default, skip_serializing_if = "crate::utils::is_false")]
+        bottom: bool,
+        #[serde(default, skip_serializing_if = "crate::utils::is_false")]
+        percent: bool,
+    },
+}
+
+impl ConditionalRule {
+    /// Используется ли стиль правила
+    pub(crate) fn has_style(&self) -> bool {
+        !matches!(self, Self::ColorScale { .. } | Self::DataBar { .. })
+    }
+}
+
+/// Оператор сравнения для правила `cell_is`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CellIsOperator {
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+    GreaterThanOrEqual,
+    GreaterThan,
+    Between,
+    NotBetween,
+}
+
+impl Display for CellIsOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::LessThan => "lessThan",
+            Self::LessThanOrEqual => "lessThanOrEqual",
+            Self::Equal => "equal",
+            Self::NotEqual => "notEqual",
+            Self::GreaterThanOrEqual => "greaterThanOrEqual",
+            Self::GreaterThan => "greaterThan",
+            Self::Between => "between",
+            Self::NotBetween => "notBetween",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Точка цветовой шкалы.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ColorStop {
+    #[serde(rename = "type")]
+    pub kind: ThresholdKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub color: Color,
+}
+
+/// Граница гистограммы.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    #[serde(rename = "type")]
+    pub kind: ThresholdKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Threshold {
+    fn min() -> Self {
+        Threshold {
+            kind: ThresholdKind::Min,
+            value: None,
+        }
+    }
+
+    fn max() -> Self {
+        Threshold {
+            kind: ThresholdKind::Max,
+            value: None,
+        }
+    }
+}
+
+/// Способ задания границы шкалы.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdKind {
+    Min,
+    Max,
+    Num,
+    Percent,
+    Percentile,
+    Formula,
+}
+
+impl Display for ThresholdKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Num => "num",
+            Self::Percent => "percent",
+            Self::Percentile => "percentile",
+            Self::Formula => "formula",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::shift::{Axis, ShiftKind};
+
+    #[test]
+    fn rule_json() {
+        let json = r##"{
+            "ranges": [[1, 10, 2, 2]],
+            "rule": {"type": "cell_is", "operator": "less_than", "formula": "0"},
+            "style": {"style_id": "negative"}
+        }"##;
+
+        let cf: ConditionalFormat = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            cf.rule,
+            ConditionalRule::CellIs {
+                operator: CellIsOperator::LessThan,
+                formula: "0".to_string(),
+                formula2: None,
+            }
+        );
+        assert_eq!(cf.style.as_ref().unwrap().get_id(), "negative");
+
+        let bar: ConditionalRule =
+            serde_json::from_str(r#"{"type": "data_bar", "color": "638EC6"}"#).unwrap();
+        assert_eq!(
+            bar,
+            ConditionalRule::DataBar {
+                color: "638EC6".parse().unwrap(),
+                min: Threshold::min(),
+                max: Threshold::max(),
+            }
+        );
+
+        assert!(serde_json::from_str::<ConditionalRule>(r#"{"type": "icon_set"}"#).is_err());
+    }
+
+    #[test]
+    fn shift_ranges_and_formulas() {
+        let mut cf = ConditionalFormat::new(
+            vec![Range::new(2, 5, 1, 1), Range::new(8, 8, 1, 3)],
+            ConditionalRule::Expression {
+                formula: "=$A2>$B$1".to_string(),
+            },
+        );
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx: 7,
+            amount: 2,
+            sheet: None,
+            local: true,
+        };
+
+        assert!(cf.shift(&shift));
+        assert_eq!(cf.ranges, vec![Range::new(2, 5, 1, 1)]);
+
+        let shift = Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Row,
+            idx: 1,
+            amount: 1,
+            ..shift
+        };
+
+        assert!(cf.shift(&shift));
+        assert_eq!(cf.ranges, vec![Range::new(3, 6, 1, 1)]);
+        assert_eq!(
+            cf.rule,
+            ConditionalRule::Expression {
+                formula: "=$A3>$B$2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn validate_rules() {
+        let range = vec![Range::new(1, 10, 1, 1)];
+        let cell_is = |formula: &str, formula2: Option<&str>| ConditionalRule::CellIs {
+            operator: if formula2.is_some() {
+                CellIsOperator::Between
+            } else {
+                CellIsOperator::GreaterThan
+            },
+            formula: formula.to_string(),
+            formula2: formula2.map(str::to_string),
+        };
+        let expression = |formula: &str| ConditionalRule::Expression {
+            formula: formula.to_string(),
+        };
+
+        assert!(
+            ConditionalFormat::new(range.clone(), cell_is("0", None))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ConditionalFormat::new(range.clone(), cell_is("0", Some("10")))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ConditionalFormat::new(Vec::new(), cell_is("0", None))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ConditionalFormat::new(range.clone(), cell_is(" ", None))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ConditionalFormat::new(range.clone(), cell_is("0", Some("=")))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ConditionalFormat::new(range.clone(), expression(""))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ConditionalFormat::new(range.clone(), expression("=$A1>0"))
+                .with_priority(0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ConditionalFormat::new(range, ConditionalRule::DuplicateValues { unique: false })
+                .with_priority(1)
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn shift_anchor_row() {
+        let mut cf = ConditionalFormat::new(
+            vec![Range::new(1, 10, 1, 1)],
+            ConditionalRule::Expression {
+                formula: "=AND(A1>$B$1,A2<$C$5)".to_string(),
+            },
+        );
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx: 1,
+            amount: 1,
+            sheet: None,
+            local: true,
+        };
+
+        assert!(cf.shift(&shift));
+        assert_eq!(cf.ranges, vec![Range::new(1, 9, 1, 1)]);
+        assert_eq!(
+            cf.rule,
+            ConditionalRule::Expression {
+                formula: "=AND(A1>#REF!,A2<$C$4)".to_string()
+            }
+        );
+
+        // Относительные ссылки сохраняют смещение от левой верхней ячейки
+        let mut cf = ConditionalFormat::new(
+            vec![Range::new(5, 10, 2, 2)],
+            ConditionalRule::CellIs {
+                operator: CellIsOperator::GreaterThan,
+                formula: "A1".to_string(),
+                formula2: None,
+            },
+        );
+        let shift = Shift {
+            kind: ShiftKind::Insert,
+            idx: 3,
+            amount: 2,
+            ..shift
+        };
+
+        assert!(cf.shift(&shift));
+        assert_eq!(cf.ranges, vec![Range::new(7, 12, 2, 2)]);
+        assert!(matches!(&cf.rule, ConditionalRule::CellIs { formula, .. } if formula == "A3"));
+    }
+}
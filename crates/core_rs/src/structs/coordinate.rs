@@ -1,10 +1,34 @@
-use serde::Serialize;
+use std::{fmt::Display, str::FromStr};
 
-use crate::utils::{get_letter_coordinate, index_from_coordinate};
+use serde::{Deserialize, Serialize};
+
+use crate::{MAX_COL, MAX_ROW, utils::get_letter_coordinate};
 
 pub type CellIndex = (Option<u32>, Option<u16>);
 
-#[derive(Clone, Default, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+/// Ошибка разбора адреса ячейки или диапазона в стиле A1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CoordinateError {
+    /// Адрес не имеет вид `A1` или `$A$1`
+    Invalid(String),
+    /// Строка или колонка за пределами листа
+    OutOfBounds(String),
+}
+
+impl Display for CoordinateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(value) => write!(f, "Invalid cell reference: {value:?}"),
+            Self::OutOfBounds(value) => {
+                write!(f, "Cell reference out of sheet bounds: {value:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CoordinateError {}
+
+#[derive(Clone, Default, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Coordinate {
     pub row: u32,
     pub column: u16,
@@ -24,21 +48,30 @@ impl From<(&u32, &u16)> for Coordinate {
     }
 }
 
-impl From<String> for Coordinate {
-    #[inline]
-    fn from(value: String) -> Self {
-        let str_ref: &str = value.as_ref();
+impl TryFrom<String> for Coordinate {
+    type Error = CoordinateError;
 
-        str_ref.into()
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Coordinate::parse(&value)
     }
 }
 
-impl From<&str> for Coordinate {
+impl TryFrom<&str> for Coordinate {
+    type Error = CoordinateError;
+
     #[inline]
-    fn from(value: &str) -> Self {
-        let (col, row) = index_from_coordinate(value.to_uppercase());
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Coordinate::parse(value)
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = CoordinateError;
 
-        Coordinate::new(col.unwrap(), row.unwrap())
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Coordinate::parse(s)
     }
 }
 
@@ -53,4 +86,87 @@ impl Coordinate {
     pub fn new(row: u32, column: u16) -> Self {
         Coordinate { row, column }
     }
+
+    /// Разбор адреса ячейки вида `A1`, `a1` или `$A$1`
+    pub fn parse(value: &str) -> Result<Self, CoordinateError> {
+        match parse_cell_index(value)? {
+            (Some(row), Some(column)) => Ok(Coordinate::new(row, column)),
+            _ => Err(CoordinateError::Invalid(value.to_string())),
+        }
+    }
+}
+
+/// Разбор адреса, в котором может не быть строки или колонки: `A1`, `$A`, `$1`
+pub(crate) fn parse_cell_index(value: &str) -> Result<CellIndex, CoordinateError> {
+    let invalid = || CoordinateError::Invalid(value.to_string());
+    let out_of_bounds = || CoordinateError::OutOfBounds(value.to_string());
+
+    let rest = value.strip_prefix('$').unwrap_or(value);
+    let letters_end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (letters, rest) = rest.split_at(letters_end);
+    // Без букв признак `$` уже отнесен к строке
+    let digits = match rest.strip_prefix('$') {
+        Some(digits) if !letters.is_empty() && !digits.is_empty() => digits,
+        Some(_) => return Err(invalid()),
+        None => rest,
+    };
+
+    if (letters.is_empty() && digits.is_empty()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let column = match letters {
+        "" => None,
+        letters => letters
+            .bytes()
+            .try_fold(0u32, |acc, b| {
+                let col = acc * 26 + (b.to_ascii_uppercase() - b'A' + 1) as u32;
+                (col <= MAX_COL as u32).then_some(col)
+            })
+            .map(|col| Some(col as u16))
+            .ok_or_else(out_of_bounds)?,
+    };
+    let row = match digits {
+        "" => None,
+        digits => digits
+            .parse::<u32>()
+            .ok()
+            .filter(|r| (1..=MAX_ROW).contains(r))
+            .map(Some)
+            .ok_or_else(out_of_bounds)?,
+    };
+
+    Ok((row, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_coordinate() {
+        assert_eq!(Coordinate::parse("A1"), Ok(Coordinate::new(1, 1)));
+        assert_eq!(Coordinate::parse("c10"), Ok(Coordinate::new(10, 3)));
+        assert_eq!(Coordinate::parse("$B$2"), Ok(Coordinate::new(2, 2)));
+        assert_eq!(
+            Coordinate::try_from("XFD1048576"),
+            Ok(Coordinate::new(1_048_576, 16_384))
+        );
+        assert_eq!("AA7".parse(), Ok(Coordinate::new(7, 27)));
+
+        for value in ["", "A", "1", "1A", "A1B", "A-1", "$$A1", "A1:B2", "Я1"] {
+            assert_eq!(
+                Coordinate::parse(value),
+                Err(CoordinateError::Invalid(value.to_string()))
+            );
+        }
+        for value in ["A0", "XFE1", "ABCD1", "A1048577", "A99999999999"] {
+            assert_eq!(
+                Coordinate::parse(value),
+                Err(CoordinateError::OutOfBounds(value.to_string()))
+            );
+        }
+    }
 }
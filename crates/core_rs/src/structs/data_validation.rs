@@ -0,0 +1,341 @@
+use std::fmt::Display;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    conditional_format::CellIsOperator,
+    merge_cells::{deserialize_vec_range, serialize_vec_range},
+    range::{Range, top_left},
+};
+use crate::formula::shift::{Shift, shift_anchored_formula};
+
+/// Максимальная длина списка значений в Excel.
+const MAX_LIST_LEN: usize = 255;
+
+/// Проверка вводимых данных для диапазонов листа.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DataValidation {
+    #[serde(
+        serialize_with = "serialize_vec_range",
+        deserialize_with = "deserialize_vec_range"
+    )]
+    pub ranges: Vec<Range>,
+    pub rule: ValidationRule,
+    /// Разрешены ли пустые значения
+    #[serde(default = "default_true")]
+    pub allow_blank: bool,
+    /// Подсказка при выборе ячейки
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_message: Option<String>,
+    /// Сообщение при вводе недопустимого значения
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub error_style: ErrorStyle,
+}
+
+impl DataValidation {
+    /// Интициализирует проверку данных для диапазонов
+    pub fn new(ranges: Vec<Range>, rule: ValidationRule) -> Self {
+        DataValidation {
+            ranges,
+            rule,
+            allow_blank: true,
+            input_title: None,
+            input_message: None,
+            error_title: None,
+            error_message: None,
+            error_style: ErrorStyle::default(),
+        }
+    }
+
+    /// Метод для проверки правила перед добавлением на лист
+    pub fn validate(&self) -> Result<()> {
+        if self.ranges.is_empty() {
+            bail!("Data validation requires at least one range");
+        }
+
+        match &self.rule {
+            ValidationRule::List { values } => {
+                if values.is_empty() {
+                    bail!("Data validation list must not be empty");
+                }
+                if values.iter().any(|v| v.contains(',')) {
+                    bail!("Data validation list values must not contain commas");
+                }
+
+                // Длина без внешних кавычек, с удвоенными кавычками внутри значений
+                let len = list_formula(values).chars().count() - 2;
+                if len > MAX_LIST_LEN {
+                    bail!("Data validation list is too long: {len} > {MAX_LIST_LEN} characters");
+                }
+            }
+            ValidationRule::Whole(c)
+            | ValidationRule::Decimal(c)
+            | ValidationRule::Date(c)
+            | ValidationRule::TextLength(c) => {
+                let needs_second = matches!(
+                    c.operator,
+                    CellIsOperator::Between | CellIsOperator::NotBetween
+                );
+                match (needs_second, &c.formula2) {
+                    (true, None) => bail!("Operator '{}' requires formula2", c.operator),
+                    (false, Some(_)) => {
+                        bail!("Operator '{}' does not accept formula2", c.operator)
+                    }
+                    _ => {}
+                }
+            }
+            ValidationRule::ListRange { .. } | ValidationRule::Custom { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    /// Метод для сдвига диапазонов и формул при вставке или удалении строк/колонок.
+    /// Возвращает `false`, если от диапазонов ничего не осталось.
+    pub(crate) fn shift(&mut self, shift: &Shift) -> bool {
+        let anchor = top_left(&self.ranges);
+        self.ranges = self.ranges.iter().filter_map(|r| r.shift(shift)).collect();
+
+        if let (Some(anchor), Some(new_anchor)) = (anchor, top_left(&self.ranges)) {
+            self.update_formulas(&|f| shift_anchored_formula(f, shift, anchor, new_anchor));
+        }
+
+        !self.ranges.is_empty()
+    }
+
+    /// Метод для изменения формул правила.
+    /// `update` возвращает новую формулу, если она изменилась.
+    pub(crate) fn update_formulas<F: Fn(&str) -> Option<String>>(&mut self, update: &F) {
+        let formulas = match &mut self.rule {
+            ValidationRule::ListRange { range } => vec![Some(range)],
+            ValidationRule::Custom { formula } => vec![Some(formula)],
+            ValidationRule::Whole(c)
+            | ValidationRule::Decimal(c)
+            | ValidationRule::Date(c)
+            | ValidationRule::TextLength(c) => vec![Some(&mut c.formula), c.formula2.as_mut()],
+            ValidationRule::List { .. } => Vec::new(),
+        };
+
+        for formula in formulas.into_iter().flatten() {
+            if let Some(new_formula) = update(formula) {
+                *formula = new_formula;
+            }
+        }
+    }
+}
+
+/// Условие проверки данных.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValidationRule {
+    /// Выпадающий список из перечисленных значений
+    List { values: Vec<String> },
+    /// Выпадающий список из диапазона, например `Справочник!$A$1:$A$10`
+    ListRange { range: String },
+    /// Целое число
+    Whole(Constraint),
+    /// Дробное число
+    Decimal(Constraint),
+    /// Дата, границы задаются формулами, например `DATE(2024,1,1)`
+    Date(Constraint),
+    /// Длина текста
+    TextLength(Constraint),
+    /// Произвольная формула относительно левой верхней ячейки диапазона
+    Custom { formula: String },
+}
+
+/// Ограничение значения: оператор и одна или две границы.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Constraint {
+    pub operator: CellIsOperator,
+    pub formula: String,
+    /// Вторая граница для `between` и `not_between`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula2: Option<String>,
+}
+
+/// Реакция Excel на недопустимое значение.
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorStyle {
+    #[default]
+    Stop,
+    Warning,
+    Information,
+}
+
+impl Display for ErrorStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Stop => "stop",
+            Self::Warning => "warning",
+            Self::Information => "information",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Формула списка значений: `"Да,Нет"`, кавычки внутри значений удваиваются
+pub(crate) fn list_formula(values: &[String]) -> String {
+    format!("\"{}\"", values.join(",").replace('"', "\"\""))
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::shift::{Axis, ShiftKind};
+
+    #[test]
+    fn validation_json() {
+        let json = r#"{
+            "ranges": [[2, 100, 3, 3]],
+            "rule": {"type": "whole", "operator": "between", "formula": "1", "formula2": "10"},
+            "error_message": "Введите число от 1 до 10"
+        }"#;
+
+        let dv: DataValidation = serde_json::from_str(json).unwrap();
+        assert!(dv.allow_blank);
+        assert_eq!(dv.error_style, ErrorStyle::Stop);
+        assert!(dv.validate().is_ok());
+
+        let list: ValidationRule =
+            serde_json::from_str(r#"{"type": "list", "values": ["Да", "Нет"]}"#).unwrap();
+        assert_eq!(
+            list,
+            ValidationRule::List {
+                values: vec!["Да".to_string(), "Нет".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn validate_rules() {
+        let range = vec![Range::new(1, 1, 1, 1)];
+        let list = |values: &[&str]| ValidationRule::List {
+            values: values.iter().map(|v| v.to_string()).collect(),
+        };
+
+        assert!(
+            DataValidation::new(range.clone(), list(&["a", "b"]))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            DataValidation::new(range.clone(), list(&[]))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::new(range.clone(), list(&["a,b"]))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::new(range.clone(), list(&[&"x".repeat(256)]))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::new(range.clone(), list(&[&"\"".repeat(128)]))
+                .validate()
+                .is_err()
+        );
+        assert_eq!(
+            list_formula(&["Сорт \"А\"".to_string(), "Б".to_string()]),
+            r#""Сорт ""А"",Б""#
+        );
+        assert!(
+            DataValidation::new(Vec::new(), list(&["a"]))
+                .validate()
+                .is_err()
+        );
+
+        let between = ValidationRule::Decimal(Constraint {
+            operator: CellIsOperator::Between,
+            formula: "0".to_string(),
+            formula2: None,
+        });
+        assert!(DataValidation::new(range, between).validate().is_err());
+    }
+
+    #[test]
+    fn shift_ranges_and_formulas() {
+        let mut dv = DataValidation::new(
+            vec![Range::new(2, 5, 2, 2)],
+            ValidationRule::ListRange {
+                range: "$A$1:$A$10".to_string(),
+            },
+        );
+        let shift = Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Col,
+            idx: 1,
+            amount: 1,
+            sheet: None,
+            local: true,
+        };
+
+        assert!(dv.shift(&shift));
+        assert_eq!(dv.ranges, vec![Range::new(2, 5, 3, 3)]);
+        assert_eq!(
+            dv.rule,
+            ValidationRule::ListRange {
+                range: "$B$1:$B$10".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn shift_anchor_row() {
+        let mut dv = DataValidation::new(
+            vec![Range::new(1, 10, 1, 1)],
+            ValidationRule::Custom {
+                formula: "=A1>0".to_string(),
+            },
+        );
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx: 1,
+            amount: 1,
+            sheet: None,
+            local: true,
+        };
+
+        assert!(dv.shift(&shift));
+        assert_eq!(dv.ranges, vec![Range::new(1, 9, 1, 1)]);
+        assert_eq!(
+            dv.rule,
+            ValidationRule::Custom {
+                formula: "=A1>0".to_string()
+            }
+        );
+
+        let mut dv = DataValidation::new(
+            vec![Range::new(3, 10, 2, 2)],
+            ValidationRule::Whole(Constraint {
+                operator: CellIsOperator::LessThan,
+                formula: "A3+$C$4".to_string(),
+                formula2: None,
+            }),
+        );
+        let shift = Shift { idx: 2, ..shift };
+
+        assert!(dv.shift(&shift));
+        assert_eq!(dv.ranges, vec![Range::new(2, 9, 2, 2)]);
+        assert!(matches!(&dv.rule, ValidationRule::Whole(c) if c.formula == "A2+$C$3"));
+    }
+}
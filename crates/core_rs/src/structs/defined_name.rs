@@ -0,0 +1,144 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::range::Range;
+use crate::formula::lexer::{RefKind, TokenKind, tokenize};
+
+/// Именованный диапазон (имя) книги или листа.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DefinedName {
+    name: String,
+    /// Ссылка или формула, например `'Отчет'!$B$2:$B$10`
+    #[serde(rename = "ref")]
+    reference: String,
+    /// Лист, в пределах которого действует имя. `None` - вся книга
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+}
+
+impl DefinedName {
+    /// Интициализирует имя с проверкой имени и ссылки
+    pub fn new(name: &str, reference: &str, scope: Option<&str>) -> Result<Self> {
+        let is_name = matches!(
+            tokenize(name).ok().as_deref(),
+            Some([token]) if matches!(token.kind, TokenKind::Name(_))
+        );
+        if !is_name || name.starts_with('=') {
+            bail!("Invalid defined name '{name}'");
+        }
+
+        let reference = reference.trim().trim_start_matches('=');
+        if reference.is_empty() || tokenize(reference).is_err() {
+            bail!("Invalid reference '{reference}' for defined name '{name}'");
+        }
+
+        Ok(DefinedName {
+            name: name.to_string(),
+            reference: reference.to_string(),
+            scope: scope.map(str::to_string),
+        })
+    }
+
+    #[inline]
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn get_reference(&self) -> &str {
+        &self.reference
+    }
+
+    #[inline]
+    pub fn get_scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// Совпадает ли имя и область действия (без учета регистра)
+    pub(crate) fn is(&self, name: &str, scope: Option<&str>) -> bool {
+        self.name.to_lowercase() == name.to_lowercase()
+            && self.scope.as_ref().map(|s| s.to_lowercase()) == scope.map(str::to_lowercase)
+    }
+
+    #[inline]
+    pub(crate) fn set_reference(&mut self, reference: String) {
+        self.reference = reference;
+    }
+
+    #[inline]
+    pub(crate) fn set_scope(&mut self, scope: Option<String>) {
+        self.scope = scope;
+    }
+
+    /// Метод для получения диапазонов, на которые ссылается имя.
+    /// Ссылки без имени листа относятся к листу области действия.
+    /// Целые строки и колонки ограничиваются размерами листа `bounds`.
+    pub fn get_ranges<F>(&self, bounds: F) -> Vec<(Option<String>, Range)>
+    where
+        F: Fn(Option<&str>) -> (u32, u16),
+    {
+        let Ok(tokens) = tokenize(&self.reference) else {
+            return Vec::new();
+        };
+
+        tokens
+            .into_iter()
+            .filter_map(|token| match token.kind {
+                TokenKind::Reference(reference) => Some(reference),
+                _ => None,
+            })
+            .map(|reference| {
+                let sheet = reference.sheet.or_else(|| self.scope.clone());
+
+                let range = match reference.kind {
+                    RefKind::Cell(a) => Range::new(a.row, a.row, a.col, a.col),
+                    RefKind::Range(a, b) => Range::new(
+                        a.row.min(b.row),
+                        a.row.max(b.row),
+                        a.col.min(b.col),
+                        a.col.max(b.col),
+                    ),
+                    RefKind::Cols { start, end, .. } => {
+                        let (max_row, _) = bounds(sheet.as_deref());
+                        Range::new(1, max_row.max(1), start.min(end), start.max(end))
+                    }
+                    RefKind::Rows { start, end, .. } => {
+                        let (_, max_col) = bounds(sheet.as_deref());
+                        Range::new(start.min(end), start.max(end), 1, max_col.max(1))
+                    }
+                };
+
+                (sheet, range)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_name() {
+        assert!(DefinedName::new("ИтогоДоходы", "'Отчет'!$B$2:$B$10", None).is_ok());
+        assert!(DefinedName::new("_rate", "=0.2", Some("Отчет")).is_ok());
+        assert!(DefinedName::new("A1", "$A$1", None).is_err());
+        assert!(DefinedName::new("TRUE", "$A$1", None).is_err());
+        assert!(DefinedName::new("Два слова", "$A$1", None).is_err());
+        assert!(DefinedName::new("Итого", "\"abc", None).is_err());
+    }
+
+    #[test]
+    fn get_ranges() {
+        let name = DefinedName::new("Итого", "Отчет!$B$2:$C$3,$A:$A", Some("Лист")).unwrap();
+        let ranges = name.get_ranges(|_| (7, 3));
+
+        assert_eq!(
+            ranges,
+            vec![
+                (Some("Отчет".to_string()), Range::new(2, 3, 2, 3)),
+                (Some("Лист".to_string()), Range::new(1, 7, 1, 1)),
+            ]
+        );
+    }
+}
@@ -1,14 +1,24 @@
-use std::sync::Arc;
+use std::{
+    io::{Read, Seek},
+    path::Path,
+    sync::Arc,
+};
 
+use anyhow::Result;
 use fancy_regex::Regex;
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::traits::ReadableSheet;
+use super::{cell::Cell, defined_name::DefinedName, sheet::Sheet};
+use crate::{
+    traits::{ReadableCell, ReadableSheet},
+    xlsx::reader::{load_package, read_package},
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct Finder<T: ReadableSheet + Send + Sync> {
     pub sheets: Vec<Arc<RwLock<T>>>,
+    pub names: Vec<DefinedName>,
 }
 
 impl<T: ReadableSheet + Send + Sync> Finder<T> {
@@ -18,7 +28,79 @@ impl<T: ReadableSheet + Send + Sync> Finder<T> {
             .map(|s| Arc::new(RwLock::new(s)))
             .collect();
 
-        Self { sheets }
+        Self {
+            sheets,
+            names: Vec::new(),
+        }
+    }
+
+    /// Метод для установки имен (именованных диапазонов) книги
+    #[inline]
+    pub fn with_names(mut self, names: Vec<DefinedName>) -> Self {
+        self.names = names;
+        self
+    }
+
+    #[inline]
+    pub fn get_defined_names(&self) -> &[DefinedName] {
+        &self.names
+    }
+
+    /// Метод для получения ячеек, на которые ссылается имя.
+    /// Имя книги приоритетнее имени листа. Ячейки возвращаются построчно.
+    pub fn resolve_name(&self, name: &str) -> Vec<Arc<RwLock<Cell>>> {
+        let Some(defined_name) = self
+            .names
+            .iter()
+            .find(|n| n.is(name, None))
+            .or_else(|| self.names.iter().find(|n| n.is(name, n.get_scope())))
+        else {
+            return Vec::new();
+        };
+
+        let find_sheet = |name: &str| {
+            let name = name.to_lowercase();
+            self.sheets
+                .iter()
+                .find(|s| s.read().get_name().to_lowercase() == name)
+        };
+
+        let ranges = defined_name.get_ranges(|sheet| {
+            sheet
+                .and_then(find_sheet)
+                .map(|s| {
+                    let guard = s.read();
+                    (guard.get_max_row(), guard.get_max_column())
+                })
+                .unwrap_or_default()
+        });
+
+        let mut cells = Vec::new();
+        for (sheet, range) in ranges {
+            let Some(sheet) = sheet.as_deref().and_then(find_sheet) else {
+                continue;
+            };
+            let guard = sheet.read();
+
+            let mut found: Vec<_> = guard
+                .get_cell_collection_by_range(
+                    Some(range.start_row),
+                    Some(range.end_row),
+                    Some(range.start_col),
+                    Some(range.end_col),
+                )
+                .map(Arc::clone)
+                .collect();
+            found.sort_by_key(|c| {
+                let c = c.read();
+                let coord = c.get_coordinate();
+                (coord.row, coord.column)
+            });
+
+            cells.extend(found);
+        }
+
+        cells
     }
 
     #[inline]
@@ -65,11 +147,28 @@ impl<T: ReadableSheet + Send + Sync> Finder<T> {
     }
 }
 
+impl Finder<Sheet> {
+    /// Создание помощника из книги XLSX
+    #[inline]
+    pub fn read_xlsx<R: Read + Seek>(reader: R) -> Result<Self> {
+        let (sheets, names) = read_package(reader)?;
+
+        Ok(Self::new(sheets).with_names(names))
+    }
+
+    /// Создание помощника из файла XLSX
+    #[inline]
+    pub fn load_xlsx<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let (sheets, names) = load_package(path)?;
+
+        Ok(Self::new(sheets).with_names(names))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::structs::sheet::Sheet;
-
     use super::*;
+    use crate::{structs::coordinate::Coordinate, traits::WriteableSheet};
 
     #[test]
     fn new_finder() {
@@ -125,4 +224,25 @@ mod tests {
 
         assert!(!f.is_empty())
     }
+
+    #[test]
+    fn resolve_name() {
+        let mut sheet = Sheet::new("Отчет", "visible");
+        for row in 1..=4 {
+            sheet.cell(Coordinate::new(row, 2), Some(&(row * 10).to_string()));
+        }
+
+        let f = Finder::new(vec![sheet]).with_names(vec![
+            DefinedName::new("ИтогоДоходы", "'Отчет'!$B$2:$B$3", None).unwrap(),
+            DefinedName::new("Колонка", "$B:$B", Some("отчет")).unwrap(),
+        ]);
+
+        let values = |cells: Vec<Arc<RwLock<Cell>>>| -> Vec<String> {
+            cells.iter().map(|c| c.read().get_value()).collect()
+        };
+
+        assert_eq!(values(f.resolve_name("итогодоходы")), vec!["20", "30"]);
+        assert_eq!(values(f.resolve_name("Колонка")).len(), 4);
+        assert!(f.resolve_name("Нет").is_empty());
+    }
 }
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::formula::lexer::{TokenKind, tokenize};
+
+/// Гиперссылка ячейки.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// Внешний адрес (URL, `mailto:`, путь к файлу) или место в книге,
+    /// например `'Лист 2'!A1` или `#ИтогоДоходы`
+    target: String,
+    /// Всплывающая подсказка
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tooltip: Option<String>,
+}
+
+impl Hyperlink {
+    /// Интициализирует гиперссылку с заданным адресом
+    pub fn new(target: &str, tooltip: Option<&str>) -> Self {
+        Hyperlink {
+            target: target.to_string(),
+            tooltip: tooltip.map(str::to_string),
+        }
+    }
+
+    #[inline]
+    pub fn get_target(&self) -> &str {
+        &self.target
+    }
+
+    #[inline]
+    pub fn get_tooltip(&self) -> Option<&str> {
+        self.tooltip.as_deref()
+    }
+
+    /// Метод для получения места в книге для внутренней ссылки.
+    /// Внутренней считается ссылка на ячейку или диапазон,
+    /// а также адрес, начинающийся с `#`.
+    pub fn get_location(&self) -> Option<&str> {
+        if let Some(location) = self.target.strip_prefix('#') {
+            return Some(location);
+        }
+
+        let is_reference = matches!(
+            tokenize(&self.target).ok().as_deref(),
+            Some([token]) if matches!(token.kind, TokenKind::Reference(_))
+        );
+
+        is_reference.then_some(self.target.as_str())
+    }
+
+    /// Изменение места внутренней ссылки теми же правилами, что и формулы.
+    /// `update` возвращает новое место, если оно изменилось.
+    /// Возвращает новый адрес, если он изменился.
+    pub(crate) fn update_location<F: Fn(&str) -> Option<String>>(
+        &self,
+        update: F,
+    ) -> Option<String> {
+        let location = update(self.get_location()?)?;
+
+        Some(if self.target.starts_with('#') {
+            format!("#{location}")
+        } else {
+            location
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::shift::rename_sheet;
+
+    #[test]
+    fn hyperlink_location() {
+        let location = |target| {
+            Hyperlink::new(target, None)
+                .get_location()
+                .map(str::to_string)
+        };
+
+        assert_eq!(location("'Лист 2'!A1"), Some("'Лист 2'!A1".to_string()));
+        assert_eq!(
+            location("Детали!$B$2:$C$3"),
+            Some("Детали!$B$2:$C$3".to_string())
+        );
+        assert_eq!(location("#Итого"), Some("Итого".to_string()));
+        assert_eq!(location("https://example.com/report?id=1"), None);
+        assert_eq!(location("mailto:info@example.com"), None);
+        assert_eq!(location("report.xlsx"), None);
+    }
+
+    #[test]
+    fn hyperlink_rename_sheet() {
+        let rename = |target| {
+            Hyperlink::new(target, None)
+                .update_location(|l| rename_sheet(l, "Лист 2", "Итоги 2024"))
+        };
+
+        assert_eq!(rename("'Лист 2'!A1"), Some("'Итоги 2024'!A1".to_string()));
+        assert_eq!(
+            rename("#'лист 2'!$B$2:$C$3"),
+            Some("#'Итоги 2024'!$B$2:$C$3".to_string())
+        );
+        assert_eq!(rename("Детали!A1"), None);
+        assert_eq!(rename("#Итого"), None);
+        assert_eq!(rename("https://example.com/Лист 2"), None);
+    }
+}
@@ -1,9 +1,16 @@
-use super::range::Range;
-use serde::Serialize;
+use super::{coordinate::Coordinate, range::Range};
+use crate::{MAX_COL, MAX_ROW, formula::shift::Shift};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Clone, Default, Debug, Serialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct MergeCells {
-    #[serde(rename = "merge_cells", serialize_with = "serialize_vec_range")]
+    #[serde(
+        rename = "merge_cells",
+        default,
+        serialize_with = "serialize_vec_range",
+        deserialize_with = "deserialize_vec_range"
+    )]
     range: Vec<Range>,
 }
 
@@ -19,18 +26,123 @@ impl MergeCells {
         &self.range
     }
 
-    /// Метод для добавления диапазона слияния
+    /// Метод для получения диапазона слияния, в который входит ячейка
     #[inline]
-    pub fn add_range(&mut self, range: Range) {
+    pub fn get_range_at(&self, coordinate: &Coordinate) -> Option<&Range> {
+        self.range.iter().find(|r| r.contains(coordinate))
+    }
+
+    /// Метод для добавления диапазона слияния.
+    /// Диапазон должен быть в пределах листа, больше одной ячейки
+    /// и не пересекаться с другими слияниями.
+    pub fn add_range(&mut self, range: Range) -> Result<()> {
+        let Range {
+            start_row,
+            end_row,
+            start_col,
+            end_col,
+        } = range;
+
+        if start_row > end_row || start_col > end_col {
+            bail!("Merge range ({start_row}, {end_row}, {start_col}, {end_col}) is inverted");
+        }
+        if start_row == 0 || start_col == 0 || end_row > MAX_ROW || end_col > MAX_COL {
+            bail!(
+                "Merge range ({start_row}, {end_row}, {start_col}, {end_col}) is out of sheet bounds"
+            );
+        }
+        if range.size() == 1 {
+            bail!("Merge range {range} must contain more than one cell");
+        }
+        if let Some(other) = self.range.iter().find(|r| r.intersects(&range)) {
+            bail!("Merge range {range} overlaps merge range {other}");
+        }
+
         self.range.push(range);
+
+        Ok(())
+    }
+
+    /// Метод для удаления диапазона слияния. `false` - диапазона нет
+    pub fn remove_range(&mut self, range: &Range) -> bool {
+        let len = self.range.len();
+        self.range.retain(|r| r != range);
+
+        self.range.len() != len
+    }
+
+    /// Метод для удаления слияния, в которое входит ячейка
+    pub fn remove_range_at(&mut self, coordinate: &Coordinate) -> Option<Range> {
+        let idx = self.range.iter().position(|r| r.contains(coordinate))?;
+
+        Some(self.range.remove(idx))
+    }
+
+    /// Метод для сдвига диапазонов при вставке или удалении строк/колонок.
+    /// Диапазоны, от которых осталась одна ячейка, удаляются.
+    pub(crate) fn shift(&mut self, shift: &Shift) {
+        if shift.amount == 0 {
+            return;
+        }
+
+        self.range = self
+            .range
+            .iter()
+            .filter_map(|r| r.shift(shift))
+            .filter(|r| r.start_row != r.end_row || r.start_col != r.end_col)
+            .collect();
     }
 }
 
 // Для сериализации
-fn serialize_vec_range<S>(ranges: &[Range], serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_vec_range<S>(ranges: &[Range], serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
     let tuples: Vec<(u32, u32, u16, u16)> = ranges.iter().map(|r| r.into()).collect();
     tuples.serialize(serializer)
 }
+
+// Для десериализации
+pub(crate) fn deserialize_vec_range<'de, D>(deserializer: D) -> Result<Vec<Range>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tuples = Vec::<(u32, u32, u16, u16)>::deserialize(deserializer)?;
+
+    Ok(tuples.into_iter().map(Range::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_range() {
+        let mut merges = MergeCells::default();
+
+        assert!(merges.add_range(Range::new(1, 2, 1, 3)).is_ok());
+        assert!(merges.add_range(Range::new(3, 3, 1, 2)).is_ok());
+
+        for range in [
+            Range::new(2, 4, 3, 4),
+            Range::new(5, 4, 1, 1),
+            Range::new(5, 5, 2, 1),
+            Range::new(5, 5, 1, 1),
+            Range::new(0, 1, 5, 5),
+            Range::new(5, 5, MAX_COL, MAX_COL + 1),
+        ] {
+            assert!(merges.add_range(range).is_err());
+        }
+        assert_eq!(merges.get_collection().len(), 2);
+
+        assert_eq!(
+            merges.remove_range_at(&Coordinate::new(2, 2)),
+            Some(Range::new(1, 2, 1, 3))
+        );
+        assert_eq!(merges.remove_range_at(&Coordinate::new(2, 2)), None);
+        assert!(merges.remove_range(&Range::new(3, 3, 1, 2)));
+        assert!(!merges.remove_range(&Range::new(3, 3, 1, 2)));
+        assert!(merges.get_collection().is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt::Display;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::range::Range;
+use crate::{
+    MAX_COL, MAX_ROW,
+    formula::shift::{Axis, Shift},
+};
+
+/// Максимальная длина колонтитула в Excel.
+const MAX_HEADER_FOOTER_LEN: usize = 255;
+
+/// Параметры страницы и печати листа.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PageSetup {
+    /// Область печати
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub print_area: Option<Range>,
+    pub orientation: Orientation,
+    /// Размер бумаги, код Excel: 1 - Letter, 8 - A3, 9 - A4, 11 - A5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paper_size: Option<u16>,
+    /// Поля страницы в дюймах
+    pub margins: Margins,
+    /// Масштаб печати в процентах. Не используется при `fit_to_page`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<u16>,
+    /// Разместить лист на заданном количестве страниц
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fit_to_page: Option<FitToPage>,
+    /// Строки, повторяемые на каждой странице, например `(1, 2)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub print_title_rows: Option<(u32, u32)>,
+    /// Колонки, повторяемые на каждой странице
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub print_title_cols: Option<(u16, u16)>,
+    /// Верхний колонтитул с кодами Excel, например `&CОтчет&RСтр. &P из &N`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    /// Нижний колонтитул
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+}
+
+impl PageSetup {
+    /// Метод для проверки параметров перед установкой на лист
+    pub fn validate(&self) -> Result<()> {
+        if let Some(scale) = self.scale
+            && !(10..=400).contains(&scale)
+        {
+            bail!("Print scale must be in 10..=400, got {scale}");
+        }
+        if self.paper_size == Some(0) {
+            bail!("Invalid paper size 0");
+        }
+        if let Some(range) = &self.print_area
+            && (range.start_row == 0
+                || range.start_col == 0
+                || range.start_row > range.end_row
+                || range.start_col > range.end_col
+                || range.end_row > MAX_ROW
+                || range.end_col > MAX_COL)
+        {
+            bail!("Invalid print area {range:?}");
+        }
+        if let Some((start, end)) = self.print_title_rows
+            && (start == 0 || start > end || end > MAX_ROW)
+        {
+            bail!("Invalid print title rows {start}:{end}");
+        }
+        if let Some((start, end)) = self.print_title_cols
+            && (start == 0 || start > end || end > MAX_COL)
+        {
+            bail!("Invalid print title columns {start}:{end}");
+        }
+
+        self.margins.validate()?;
+
+        for text in [&self.header, &self.footer].into_iter().flatten() {
+            if text.chars().count() > MAX_HEADER_FOOTER_LEN {
+                bail!("Header/footer is too long: > {MAX_HEADER_FOOTER_LEN} characters");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Заданы ли параметры по умолчанию
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Метод для сдвига области печати и сквозных строк/колонок
+    /// при вставке или удалении строк/колонок
+    pub(crate) fn shift(&mut self, shift: &Shift) {
+        self.print_area = self.print_area.as_ref().and_then(|r| r.shift(shift));
+
+        match shift.axis {
+            Axis::Row => {
+                self.print_title_rows = self
+                    .print_title_rows
+                    .and_then(|(start, end)| shift.span(start, end));
+            }
+            Axis::Col => {
+                self.print_title_cols = self.print_title_cols.and_then(|(start, end)| {
+                    shift
+                        .span(start as u32, end as u32)
+                        .map(|(s, e)| (s as u16, e as u16))
+                });
+            }
+        }
+    }
+}
+
+/// Ориентация страницы.
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    Landscape,
+}
+
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Portrait => "portrait",
+            Self::Landscape => "landscape",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Поля страницы в дюймах.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Margins {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    /// Отступ верхнего колонтитула
+    pub header: f64,
+    /// Отступ нижнего колонтитула
+    pub footer: f64,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Margins {
+            left: 0.7,
+            right: 0.7,
+            top: 0.75,
+            bottom: 0.75,
+            header: 0.3,
+            footer: 0.3,
+        }
+    }
+}
+
+impl Margins {
+    fn validate(&self) -> Result<()> {
+        let values = [
+            self.left,
+            self.right,
+            self.top,
+            self.bottom,
+            self.header,
+            self.footer,
+        ];
+
+        if values.iter().any(|v| !v.is_finite() || *v < 0.0) {
+            bail!("Page margins must be non-negative");
+        }
+
+        Ok(())
+    }
+}
+
+/// Количество страниц по ширине и высоте. 0 - без ограничения.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FitToPage {
+    #[serde(default = "one")]
+    pub width: u32,
+    #[serde(default = "one")]
+    pub height: u32,
+}
+
+impl Default for FitToPage {
+    fn default() -> Self {
+        FitToPage {
+            width: 1,
+            height: 1,
+        }
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::shift::ShiftKind;
+
+    #[test]
+    fn page_setup_json() {
+        let json = r#"{
+            "orientation": "landscape",
+            "paper_size": 9,
+            "fit_to_page": {"height": 0},
+            "print_title_rows": [1, 2],
+            "margins": {"left": 0.5}
+        }"#;
+
+        let setup: PageSetup = serde_json::from_str(json).unwrap();
+        assert_eq!(setup.orientation, Orientation::Landscape);
+        assert_eq!(
+            setup.fit_to_page,
+            Some(FitToPage {
+                width: 1,
+                height: 0
+            })
+        );
+        assert_eq!(setup.margins.left, 0.5);
+        assert_eq!(setup.margins.top, 0.75);
+        assert!(setup.validate().is_ok());
+
+        assert!(PageSetup::default().is_default());
+        assert!(serde_json::from_str::<PageSetup>(r#"{"landscape": true}"#).is_err());
+    }
+
+    #[test]
+    fn validate_page_setup() {
+        let setup = |f: fn(&mut PageSetup)| {
+            let mut setup = PageSetup::default();
+            f(&mut setup);
+            setup.validate()
+        };
+
+        assert!(setup(|s| s.scale = Some(5)).is_err());
+        assert!(setup(|s| s.print_title_rows = Some((3, 1))).is_err());
+        assert!(setup(|s| s.margins.top = -1.0).is_err());
+        assert!(setup(|s| s.footer = Some("x".repeat(256))).is_err());
+        assert!(setup(|s| s.print_area = Some(Range::new(1, 10, 1, 4))).is_ok());
+    }
+
+    #[test]
+    fn shift_print_titles() {
+        let mut setup = PageSetup {
+            print_area: Some(Range::new(1, 10, 1, 4)),
+            print_title_rows: Some((2, 3)),
+            ..Default::default()
+        };
+        let shift = Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx: 1,
+            amount: 1,
+            sheet: None,
+            local: true,
+        };
+
+        setup.shift(&shift);
+        assert_eq!(setup.print_area, Some(Range::new(1, 9, 1, 4)));
+        assert_eq!(setup.print_title_rows, Some((1, 2)));
+
+        setup.shift(&Shift { amount: 2, ..shift });
+        assert_eq!(setup.print_title_rows, None);
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Защита листа. Флаги действий разрешают их на защищенном листе.
+/// Изменять можно только ячейки со снятой блокировкой в стиле.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SheetProtection {
+    /// Хеш пароля в формате Excel, например `CBEB`. `None` - без пароля
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+    pub select_locked_cells: bool,
+    pub select_unlocked_cells: bool,
+    pub format_cells: bool,
+    pub format_columns: bool,
+    pub format_rows: bool,
+    pub insert_columns: bool,
+    pub insert_rows: bool,
+    pub insert_hyperlinks: bool,
+    pub delete_columns: bool,
+    pub delete_rows: bool,
+    pub sort: bool,
+    pub auto_filter: bool,
+    pub pivot_tables: bool,
+    /// Изменение фигур и диаграмм
+    pub objects: bool,
+    pub scenarios: bool,
+}
+
+impl Default for SheetProtection {
+    fn default() -> Self {
+        SheetProtection {
+            password_hash: None,
+            select_locked_cells: true,
+            select_unlocked_cells: true,
+            format_cells: false,
+            format_columns: false,
+            format_rows: false,
+            insert_columns: false,
+            insert_rows: false,
+            insert_hyperlinks: false,
+            delete_columns: false,
+            delete_rows: false,
+            sort: false,
+            auto_filter: false,
+            pivot_tables: false,
+            objects: false,
+            scenarios: false,
+        }
+    }
+}
+
+impl SheetProtection {
+    /// Метод для установки пароля. Пустой пароль снимает пароль
+    pub fn set_password(&mut self, password: &str) -> &mut Self {
+        self.password_hash = (!password.is_empty()).then(|| password_hash(password));
+
+        self
+    }
+
+    /// Метод для проверки настроек перед установкой на лист
+    pub fn validate(&self) -> Result<()> {
+        validate_hash(self.password_hash.as_deref())
+    }
+}
+
+/// Защита структуры книги: листы нельзя добавлять, удалять,
+/// переименовывать, перемещать, скрывать и показывать.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkbookProtection {
+    /// Хеш пароля в формате Excel. `None` - без пароля
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+    pub lock_structure: bool,
+    /// Запрет изменения размеров и положения окон книги
+    pub lock_windows: bool,
+}
+
+impl Default for WorkbookProtection {
+    fn default() -> Self {
+        WorkbookProtection {
+            password_hash: None,
+            lock_structure: true,
+            lock_windows: false,
+        }
+    }
+}
+
+impl WorkbookProtection {
+    /// Метод для установки пароля. Пустой пароль снимает пароль
+    pub fn set_password(&mut self, password: &str) -> &mut Self {
+        self.password_hash = (!password.is_empty()).then(|| password_hash(password));
+
+        self
+    }
+
+    /// Метод для проверки настроек перед установкой на книгу
+    pub fn validate(&self) -> Result<()> {
+        validate_hash(self.password_hash.as_deref())
+    }
+}
+
+/// Хеш пароля защиты листа и книги по алгоритму Excel.
+/// Это не шифрование: хеш лишь защищает от случайных изменений.
+pub fn password_hash(password: &str) -> String {
+    let chars: Vec<u16> = password.encode_utf16().collect();
+
+    let mut hash: u16 = 0;
+    for c in chars.iter().rev() {
+        hash = ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+        hash ^= c;
+    }
+    hash = ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+    hash ^= chars.len() as u16;
+    hash ^= 0xCE4B;
+
+    format!("{hash:04X}")
+}
+
+fn validate_hash(hash: Option<&str>) -> Result<()> {
+    if let Some(hash) = hash
+        && (hash.len() != 4 || !hash.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        bail!("Invalid password hash: {hash}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_password() {
+        assert_eq!(password_hash("secret"), "DAA7");
+        assert_eq!(password_hash("test"), "CBEB");
+
+        let mut protection = SheetProtection::default();
+        protection.set_password("secret");
+        assert_eq!(protection.password_hash.as_deref(), Some("DAA7"));
+        assert!(protection.validate().is_ok());
+
+        protection.set_password("");
+        assert_eq!(protection.password_hash, None);
+    }
+
+    #[test]
+    fn protection_json() {
+        let protection: SheetProtection =
+            serde_json::from_str(r#"{"sort": true, "auto_filter": true}"#).unwrap();
+
+        assert!(protection.sort);
+        assert!(protection.select_locked_cells);
+        assert!(!protection.format_cells);
+
+        assert!(serde_json::from_str::<SheetProtection>(r#"{"password": "1"}"#).is_err());
+        assert!(
+            serde_json::from_str::<WorkbookProtection>(r#"{"password_hash": "XYZ"}"#)
+                .unwrap()
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -1,8 +1,17 @@
-use serde::Serialize;
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::coordinate::{Coordinate, CoordinateError, parse_cell_index};
+use crate::{
+    MAX_COL, MAX_ROW,
+    formula::shift::{Axis, Shift},
+    utils::{get_letter_coordinate, index_to_alpha},
+};
 
 pub type MergedRange = (u32, u32, u16, u16);
 
-#[derive(Clone, Default, Debug, Serialize, PartialEq, Eq)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Range {
     pub start_row: u32,
     pub end_row: u32,
@@ -20,6 +29,164 @@ impl Range {
             end_col,
         }
     }
+
+    /// Разбор диапазона в стиле A1: `B2:D10`, `$B$2`, колонки `A:C` и строки `3:5`.
+    /// Границы диапазона упорядочиваются.
+    pub fn parse(value: &str) -> Result<Self, CoordinateError> {
+        let invalid = || CoordinateError::Invalid(value.to_string());
+        let index = |part: &str| {
+            parse_cell_index(part).map_err(|e| match e {
+                CoordinateError::Invalid(_) => invalid(),
+                CoordinateError::OutOfBounds(_) => CoordinateError::OutOfBounds(value.to_string()),
+            })
+        };
+
+        let (start, end) = match value.split_once(':') {
+            Some((start, end)) => (index(start)?, index(end)?),
+            None => {
+                let index = index(value)?;
+                (index, index)
+            }
+        };
+        let is_span = value.contains(':');
+
+        let (rows, cols) = match (start, end) {
+            ((Some(r1), Some(c1)), (Some(r2), Some(c2))) => ((r1, r2), (c1, c2)),
+            ((None, Some(c1)), (None, Some(c2))) if is_span => ((1, MAX_ROW), (c1, c2)),
+            ((Some(r1), None), (Some(r2), None)) if is_span => ((r1, r2), (1, MAX_COL)),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Range::new(
+            rows.0.min(rows.1),
+            rows.0.max(rows.1),
+            cols.0.min(cols.1),
+            cols.0.max(cols.1),
+        ))
+    }
+
+    /// Количество строк диапазона
+    #[inline]
+    pub fn height(&self) -> u32 {
+        (self.end_row + 1).saturating_sub(self.start_row)
+    }
+
+    /// Количество колонок диапазона
+    #[inline]
+    pub fn width(&self) -> u16 {
+        (self.end_col as u32 + 1).saturating_sub(self.start_col as u32) as u16
+    }
+
+    /// Количество ячеек диапазона
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.height() as u64 * self.width() as u64
+    }
+
+    /// Входит ли ячейка в диапазон
+    #[inline]
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        (self.start_row..=self.end_row).contains(&coordinate.row)
+            && (self.start_col..=self.end_col).contains(&coordinate.column)
+    }
+
+    /// Есть ли у диапазонов общие ячейки
+    #[inline]
+    pub fn intersects(&self, other: &Range) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Метод для получения общей части диапазонов. `None` - общих ячеек нет
+    pub fn intersection(&self, other: &Range) -> Option<Range> {
+        let range = Range::new(
+            self.start_row.max(other.start_row),
+            self.end_row.min(other.end_row),
+            self.start_col.max(other.start_col),
+            self.end_col.min(other.end_col),
+        );
+
+        (range.start_row <= range.end_row && range.start_col <= range.end_col).then_some(range)
+    }
+
+    /// Метод для получения наименьшего диапазона, содержащего оба диапазона
+    pub fn union(&self, other: &Range) -> Range {
+        Range::new(
+            self.start_row.min(other.start_row),
+            self.end_row.max(other.end_row),
+            self.start_col.min(other.start_col),
+            self.end_col.max(other.end_col),
+        )
+    }
+
+    /// Координаты ячеек диапазона по строкам слева направо
+    pub fn cells(&self) -> impl Iterator<Item = Coordinate> + use<> {
+        let (start_col, end_col) = (self.start_col, self.end_col);
+
+        (self.start_row..=self.end_row)
+            .flat_map(move |row| (start_col..=end_col).map(move |col| Coordinate::new(row, col)))
+    }
+
+    /// Метод для сдвига диапазона при вставке или удалении строк/колонок.
+    /// `None` - диапазон удален целиком.
+    pub(crate) fn shift(&self, shift: &Shift) -> Option<Range> {
+        let mut range = self.clone();
+
+        match shift.axis {
+            Axis::Row => {
+                (range.start_row, range.end_row) = shift.span(range.start_row, range.end_row)?;
+            }
+            Axis::Col => {
+                let (start, end) = shift.span(range.start_col as u32, range.end_col as u32)?;
+                (range.start_col, range.end_col) = (start as u16, end as u16);
+            }
+        }
+
+        Some(range)
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let whole_cols = self.start_row == 1 && self.end_row == MAX_ROW;
+        let whole_rows = self.start_col == 1 && self.end_col == MAX_COL;
+
+        match (whole_cols, whole_rows) {
+            (true, false) => write!(
+                f,
+                "{}:{}",
+                index_to_alpha(self.start_col),
+                index_to_alpha(self.end_col)
+            ),
+            (false, true) => write!(f, "{}:{}", self.start_row, self.end_row),
+            _ => {
+                let start = get_letter_coordinate(self.start_row, self.start_col);
+                if self.start_row == self.end_row && self.start_col == self.end_col {
+                    write!(f, "{start}")
+                } else {
+                    let end = get_letter_coordinate(self.end_row, self.end_col);
+                    write!(f, "{start}:{end}")
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = CoordinateError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Range::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Range {
+    type Error = CoordinateError;
+
+    #[inline]
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Range::parse(value)
+    }
 }
 
 impl From<(u32, u32, u16, u16)> for Range {
@@ -52,3 +219,66 @@ impl From<&Range> for (u32, u32, u16, u16) {
         )
     }
 }
+
+/// Левая верхняя ячейка (row, col) первого диапазона, от которой
+/// задаются относительные ссылки условного форматирования и проверки данных
+pub(crate) fn top_left(ranges: &[Range]) -> Option<(u32, u16)> {
+    ranges.first().map(|r| (r.start_row, r.start_col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range() {
+        let range = Range::parse("B2:D10").unwrap();
+        assert_eq!(range, Range::new(2, 10, 2, 4));
+        assert_eq!(range.to_string(), "B2:D10");
+        assert_eq!(Range::parse("$D$10:b2"), Ok(range));
+
+        assert_eq!(Range::parse("C3"), Ok(Range::new(3, 3, 3, 3)));
+        assert_eq!(Range::new(3, 3, 3, 3).to_string(), "C3");
+
+        let cols = Range::parse("A:C").unwrap();
+        assert_eq!(cols, Range::new(1, MAX_ROW, 1, 3));
+        assert_eq!(cols.to_string(), "A:C");
+
+        let rows = Range::parse("$3:5").unwrap();
+        assert_eq!(rows, Range::new(3, 5, 1, MAX_COL));
+        assert_eq!(rows.to_string(), "3:5");
+
+        for value in ["", "A", "3", "A1:", "A:3", "A1:C", "A1:B2:C3", "A1-B2"] {
+            assert_eq!(
+                Range::parse(value),
+                Err(CoordinateError::Invalid(value.to_string()))
+            );
+        }
+        assert_eq!(
+            Range::parse("A1:XFE1"),
+            Err(CoordinateError::OutOfBounds("A1:XFE1".to_string()))
+        );
+    }
+
+    #[test]
+    fn range_operations() {
+        let range = Range::parse("B2:D4").unwrap();
+        let other = Range::parse("C3:E6").unwrap();
+
+        assert_eq!((range.height(), range.width(), range.size()), (3, 3, 9));
+        assert!(range.contains(&Coordinate::new(4, 2)));
+        assert!(!range.contains(&Coordinate::new(5, 2)));
+
+        assert!(range.intersects(&other));
+        assert_eq!(range.intersection(&other), Some(Range::new(3, 4, 3, 4)));
+        assert_eq!(range.union(&other), Range::new(2, 6, 2, 5));
+        assert!(!range.intersects(&Range::parse("E1:F1").unwrap()));
+
+        let cells: Vec<String> = Range::parse("A1:B2")
+            .unwrap()
+            .cells()
+            .map(|c| String::from(&c))
+            .collect();
+        assert_eq!(cells, ["A1", "B1", "A2", "B2"]);
+    }
+}
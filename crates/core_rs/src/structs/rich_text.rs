@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::style::Font;
+
+/// Фрагмент форматированного текста.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TextRun {
+    pub text: String,
+    /// Шрифт фрагмента. `None` - шрифт ячейки
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<Font>,
+}
+
+impl TextRun {
+    /// Интициализирует фрагмент с заданным текстом и шрифтом
+    pub fn new(text: &str, font: Option<Font>) -> Self {
+        TextRun {
+            text: text.to_string(),
+            font,
+        }
+    }
+}
+
+/// Форматированный текст ячейки из фрагментов со своим шрифтом,
+/// например жирная подпись рядом с обычным значением.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RichText {
+    pub runs: Vec<TextRun>,
+}
+
+impl RichText {
+    /// Интициализирует текст из фрагментов
+    pub fn new(runs: Vec<TextRun>) -> Self {
+        RichText { runs }
+    }
+
+    #[inline]
+    pub fn get_runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /// Метод для получения текста без форматирования
+    #[inline]
+    pub fn get_text(&self) -> String {
+        self.runs.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    /// Есть ли в тексте символы
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|r| r.text.is_empty())
+    }
+}
+
+impl Display for RichText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for run in self.runs.iter() {
+            write!(f, "{}", run.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rich_text_json() {
+        let json = r#"{"runs": [
+            {"text": "Итого: ", "font": {"bold": true}},
+            {"text": "100"}
+        ]}"#;
+
+        let text: RichText = serde_json::from_str(json).unwrap();
+        assert_eq!(text.get_text(), "Итого: 100");
+        assert_eq!(text.to_string(), "Итого: 100");
+        assert!(text.runs[0].font.as_ref().is_some_and(|f| f.bold));
+        assert_eq!(text.runs[1].font, None);
+
+        assert_eq!(
+            serde_json::to_string(&text).unwrap(),
+            r#"{"runs":[{"text":"Итого: ","font":{"bold":true}},{"text":"100"}]}"#
+        );
+        assert!(serde_json::from_str::<RichText>(r#"{"runs": [{"bold": true}]}"#).is_err());
+    }
+}
@@ -1,8 +1,8 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::DEFAULT_ROW_HEIGHT;
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Default)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Default)]
 pub struct Row {
     row_num: u32,
     height: f64,
@@ -20,6 +20,14 @@ impl Row {
         }
     }
 
+    pub fn get_row_num(&self) -> u32 {
+        self.row_num
+    }
+
+    pub(crate) fn set_row_num(&mut self, val: u32) {
+        self.row_num = val;
+    }
+
     pub fn get_height(&self) -> &f64 {
         &self.height
     }
@@ -28,6 +36,16 @@ impl Row {
         &self.hidden
     }
 
+    /// Уровень группировки, 0 - вне группы
+    pub fn get_level(&self) -> &u32 {
+        &self.level
+    }
+
+    /// Свернута ли группа, итоговой для которой является эта строка
+    pub fn get_collapsed(&self) -> &bool {
+        &self.collapsed
+    }
+
     pub fn set_height(&mut self, val: f64) -> &mut Self {
         self.height = val;
 
@@ -39,4 +57,16 @@ impl Row {
 
         self
     }
+
+    pub fn set_level(&mut self, val: u32) -> &mut Self {
+        self.level = val;
+
+        self
+    }
+
+    pub fn set_collapsed(&mut self, val: bool) -> &mut Self {
+        self.collapsed = val;
+
+        self
+    }
 }
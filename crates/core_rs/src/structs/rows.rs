@@ -1,15 +1,37 @@
 use ahash::HashMap;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::DEFAULT_ROW_HEIGHT;
+use crate::{
+    DEFAULT_ROW_HEIGHT,
+    formula::shift::{Axis, Shift},
+};
 
 use super::row::Row;
 
-#[derive(Clone, Debug, Serialize)]
+/// Вспомогательная функция для десериализации строк с восстановлением ключей по номеру строки.
+fn deserialize_rows<'de, D>(deserializer: D) -> Result<HashMap<u32, Box<Row>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rows = HashMap::<String, Box<Row>>::deserialize(deserializer)?;
+
+    Ok(rows
+        .into_values()
+        .map(|row| (row.get_row_num(), row))
+        .collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub(crate) struct Rows {
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(
+        skip_serializing_if = "HashMap::is_empty",
+        deserialize_with = "deserialize_rows"
+    )]
     rows: HashMap<u32, Box<Row>>,
     default_height: f64,
+    /// Итоговая строка группы под строками группы
+    summary_below: bool,
 }
 
 impl Default for Rows {
@@ -17,6 +39,7 @@ impl Default for Rows {
         Self {
             rows: HashMap::default(),
             default_height: DEFAULT_ROW_HEIGHT,
+            summary_below: true,
         }
     }
 }
@@ -42,6 +65,64 @@ impl Rows {
         row.set_hidden(val);
     }
 
+    #[inline]
+    pub(crate) fn set_level(&mut self, row_num: u32, val: u32) {
+        let row = self
+            .rows
+            .entry(row_num)
+            .or_insert(Box::new(Row::new(row_num)));
+
+        row.set_level(val);
+    }
+
+    #[inline]
+    pub(crate) fn set_collapsed(&mut self, row_num: u32, val: bool) {
+        let row = self
+            .rows
+            .entry(row_num)
+            .or_insert(Box::new(Row::new(row_num)));
+
+        row.set_collapsed(val);
+    }
+
+    #[inline]
+    pub(crate) fn set_summary_below(&mut self, val: bool) {
+        self.summary_below = val;
+    }
+
+    /// Метод для перестроения строк при вставке или удалении строк листа
+    pub(crate) fn shift(&mut self, shift: &Shift) {
+        if shift.axis != Axis::Row || shift.amount == 0 {
+            return;
+        }
+
+        self.rows = self
+            .rows
+            .drain()
+            .filter_map(|(_, mut row)| {
+                let row_num = shift.position(row.get_row_num())?;
+                row.set_row_num(row_num);
+
+                Some((row_num, row))
+            })
+            .collect();
+    }
+
+    /// Метод для получения высоты строки по умолчанию
+    #[inline]
+    pub(crate) fn get_default_height(&self) -> &f64 {
+        &self.default_height
+    }
+
+    /// Метод для получения коллекции строк, отсортированной по номеру
+    #[inline]
+    pub(crate) fn get_collection_sorted(&self) -> Vec<&Row> {
+        let mut rows: Vec<_> = self.rows.values().map(|r| r.as_ref()).collect();
+        rows.sort_unstable_by_key(|r| r.get_row_num());
+
+        rows
+    }
+
     #[inline]
     pub(crate) fn get_heignt(&self, row_num: u32) -> &f64 {
         self.rows
@@ -57,6 +138,37 @@ impl Rows {
             .map(|r| r.get_hidden())
             .unwrap_or_else(|| &false)
     }
+
+    #[inline]
+    pub(crate) fn get_level(&self, row_num: u32) -> &u32 {
+        self.rows
+            .get(&row_num)
+            .map(|r| r.get_level())
+            .unwrap_or_else(|| &0)
+    }
+
+    #[inline]
+    pub(crate) fn get_collapsed(&self, row_num: u32) -> &bool {
+        self.rows
+            .get(&row_num)
+            .map(|r| r.get_collapsed())
+            .unwrap_or_else(|| &false)
+    }
+
+    /// Метод для получения максимального уровня группировки строк
+    #[inline]
+    pub(crate) fn get_max_level(&self) -> u32 {
+        self.rows
+            .values()
+            .map(|r| *r.get_level())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    pub(crate) fn get_summary_below(&self) -> bool {
+        self.summary_below
+    }
 }
 
 #[cfg(test)]
@@ -90,4 +202,17 @@ mod tests {
         assert!(*rows.get_hidden(1));
         assert_eq!(*rows.get_heignt(1), DEFAULT_ROW_HEIGHT);
     }
+
+    #[test]
+    fn new_set_level() {
+        let mut rows = Rows::default();
+
+        rows.set_level(2, 1);
+        rows.set_level(3, 2);
+
+        assert_eq!(*rows.get_level(3), 2);
+        assert_eq!(*rows.get_level(4), 0);
+        assert_eq!(rows.get_max_level(), 2);
+        assert!(rows.get_summary_below());
+    }
 }
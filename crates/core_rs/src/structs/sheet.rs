@@ -1,27 +1,61 @@
 use std::sync::Arc;
 
 use ahash::HashMap;
-use anyhow::Result;
+use anyhow::{Result, bail};
 use parking_lot::RwLock;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::traits::{ReadableSheet, WriteableSheet};
+use crate::{
+    MAX_COL, MAX_OUTLINE_LEVEL, MAX_OUTLINE_ROWS, MAX_ROW, MAX_SHEET_NAME_LEN,
+    formula::shift::{Axis, Shift, ShiftKind, shift_formula},
+    funcs::{find_cell_by_regex, find_cell_by_str},
+    traits::{ReadableCell, ReadableSheet, WriteableCell, WriteableSheet},
+};
 
 use super::{
-    cell::Cell, cells::Cells, columns::Columns, comment::Comment, coordinate::Coordinate,
-    merge_cells::MergeCells, range::Range, rows::Rows,
+    cell::Cell,
+    cells::{Cells, SearchOrder},
+    columns::Columns,
+    comment::Comment,
+    conditional_format::ConditionalFormat,
+    coordinate::Coordinate,
+    data_validation::DataValidation,
+    merge_cells::MergeCells,
+    page_setup::PageSetup,
+    protection::SheetProtection,
+    range::Range,
+    rows::Rows,
+    sheet_view::SheetView,
 };
 
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Sheet {
     name: String,
     sheet_state: Box<str>,
+    /// Лист оглавления, построенный `Book::build_toc`
+    #[serde(default, skip_serializing_if = "crate::utils::is_false")]
+    toc: bool,
     #[serde(flatten)]
     merge_cells: MergeCells,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    conditional_formats: Vec<ConditionalFormat>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    data_validations: Vec<DataValidation>,
+    #[serde(default, skip_serializing_if = "SheetView::is_default")]
+    sheet_view: SheetView,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_filter: Option<Range>,
+    #[serde(default, skip_serializing_if = "PageSetup::is_default")]
+    page_setup: PageSetup,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    protection: Option<SheetProtection>,
     #[serde(flatten)]
     cells: Cells,
+    #[serde(default)]
     row_dimensions: Rows,
+    #[serde(default)]
     column_dimensions: Columns,
+    #[serde(default)]
     comments: Vec<Arc<RwLock<Comment>>>,
 }
 
@@ -50,6 +84,172 @@ impl Sheet {
             ..Default::default()
         }
     }
+
+    /// Метод для преобразования листа в JSON
+    #[inline]
+    pub fn to_json(&self) -> Result<String> {
+        match serde_json::to_string(self) {
+            Ok(j) => Ok(j),
+            Err(e) => {
+                bail!("Failed to convert in JSON: {}", e);
+            }
+        }
+    }
+
+    /// Метод для восстановления листа из JSON
+    #[inline]
+    pub fn from_json(value: &str) -> Result<Self> {
+        match serde_json::from_str(value) {
+            Ok(sheet) => Ok(sheet),
+            Err(e) => {
+                bail!("Failed to convert from JSON: {}", e);
+            }
+        }
+    }
+
+    /// Является ли лист оглавлением, построенным `Book::build_toc`
+    #[inline]
+    pub(crate) fn is_toc(&self) -> bool {
+        self.toc
+    }
+
+    #[inline]
+    pub(crate) fn set_toc(&mut self, value: bool) {
+        self.toc = value;
+    }
+
+    /// Метод для сдвига объединений, комментариев, размеров строк/колонок,
+    /// автофильтра, области печати, условного форматирования, проверок данных
+    /// и внутренних гиперссылок
+    fn shift_structure(&mut self, shift: &Shift) {
+        if shift.amount == 0 || shift.idx == 0 {
+            return;
+        }
+
+        self.merge_cells.shift(shift);
+        self.row_dimensions.shift(shift);
+        self.column_dimensions.shift(shift);
+        self.auto_filter = self.auto_filter.as_ref().and_then(|r| r.shift(shift));
+        self.page_setup.shift(shift);
+
+        // Ссылки на свой лист в формулах правил и гиперссылках могут быть с именем листа
+        let local = Shift {
+            sheet: Some(&self.name),
+            ..*shift
+        };
+        self.conditional_formats.retain_mut(|cf| cf.shift(&local));
+        self.data_validations.retain_mut(|dv| dv.shift(&local));
+        self.cells.update_hyperlinks(|l| shift_formula(l, &local));
+
+        self.comments.retain(|comment| {
+            let mut guard = comment.write();
+            let Coordinate { row, column } = guard.get_coordinate().clone();
+
+            let new_coord = match shift.axis {
+                Axis::Row => shift.position(row).map(|r| Coordinate::new(r, column)),
+                Axis::Col => shift
+                    .position(column as u32)
+                    .map(|c| Coordinate::new(row, c as u16)),
+            };
+
+            match new_coord {
+                Some(coord) => {
+                    guard.set_coordinate(coord);
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Метод для пересчета ссылок в формулах и гиперссылках после изменения другого листа
+    #[inline]
+    pub(crate) fn shift_formulas(&mut self, shift: &Shift) {
+        self.update_formulas(|f| shift_formula(f, shift));
+        self.update_hyperlinks(|l| shift_formula(l, shift));
+    }
+
+    /// Метод для изменения формул ячеек, условного форматирования и проверок данных.
+    /// `update` возвращает новую формулу, если она изменилась.
+    pub(crate) fn update_formulas<F: Fn(&str) -> Option<String>>(&mut self, update: F) {
+        self.cells.update_formulas(&update);
+
+        for cf in self.conditional_formats.iter_mut() {
+            cf.update_formulas(&update);
+        }
+        for dv in self.data_validations.iter_mut() {
+            dv.update_formulas(&update);
+        }
+    }
+
+    /// Метод для изменения мест внутренних гиперссылок ячеек.
+    /// `update` возвращает новое место, если оно изменилось.
+    #[inline]
+    pub(crate) fn update_hyperlinks<F: Fn(&str) -> Option<String>>(&mut self, update: F) {
+        self.cells.update_hyperlinks(update);
+    }
+
+    /// Метод для установки признака свернутой группы у итоговой строки
+    #[inline]
+    pub(crate) fn set_collapsed_row(&mut self, row_num: u32, val: bool) {
+        self.row_dimensions.set_collapsed(row_num, val);
+    }
+
+    /// Метод для установки признака свернутой группы у итоговой колонки
+    #[inline]
+    pub(crate) fn set_collapsed_column(&mut self, col_num: u16, val: bool) {
+        self.column_dimensions.set_collapsed(col_num, val);
+    }
+
+    /// Итоговая строка группы: под группой или над ней
+    fn summary_row(&self, start: u32, end: u32) -> Option<u32> {
+        if self.row_dimensions.get_summary_below() {
+            (end < MAX_ROW).then_some(end + 1)
+        } else {
+            (start > 1).then(|| start - 1)
+        }
+    }
+
+    /// Итоговая колонка группы: справа от группы или слева
+    fn summary_col(&self, start: u16, end: u16) -> Option<u16> {
+        if self.column_dimensions.get_summary_right() {
+            (end < MAX_COL).then_some(end + 1)
+        } else {
+            (start > 1).then(|| start - 1)
+        }
+    }
+
+    /// Левая верхняя ячейка объединения, в которое входит ячейка, или сама ячейка
+    fn merge_anchor(&self, coordinate: Coordinate) -> Coordinate {
+        match self.merge_cells.get_range_at(&coordinate) {
+            Some(range) => Coordinate::new(range.start_row, range.start_col),
+            None => coordinate,
+        }
+    }
+
+    /// Ячейки в порядке обхода без закрытых объединениями: объединение
+    /// представлено только своей левой верхней ячейкой
+    fn merge_anchor_cells(&self, order: SearchOrder) -> Vec<&Arc<RwLock<Cell>>> {
+        let mut cells = self.cells.get_collection_ordered(order);
+        cells.retain(|cell| {
+            let coordinate = cell.read().get_coordinate().clone();
+            self.merge_anchor(coordinate.clone()) == coordinate
+        });
+
+        cells
+    }
+
+    /// Метод для получения размеров строк
+    #[inline]
+    pub(crate) fn get_row_dimensions(&self) -> &Rows {
+        &self.row_dimensions
+    }
+
+    /// Метод для получения размеров колонок
+    #[inline]
+    pub(crate) fn get_column_dimensions(&self) -> &Columns {
+        &self.column_dimensions
+    }
 }
 
 impl ReadableSheet for Sheet {
@@ -125,24 +325,125 @@ impl ReadableSheet for Sheet {
         self.column_dimensions.get_hidden(col_num)
     }
 
+    #[inline]
+    fn get_level_by_row(&self, row_num: u32) -> &u32 {
+        self.row_dimensions.get_level(row_num)
+    }
+
+    #[inline]
+    fn get_collapsed_by_row(&self, row_num: u32) -> &bool {
+        self.row_dimensions.get_collapsed(row_num)
+    }
+
+    #[inline]
+    fn get_level_by_column(&self, col_num: u16) -> &u32 {
+        self.column_dimensions.get_level(col_num)
+    }
+
+    #[inline]
+    fn get_collapsed_by_column(&self, col_num: u16) -> &bool {
+        self.column_dimensions.get_collapsed(col_num)
+    }
+
+    /// Итоговая строка группы под строками группы
+    #[inline]
+    fn get_summary_below(&self) -> bool {
+        self.row_dimensions.get_summary_below()
+    }
+
+    /// Итоговая колонка группы справа от колонок группы
+    #[inline]
+    fn get_summary_right(&self) -> bool {
+        self.column_dimensions.get_summary_right()
+    }
+
     #[inline]
     fn get_merge_cell_collection(&self) -> &[Range] {
         self.merge_cells.get_collection()
     }
 
+    #[inline]
+    fn merged_range_at(&self, coordinate: &Coordinate) -> Option<&Range> {
+        self.merge_cells.get_range_at(coordinate)
+    }
+
+    /// Значение ячейки, для объединенных ячеек - значение левой верхней ячейки
+    #[inline]
+    fn get_cell_value_merged<T>(&self, coordinate: T) -> String
+    where
+        T: Into<Coordinate>,
+    {
+        self.cells
+            .get_cell_value(self.merge_anchor(coordinate.into()))
+    }
+
+    #[inline]
+    fn get_conditional_formats(&self) -> &[ConditionalFormat] {
+        &self.conditional_formats
+    }
+
+    #[inline]
+    fn get_data_validations(&self) -> &[DataValidation] {
+        &self.data_validations
+    }
+
+    #[inline]
+    fn get_sheet_view(&self) -> &SheetView {
+        &self.sheet_view
+    }
+
+    #[inline]
+    fn get_auto_filter(&self) -> Option<&Range> {
+        self.auto_filter.as_ref()
+    }
+
+    #[inline]
+    fn get_page_setup(&self) -> &PageSetup {
+        &self.page_setup
+    }
+
+    #[inline]
+    fn get_protection(&self) -> Option<&SheetProtection> {
+        self.protection.as_ref()
+    }
+
     #[inline]
     fn get_comments(&self) -> &[Arc<RwLock<Comment>>] {
         &self.comments
     }
 
     #[inline]
-    fn find_cell_by_regex(&self, regex: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
-        self.cells.find_cell_by_regex(regex)
+    fn find_cell_by_regex(
+        &self,
+        regex: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        self.cells.find_cell_by_regex(regex, order)
     }
 
     #[inline]
-    fn find_cell_by_str(&self, value: &str) -> Result<Option<&Arc<RwLock<Cell>>>> {
-        self.cells.find_cell_by_str(value)
+    fn find_cell_by_str(
+        &self,
+        value: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        self.cells.find_cell_by_str(value, order)
+    }
+
+    fn find_cell_by_regex_merged(
+        &self,
+        regex: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        find_cell_by_regex(regex.into(), self.merge_anchor_cells(order))
+    }
+
+    fn find_cell_by_str_merged(
+        &self,
+        value: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>> {
+        find_cell_by_str(value.into(), self.merge_anchor_cells(order))
     }
 
     #[inline]
@@ -234,6 +535,37 @@ impl ReadableSheet for Sheet {
     fn find_value_by_coords(&self, row: u32, col: u16) -> Result<Option<String>> {
         self.cells.find_value_by_coords(row, col)
     }
+
+    #[inline]
+    fn find_value_by_coords_merged(&self, row: u32, col: u16) -> Result<Option<String>> {
+        let Coordinate { row, column } = self.merge_anchor(Coordinate::new(row, col));
+
+        self.cells.find_value_by_coords(row, column)
+    }
+
+    /// Значения ячеек колонки с учетом объединений: как и без объединений,
+    /// строки идут в порядке листа без повторов, но каждая ячейка
+    /// объединения возвращает значение его левой верхней ячейки
+    fn find_values_by_col_rows_merged(&self, col: u16, mut rows: Vec<u32>) -> Result<Vec<String>> {
+        rows.sort_unstable();
+        rows.dedup();
+
+        rows.into_iter()
+            .filter_map(|row| self.find_value_by_coords_merged(row, col).transpose())
+            .collect()
+    }
+
+    /// Значения ячеек строки с учетом объединений: как и без объединений,
+    /// колонки идут в порядке листа без повторов, но каждая ячейка
+    /// объединения возвращает значение его левой верхней ячейки
+    fn find_values_by_row_cols_merged(&self, row: u32, mut cols: Vec<u16>) -> Result<Vec<String>> {
+        cols.sort_unstable();
+        cols.dedup();
+
+        cols.into_iter()
+            .filter_map(|col| self.find_value_by_coords_merged(row, col).transpose())
+            .collect()
+    }
 }
 
 impl WriteableSheet for Sheet {
@@ -248,8 +580,106 @@ impl WriteableSheet for Sheet {
     }
 
     #[inline]
-    fn add_merge_range(&mut self, range: Range) {
-        self.merge_cells.add_range(range);
+    fn add_merge_range(&mut self, range: Range) -> Result<()> {
+        self.merge_cells.add_range(range)
+    }
+
+    /// Объединение с очисткой значений всех ячеек, кроме левой верхней, как в Excel
+    fn add_merge_range_clear(&mut self, range: Range) -> Result<()> {
+        self.merge_cells.add_range(range.clone())?;
+
+        let anchor = Coordinate::new(range.start_row, range.start_col);
+        self.cells
+            .get_cell_collection_by_range(
+                Some(range.start_row),
+                Some(range.end_row),
+                Some(range.start_col),
+                Some(range.end_col),
+            )
+            .for_each(|cell| {
+                let mut cell = cell.write();
+                if *cell.get_coordinate() != anchor {
+                    cell.set_value("");
+                }
+            });
+
+        Ok(())
+    }
+
+    #[inline]
+    fn remove_merge_range(&mut self, range: &Range) -> bool {
+        self.merge_cells.remove_range(range)
+    }
+
+    /// Метод для снятия объединения, в которое входит ячейка
+    #[inline]
+    fn unmerge(&mut self, coordinate: &Coordinate) -> Option<Range> {
+        self.merge_cells.remove_range_at(coordinate)
+    }
+
+    #[inline]
+    fn add_conditional_format(&mut self, value: ConditionalFormat) -> Result<()> {
+        value.validate()?;
+        if let Some(priority) = value.priority
+            && self
+                .conditional_formats
+                .iter()
+                .any(|cf| cf.priority == Some(priority))
+        {
+            bail!("Conditional format priority {priority} is already used");
+        }
+        self.conditional_formats.push(value);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn add_data_validation(&mut self, value: DataValidation) -> Result<()> {
+        value.validate()?;
+        self.data_validations.push(value);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn set_sheet_view(&mut self, value: SheetView) -> Result<()> {
+        value.validate()?;
+        self.sheet_view = value;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn set_freeze_panes(&mut self, rows: u32, cols: u16) -> Result<()> {
+        self.set_sheet_view(SheetView {
+            frozen_rows: rows,
+            frozen_cols: cols,
+            ..self.sheet_view.clone()
+        })
+    }
+
+    #[inline]
+    fn set_auto_filter(&mut self, range: Option<Range>) {
+        self.auto_filter = range;
+    }
+
+    #[inline]
+    fn set_page_setup(&mut self, value: PageSetup) -> Result<()> {
+        value.validate()?;
+        self.page_setup = value;
+
+        Ok(())
+    }
+
+    /// Метод для установки защиты листа. `None` снимает защиту
+    #[inline]
+    fn set_protection(&mut self, value: Option<SheetProtection>) -> Result<()> {
+        if let Some(protection) = &value {
+            protection.validate()?;
+        }
+        self.protection = value;
+
+        Ok(())
     }
 
     #[inline]
@@ -263,14 +693,56 @@ impl WriteableSheet for Sheet {
         self.cells.cell(coordinate, value)
     }
 
+    #[inline]
+    fn insert_cols(&mut self, idx: u16, amount: u16) {
+        self.cells.insert_cols_in(idx, amount, Some(&self.name));
+        self.shift_structure(&Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Col,
+            idx: idx as u32,
+            amount: amount as u32,
+            sheet: None,
+            local: true,
+        });
+    }
+
+    #[inline]
+    fn insert_rows(&mut self, idx: u32, amount: u32) {
+        self.cells.insert_rows_in(idx, amount, Some(&self.name));
+        self.shift_structure(&Shift {
+            kind: ShiftKind::Insert,
+            axis: Axis::Row,
+            idx,
+            amount,
+            sheet: None,
+            local: true,
+        });
+    }
+
     #[inline]
     fn delete_cols(&mut self, idx: u16, amount: u16) {
-        self.cells.delete_cols(idx, amount);
+        self.cells.delete_cols_in(idx, amount, Some(&self.name));
+        self.shift_structure(&Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Col,
+            idx: idx as u32,
+            amount: amount as u32,
+            sheet: None,
+            local: true,
+        });
     }
 
     #[inline]
     fn delete_rows(&mut self, idx: u32, amount: u32) {
-        self.cells.delete_rows(idx, amount);
+        self.cells.delete_rows_in(idx, amount, Some(&self.name));
+        self.shift_structure(&Shift {
+            kind: ShiftKind::Delete,
+            axis: Axis::Row,
+            idx,
+            amount,
+            sheet: None,
+            local: true,
+        });
     }
 
     #[inline]
@@ -292,11 +764,149 @@ impl WriteableSheet for Sheet {
     fn set_hidden_column(&mut self, col_num: u16, val: bool) {
         self.column_dimensions.set_hidden(col_num, val)
     }
+
+    /// Метод для группировки строк с заданным уровнем (1-7), 0 - разгруппировать
+    fn group_rows(&mut self, start: u32, end: u32, level: u32) -> Result<()> {
+        check_row_span(start, end)?;
+        check_level(level)?;
+
+        for row_num in start..=end {
+            self.row_dimensions.set_level(row_num, level);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для группировки колонок с заданным уровнем (1-7), 0 - разгруппировать
+    fn group_cols(&mut self, start: u16, end: u16, level: u32) -> Result<()> {
+        check_span(start as u32, end as u32, MAX_COL as u32)?;
+        check_level(level)?;
+
+        for col_num in start..=end {
+            self.column_dimensions.set_level(col_num, level);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для сворачивания группы строк: строки скрываются,
+    /// у итоговой строки ставится признак свернутой группы
+    fn collapse_rows(&mut self, start: u32, end: u32) -> Result<()> {
+        check_row_span(start, end)?;
+
+        for row_num in start..=end {
+            self.row_dimensions.set_hidden(row_num, true);
+        }
+        if let Some(summary) = self.summary_row(start, end) {
+            self.row_dimensions.set_collapsed(summary, true);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для разворачивания группы строк вместе с вложенными группами
+    fn expand_rows(&mut self, start: u32, end: u32) -> Result<()> {
+        check_row_span(start, end)?;
+
+        for row_num in start..=end {
+            self.row_dimensions.set_hidden(row_num, false);
+            self.row_dimensions.set_collapsed(row_num, false);
+        }
+        if let Some(summary) = self.summary_row(start, end) {
+            self.row_dimensions.set_collapsed(summary, false);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для сворачивания группы колонок: колонки скрываются,
+    /// у итоговой колонки ставится признак свернутой группы
+    fn collapse_cols(&mut self, start: u16, end: u16) -> Result<()> {
+        check_span(start as u32, end as u32, MAX_COL as u32)?;
+
+        for col_num in start..=end {
+            self.column_dimensions.set_hidden(col_num, true);
+        }
+        if let Some(summary) = self.summary_col(start, end) {
+            self.column_dimensions.set_collapsed(summary, true);
+        }
+
+        Ok(())
+    }
+
+    /// Метод для разворачивания группы колонок вместе с вложенными группами
+    fn expand_cols(&mut self, start: u16, end: u16) -> Result<()> {
+        check_span(start as u32, end as u32, MAX_COL as u32)?;
+
+        for col_num in start..=end {
+            self.column_dimensions.set_hidden(col_num, false);
+            self.column_dimensions.set_collapsed(col_num, false);
+        }
+        if let Some(summary) = self.summary_col(start, end) {
+            self.column_dimensions.set_collapsed(summary, false);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn set_summary_below(&mut self, val: bool) {
+        self.row_dimensions.set_summary_below(val);
+    }
+
+    #[inline]
+    fn set_summary_right(&mut self, val: bool) {
+        self.column_dimensions.set_summary_right(val);
+    }
+}
+
+/// Проверка диапазона строк или колонок группы
+fn check_span(start: u32, end: u32, max: u32) -> Result<()> {
+    if start == 0 || start > end || end > max {
+        bail!("Invalid outline range {start}:{end}");
+    }
+
+    Ok(())
+}
+
+/// Проверка диапазона строк группы: у каждой строки заполняются
+/// свойства, поэтому слишком длинные группы не допускаются
+fn check_row_span(start: u32, end: u32) -> Result<()> {
+    check_span(start, end, MAX_ROW)?;
+    if end - start >= MAX_OUTLINE_ROWS {
+        bail!("Outline range {start}:{end} is longer than {MAX_OUTLINE_ROWS} rows");
+    }
+
+    Ok(())
+}
+
+/// Проверка уровня группировки
+fn check_level(level: u32) -> Result<()> {
+    if level > MAX_OUTLINE_LEVEL {
+        bail!("Outline level must be in 0..={MAX_OUTLINE_LEVEL}, got {level}");
+    }
+
+    Ok(())
+}
+
+/// Проверка имени листа на допустимость в Excel
+pub(crate) fn validate_sheet_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().count() > MAX_SHEET_NAME_LEN {
+        bail!("Invalid sheet name '{name}': length must be 1..={MAX_SHEET_NAME_LEN}");
+    }
+    if name.contains([':', '\\', '/', '?', '*', '[', ']'])
+        || name.starts_with('\'')
+        || name.ends_with('\'')
+    {
+        bail!("Invalid sheet name '{name}': contains forbidden characters");
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::traits::ReadableCell;
+    use crate::{datatype::CellRawValue, structs::conditional_format::ConditionalRule};
 
     use super::*;
 
@@ -427,12 +1037,134 @@ mod tests {
         assert_eq!(sheet.get_cell_collection().len(), 5);
     }
 
+    #[test]
+    fn delete_rows_structure() {
+        let mut sheet = sheet();
+        sheet.add_merge_range(Range::new(1, 1, 1, 5)).unwrap();
+        sheet.add_merge_range(Range::new(2, 4, 1, 1)).unwrap();
+        sheet.add_merge_range(Range::new(3, 3, 2, 3)).unwrap();
+        sheet.add_merge_range(Range::new(5, 6, 2, 2)).unwrap();
+        sheet.add_comments(Comment::new(Coordinate::new(3, 1), "A.C"));
+        sheet.add_comments(Comment::new(Coordinate::new(5, 1), "A.C"));
+        sheet.set_height_row(3, 25.0);
+        sheet.set_height_row(5, 30.0);
+        sheet.set_hidden_row(6, true);
+
+        sheet.delete_rows(3, 1);
+
+        assert_eq!(
+            sheet.get_merge_cell_collection(),
+            &[
+                Range::new(1, 1, 1, 5),
+                Range::new(2, 3, 1, 1),
+                Range::new(4, 5, 2, 2)
+            ]
+        );
+        assert_eq!(sheet.get_comments().len(), 1);
+        assert_eq!(
+            sheet.get_comments()[0].read().get_coordinate(),
+            &Coordinate::new(4, 1)
+        );
+        assert_eq!(*sheet.get_height_by_row(4), 30.0);
+        assert_ne!(*sheet.get_height_by_row(3), 25.0);
+        assert!(*sheet.get_hidden_by_row(5));
+        assert!(!*sheet.get_hidden_by_row(6));
+
+        // Объединение, от которого осталась одна ячейка, удаляется
+        sheet.delete_rows(3, 1);
+        assert_eq!(
+            sheet.get_merge_cell_collection(),
+            &[Range::new(1, 1, 1, 5), Range::new(3, 4, 2, 2)]
+        );
+    }
+
+    #[test]
+    fn delete_cols_structure() {
+        let mut sheet = sheet();
+        sheet.add_merge_range(Range::new(1, 1, 1, 5)).unwrap();
+        sheet.add_merge_range(Range::new(2, 3, 2, 3)).unwrap();
+        sheet.add_comments(Comment::new(Coordinate::new(1, 4), "A.C"));
+        sheet.set_width_column(5, 40.0);
+        sheet.set_hidden_column(3, true);
+        sheet.set_auto_filter(Some(Range::new(1, 5, 1, 5)));
+
+        sheet.delete_cols(2, 2);
+
+        assert_eq!(sheet.get_merge_cell_collection(), &[Range::new(1, 1, 1, 3)]);
+        assert_eq!(sheet.get_auto_filter(), Some(&Range::new(1, 5, 1, 3)));
+        assert_eq!(
+            sheet.get_comments()[0].read().get_coordinate(),
+            &Coordinate::new(1, 2)
+        );
+        assert_eq!(*sheet.get_width_by_column(3), 40.0);
+        assert!(!*sheet.get_hidden_by_column(3));
+    }
+
+    #[test]
+    fn insert_rows() {
+        let mut sheet = sheet();
+        sheet.add_merge_range(Range::new(1, 3, 1, 2)).unwrap();
+        sheet.add_merge_range(Range::new(4, 5, 1, 1)).unwrap();
+        sheet.add_comments(Comment::new(Coordinate::new(3, 1), "A.C"));
+        sheet.set_height_row(4, 30.0);
+        sheet
+            .cell(Coordinate::new(6, 1), None)
+            .write()
+            .set_formula("=SUM(A1:A5)+A2");
+        sheet.set_auto_filter(Some(Range::new(1, 5, 1, 5)));
+
+        sheet.insert_rows(2, 2);
+
+        assert_eq!(sheet.get_max_row(), 8);
+        assert_eq!(sheet.get_auto_filter(), Some(&Range::new(1, 7, 1, 5)));
+        assert_eq!(sheet.get_cell_value((1, 1)), "Yop! 1:1");
+        assert_eq!(sheet.get_cell_value((2, 1)), "");
+        assert_eq!(sheet.get_cell_value((4, 1)), "Yop! 2:1");
+        assert_eq!(
+            sheet.get_merge_cell_collection(),
+            &[Range::new(1, 5, 1, 2), Range::new(6, 7, 1, 1)]
+        );
+        assert_eq!(
+            sheet.get_comments()[0].read().get_coordinate(),
+            &Coordinate::new(5, 1)
+        );
+        assert_eq!(*sheet.get_height_by_row(6), 30.0);
+        assert_eq!(
+            sheet
+                .find_cell_by_coords(8, 1)
+                .unwrap()
+                .unwrap()
+                .read()
+                .get_formula(),
+            Some("=SUM(A1:A7)+A4".to_string())
+        );
+    }
+
+    #[test]
+    fn insert_cols() {
+        let mut sheet = sheet();
+        sheet.add_merge_range(Range::new(1, 1, 2, 3)).unwrap();
+        sheet.set_hidden_column(3, true);
+
+        sheet.insert_cols(1, 1);
+
+        assert_eq!(sheet.get_max_column(), 6);
+        assert_eq!(sheet.get_cell_value((1, 1)), "");
+        assert_eq!(sheet.get_cell_value((1, 2)), "Yop! 1:1");
+        assert_eq!(sheet.get_merge_cell_collection(), &[Range::new(1, 1, 3, 4)]);
+        assert!(*sheet.get_hidden_by_column(4));
+        assert!(!*sheet.get_hidden_by_column(3));
+    }
+
     #[test]
     pub fn find_cell_by_regex() {
         let sheet = sheet();
         let regex = "Yop! 3:3";
 
-        let cell = sheet.cells.find_cell_by_regex(regex).unwrap();
+        let cell = sheet
+            .cells
+            .find_cell_by_regex(regex, SearchOrder::Rows)
+            .unwrap();
 
         assert!(cell.is_some());
         assert_eq!(cell.unwrap().read().get_value(), "Yop! 3:3");
@@ -443,12 +1175,39 @@ mod tests {
         let sheet = sheet();
         let regex = "Yop! 3:3";
 
-        let cell = sheet.cells.find_cell_by_str(regex).unwrap();
+        let cell = sheet
+            .cells
+            .find_cell_by_str(regex, SearchOrder::Rows)
+            .unwrap();
 
         assert!(cell.is_some());
         assert_eq!(cell.unwrap().read().get_value(), "Yop! 3:3");
     }
 
+    #[test]
+    fn find_cell_order() {
+        let mut sheet = sheet();
+        for (row, col) in [(2, 4), (4, 2), (3, 3)] {
+            sheet.cell(Coordinate::new(row, col), Some("Итого"));
+        }
+
+        for _ in 0..10 {
+            let cell = sheet.find_cell_by_str("Итого", SearchOrder::Rows).unwrap();
+            assert_eq!(
+                cell.unwrap().read().get_coordinate(),
+                &Coordinate::new(2, 4)
+            );
+
+            let cell = sheet
+                .find_cell_by_regex("^итог", SearchOrder::Cols)
+                .unwrap();
+            assert_eq!(
+                cell.unwrap().read().get_coordinate(),
+                &Coordinate::new(4, 2)
+            );
+        }
+    }
+
     #[test]
     pub fn find_cell_by_coords() {
         let sheet = sheet();
@@ -468,6 +1227,10 @@ mod tests {
 
         assert!(cell.is_some());
         assert_eq!(cell.unwrap().read().get_value(), "Yop! 1:3");
+
+        for letter in ["A", "ABCD1", "1A"] {
+            assert!(sheet.cells.find_cell_by_letter(letter).is_err());
+        }
     }
 
     #[test]
@@ -601,6 +1364,79 @@ mod tests {
         assert_eq!(cells.len(), 3);
     }
 
+    #[test]
+    fn merged_values() {
+        let mut sheet = Sheet::new("A", "visible");
+        sheet.cell(Coordinate::new(1, 1), Some("Период"));
+        sheet.cell(Coordinate::new(1, 3), Some("Итого"));
+        sheet.cell(Coordinate::new(2, 1), Some("2024"));
+        sheet.cell(Coordinate::new(2, 2), Some("2025"));
+        sheet.add_merge_range(Range::new(1, 1, 1, 2)).unwrap();
+        sheet.add_merge_range(Range::new(1, 2, 3, 3)).unwrap();
+
+        assert_eq!(
+            sheet.merged_range_at(&Coordinate::new(2, 3)),
+            Some(&Range::new(1, 2, 3, 3))
+        );
+        assert_eq!(sheet.merged_range_at(&Coordinate::new(2, 1)), None);
+
+        assert_eq!(sheet.get_cell_value((1, 2)), "");
+        assert_eq!(sheet.get_cell_value_merged((1, 2)), "Период");
+        assert_eq!(sheet.get_cell_value_merged((2, 3)), "Итого");
+        assert_eq!(sheet.get_cell_value_merged((2, 2)), "2025");
+
+        assert_eq!(
+            sheet.find_value_by_coords_merged(2, 3).unwrap().as_deref(),
+            Some("Итого")
+        );
+        assert_eq!(
+            sheet
+                .find_values_by_row_cols_merged(1, vec![3, 2, 1, 2])
+                .unwrap(),
+            ["Период", "Период", "Итого"]
+        );
+        assert_eq!(
+            sheet
+                .find_values_by_col_rows_merged(3, vec![1, 2, 3])
+                .unwrap(),
+            ["Итого", "Итого"]
+        );
+
+        // Значение, оставшееся под объединением, не находится при merged
+        sheet.cell(Coordinate::new(2, 3), Some("Итого"));
+        let found =
+            |cell: Option<&Arc<RwLock<Cell>>>| cell.map(|c| c.read().get_coordinate().clone());
+        assert_eq!(
+            found(sheet.find_cell_by_str("Итого", SearchOrder::Cols).unwrap()),
+            Some(Coordinate::new(1, 3))
+        );
+        assert_eq!(
+            found(sheet.find_cell_by_str("2025", SearchOrder::Rows).unwrap()),
+            Some(Coordinate::new(2, 2))
+        );
+        sheet.cell(Coordinate::new(1, 2), Some("Старое"));
+        assert_eq!(
+            found(sheet.find_cell_by_str("Старое", SearchOrder::Rows).unwrap()),
+            Some(Coordinate::new(1, 2))
+        );
+        assert_eq!(
+            found(
+                sheet
+                    .find_cell_by_str_merged("Старое", SearchOrder::Rows)
+                    .unwrap()
+            ),
+            None
+        );
+        assert_eq!(
+            found(
+                sheet
+                    .find_cell_by_regex_merged("^(стар|пери)", SearchOrder::Cols)
+                    .unwrap()
+            ),
+            Some(Coordinate::new(1, 1))
+        );
+    }
+
     #[test]
     pub fn find_value_by_coords() {
         let sheet = sheet();
@@ -615,13 +1451,32 @@ mod tests {
         let mut sheet = sheet();
 
         let range = Range::new(1, 2, 1, 2);
-        sheet.add_merge_range(range);
+        sheet.add_merge_range(range).unwrap();
 
         let range = sheet.get_merge_cell_collection();
 
         assert_eq!(range.len(), 1);
     }
 
+    #[test]
+    pub fn add_merge_range_clear() {
+        let mut sheet = sheet();
+
+        sheet.add_merge_range_clear(Range::new(1, 2, 1, 2)).unwrap();
+        assert!(sheet.add_merge_range_clear(Range::new(2, 3, 2, 3)).is_err());
+
+        assert_eq!(sheet.get_cell_value((1, 1)), "Yop! 1:1");
+        assert_eq!(sheet.get_cell_value((1, 2)), "");
+        assert_eq!(sheet.get_cell_value((2, 2)), "");
+        assert_eq!(sheet.get_cell_value((3, 3)), "Yop! 3:3");
+
+        assert_eq!(
+            sheet.unmerge(&Coordinate::new(2, 2)),
+            Some(Range::new(1, 2, 1, 2))
+        );
+        assert!(sheet.get_merge_cell_collection().is_empty());
+    }
+
     #[test]
     pub fn get_merge_cell_collection() {
         let sheet = sheet();
@@ -645,4 +1500,115 @@ mod tests {
 
         assert_eq!(sheet.get_comments().len(), 1);
     }
+
+    #[test]
+    pub fn group_rows() {
+        let mut sheet = sheet();
+        sheet.group_rows(2, 5, 1).unwrap();
+        sheet.group_rows(3, 4, 2).unwrap();
+
+        assert_eq!(*sheet.get_level_by_row(2), 1);
+        assert_eq!(*sheet.get_level_by_row(4), 2);
+        assert_eq!(*sheet.get_level_by_row(6), 0);
+        assert!(sheet.group_rows(2, 5, 8).is_err());
+        assert!(sheet.group_rows(5, 2, 1).is_err());
+        assert!(sheet.group_rows(1, MAX_ROW, 1).is_err());
+        assert!(sheet.collapse_rows(1, MAX_OUTLINE_ROWS + 1).is_err());
+        assert_eq!(*sheet.get_level_by_row(100), 0);
+        sheet.group_rows(10, MAX_OUTLINE_ROWS + 9, 1).unwrap();
+        sheet.group_rows(10, MAX_OUTLINE_ROWS + 9, 0).unwrap();
+
+        sheet.collapse_rows(3, 4).unwrap();
+        assert!(*sheet.get_hidden_by_row(3));
+        assert!(*sheet.get_collapsed_by_row(5));
+
+        sheet.expand_rows(2, 5).unwrap();
+        assert!(!*sheet.get_hidden_by_row(3));
+        assert!(!*sheet.get_collapsed_by_row(5));
+
+        // Итоговая колонка слева от группы
+        sheet.set_summary_right(false);
+        sheet.group_cols(2, 3, 1).unwrap();
+        sheet.collapse_cols(2, 3).unwrap();
+        assert!(*sheet.get_hidden_by_column(2));
+        assert!(*sheet.get_collapsed_by_column(1));
+
+        let restored = Sheet::from_json(&sheet.to_json().unwrap()).unwrap();
+        assert_eq!(*restored.get_level_by_column(3), 1);
+        assert!(!restored.get_summary_right());
+        assert!(restored.get_summary_below());
+    }
+
+    #[test]
+    pub fn from_json() {
+        let mut sheet = sheet();
+        sheet.add_merge_range(Range::new(1, 1, 1, 3)).unwrap();
+        sheet.add_comments(Comment::new(Coordinate::new(2, 2), "A.C"));
+        sheet.set_height_row(3, 35.0);
+        sheet.set_hidden_column(4, true);
+        sheet.cell(Coordinate::new(6, 1), Some("42"));
+        sheet.cell(Coordinate::new(6, 2), Some("4.5"));
+        sheet.cell(Coordinate::new(6, 3), Some("2024-01-31 10:15:00"));
+        sheet
+            .cell(Coordinate::new(6, 4), None)
+            .write()
+            .set_formula("SUM(A6:B6)");
+
+        let restored = Sheet::from_json(&sheet.to_json().unwrap()).unwrap();
+
+        assert_eq!(restored.get_name(), "A");
+        assert_eq!(restored.get_max_row(), 6);
+        assert_eq!(restored.get_cell_value((1, 1)), "Yop! 1:1");
+        assert_eq!(
+            restored.get_merge_cell_collection(),
+            &[Range::new(1, 1, 1, 3)]
+        );
+        assert_eq!(restored.get_comments().len(), 1);
+        assert_eq!(restored.get_height_by_row(3), &35.0);
+        assert!(restored.get_hidden_by_column(4));
+
+        let cell = restored.find_cell_by_coords(6, 1).unwrap().unwrap();
+        assert!(matches!(
+            cell.read().get_raw_value(),
+            CellRawValue::Integer(42)
+        ));
+
+        let cell = restored.find_cell_by_coords(6, 2).unwrap().unwrap();
+        assert!(matches!(
+            cell.read().get_raw_value(),
+            CellRawValue::Numeric(_)
+        ));
+
+        let cell = restored.find_cell_by_coords(6, 3).unwrap().unwrap();
+        assert!(matches!(
+            cell.read().get_raw_value(),
+            CellRawValue::Datetime(_)
+        ));
+        assert_eq!(cell.read().get_data_type(), "d");
+
+        let cell = restored.find_cell_by_coords(6, 4).unwrap().unwrap();
+        assert_eq!(cell.read().get_formula(), Some("SUM(A6:B6)".to_string()));
+        assert_eq!(cell.read().get_data_type(), "f");
+    }
+
+    #[test]
+    fn add_conditional_format_priority() {
+        let mut sheet = Sheet::new("A", "visible");
+        let rule = |priority: Option<u32>| {
+            let mut cf = ConditionalFormat::new(
+                vec![Range::new(1, 10, 1, 1)],
+                ConditionalRule::DuplicateValues { unique: false },
+            );
+            cf.priority = priority;
+            cf
+        };
+
+        assert!(sheet.add_conditional_format(rule(Some(1))).is_ok());
+        assert!(sheet.add_conditional_format(rule(None)).is_ok());
+        assert!(sheet.add_conditional_format(rule(None)).is_ok());
+        assert!(sheet.add_conditional_format(rule(Some(2))).is_ok());
+        assert!(sheet.add_conditional_format(rule(Some(1))).is_err());
+        assert!(sheet.add_conditional_format(rule(Some(0))).is_err());
+        assert_eq!(sheet.get_conditional_formats().len(), 4);
+    }
 }
@@ -0,0 +1,141 @@
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{coordinate::Coordinate, style::Color};
+use crate::{MAX_COL, MAX_ROW};
+
+/// Настройки отображения листа.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SheetView {
+    /// Количество закрепленных строк сверху
+    pub frozen_rows: u32,
+    /// Количество закрепленных колонок слева
+    pub frozen_cols: u16,
+    /// Разделение окна без закрепления. Не используется при закреплении
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<Split>,
+    /// Масштаб в процентах
+    pub zoom: u16,
+    pub show_grid_lines: bool,
+    /// Активная ячейка
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_cell: Option<Coordinate>,
+    /// Цвет ярлыка листа
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_color: Option<Color>,
+    /// Отображение листа справа налево
+    pub right_to_left: bool,
+}
+
+impl Default for SheetView {
+    fn default() -> Self {
+        SheetView {
+            frozen_rows: 0,
+            frozen_cols: 0,
+            split: None,
+            zoom: 100,
+            show_grid_lines: true,
+            selected_cell: None,
+            tab_color: None,
+            right_to_left: false,
+        }
+    }
+}
+
+impl SheetView {
+    /// Метод для проверки настроек перед установкой на лист
+    pub fn validate(&self) -> Result<()> {
+        if !(10..=400).contains(&self.zoom) {
+            bail!("Zoom must be in 10..=400, got {}", self.zoom);
+        }
+        if self.frozen_rows >= MAX_ROW || self.frozen_cols >= MAX_COL {
+            bail!(
+                "Too many frozen rows/columns: {}/{}",
+                self.frozen_rows,
+                self.frozen_cols
+            );
+        }
+        if let Some(split) = &self.split
+            && (split.x < 0.0 || split.y < 0.0 || !split.x.is_finite() || !split.y.is_finite())
+        {
+            bail!("Split position must be non-negative");
+        }
+        if let Some(Coordinate { row, column }) = self.selected_cell
+            && (row == 0 || column == 0 || row > MAX_ROW || column > MAX_COL)
+        {
+            bail!("Invalid selected cell ({row}, {column})");
+        }
+
+        Ok(())
+    }
+
+    /// Заданы ли настройки по умолчанию
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Есть ли закрепленные строки или колонки
+    #[inline]
+    pub fn is_frozen(&self) -> bool {
+        self.frozen_rows > 0 || self.frozen_cols > 0
+    }
+}
+
+/// Положение разделителя окна в 1/20 пункта.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Split {
+    /// По горизонтали, от левого края
+    #[serde(default)]
+    pub x: f64,
+    /// По вертикали, от верхнего края
+    #[serde(default)]
+    pub y: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_json() {
+        let view: SheetView =
+            serde_json::from_str(r##"{"frozen_rows": 1, "tab_color": "#1F4E78"}"##).unwrap();
+
+        assert_eq!(view.frozen_rows, 1);
+        assert_eq!(view.zoom, 100);
+        assert!(view.show_grid_lines);
+        assert!(view.is_frozen());
+        assert!(view.validate().is_ok());
+
+        assert!(serde_json::from_str::<SheetView>(r#"{"frozen": 1}"#).is_err());
+        assert_eq!(
+            serde_json::to_string(&SheetView::default()).unwrap(),
+            concat!(
+                r#"{"frozen_rows":0,"frozen_cols":0,"zoom":100,"#,
+                r#""show_grid_lines":true,"right_to_left":false}"#
+            )
+        );
+    }
+
+    #[test]
+    fn validate_view() {
+        let view = |zoom| SheetView {
+            zoom,
+            ..Default::default()
+        };
+
+        assert!(view(5).validate().is_err());
+        assert!(view(400).validate().is_ok());
+        assert!(
+            SheetView {
+                selected_cell: Some(Coordinate::new(0, 1)),
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
@@ -1,15 +1,30 @@
-use serde::Serialize;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Default, Debug, Clone, Serialize, PartialEq, Eq)]
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// Стиль ячейки: имя стиля из реестра книги или встроенное описание.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Style {
     #[serde(rename = "style_id")]
     id: String,
+    #[serde(rename = "style", default, skip_serializing_if = "Option::is_none")]
+    format: Option<Box<Format>>,
 }
 
 impl Style {
     pub fn new(val: &str) -> Self {
         Style {
             id: val.to_string(),
+            format: None,
+        }
+    }
+
+    /// Стиль со встроенным описанием, без регистрации в книге
+    pub fn inline(format: Format) -> Self {
+        Style {
+            id: String::new(),
+            format: Some(Box::new(format)),
         }
     }
 
@@ -17,4 +32,334 @@ impl Style {
     pub fn get_id(&self) -> String {
         self.id.to_string()
     }
+
+    /// Метод для получения встроенного описания стиля
+    #[inline]
+    pub fn get_format(&self) -> Option<&Format> {
+        self.format.as_deref()
+    }
+}
+
+impl From<&str> for Style {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Style::new(value)
+    }
+}
+
+impl From<String> for Style {
+    #[inline]
+    fn from(value: String) -> Self {
+        Style {
+            id: value,
+            format: None,
+        }
+    }
+}
+
+impl From<Format> for Style {
+    #[inline]
+    fn from(value: Format) -> Self {
+        Style::inline(value)
+    }
+}
+
+/// Описание оформления ячейки.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Format {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<Font>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Fill>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<Border>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<Alignment>,
+    /// Перенос текста по словам
+    #[serde(skip_serializing_if = "crate::utils::is_false")]
+    pub wrap: bool,
+    /// Числовой формат, например `# ##0.00` или `dd.mm.yyyy`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_format: Option<String>,
+    /// Блокировка и скрытие формулы на защищенном листе
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection: Option<CellProtection>,
+}
+
+/// Шрифт.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Font {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<f64>,
+    #[serde(skip_serializing_if = "crate::utils::is_false")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "crate::utils::is_false")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "crate::utils::is_false")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "crate::utils::is_false")]
+    pub strike: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+/// Сплошная заливка.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Fill {
+    pub color: Color,
+}
+
+/// Границы ячейки.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Border {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<BorderSide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<BorderSide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<BorderSide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<BorderSide>,
+}
+
+impl Border {
+    /// Одинаковые границы со всех сторон
+    pub fn all(side: BorderSide) -> Self {
+        Border {
+            left: Some(side.clone()),
+            right: Some(side.clone()),
+            top: Some(side.clone()),
+            bottom: Some(side),
+        }
+    }
+}
+
+/// Одна граница ячейки.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BorderSide {
+    pub style: BorderStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+/// Тип линии границы.
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    #[default]
+    Thin,
+    Medium,
+    Thick,
+    Dashed,
+    Dotted,
+    Double,
+    Hair,
+}
+
+impl Display for BorderStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Thin => "thin",
+            Self::Medium => "medium",
+            Self::Thick => "thick",
+            Self::Dashed => "dashed",
+            Self::Dotted => "dotted",
+            Self::Double => "double",
+            Self::Hair => "hair",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Защита ячейки, действует только на защищенном листе.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CellProtection {
+    /// Запрет изменения ячейки
+    pub locked: bool,
+    /// Скрытие формулы в строке формул
+    pub hidden: bool,
+}
+
+impl Default for CellProtection {
+    fn default() -> Self {
+        CellProtection {
+            locked: true,
+            hidden: false,
+        }
+    }
+}
+
+/// Выравнивание текста в ячейке.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Alignment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal: Option<HorizontalAlignment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical: Option<VerticalAlignment>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub indent: u8,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HorizontalAlignment {
+    Left,
+    Center,
+    Right,
+    Fill,
+    Justify,
+}
+
+impl Display for HorizontalAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+            Self::Fill => "fill",
+            Self::Justify => "justify",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalAlignment {
+    Top,
+    Center,
+    Bottom,
+    Justify,
+}
+
+impl Display for VerticalAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Top => "top",
+            Self::Center => "center",
+            Self::Bottom => "bottom",
+            Self::Justify => "justify",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Цвет в формате ARGB, например `FFFF0000`.
+/// Принимает также `RRGGBB` и `#RRGGBB`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color(String);
+
+impl Default for Color {
+    fn default() -> Self {
+        Color("FF000000".to_string())
+    }
+}
+
+impl Color {
+    #[inline]
+    pub fn get_argb(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Color {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim().trim_start_matches('#');
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("Invalid color: {s}");
+        }
+
+        match hex.len() {
+            6 => Ok(Color(format!("FF{}", hex.to_uppercase()))),
+            8 => Ok(Color(hex.to_uppercase())),
+            _ => bail!("Invalid color: {s}"),
+        }
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = anyhow::Error;
+
+    #[inline]
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Color> for String {
+    #[inline]
+    fn from(value: Color) -> Self {
+        value.0
+    }
+}
+
+fn is_zero(value: &u8) -> bool {
+    *value == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        assert_eq!("#ff0000".parse::<Color>().unwrap().get_argb(), "FFFF0000");
+        assert_eq!("80112233".parse::<Color>().unwrap().get_argb(), "80112233");
+        assert!("red".parse::<Color>().is_err());
+        assert!("#FFF".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn format_json() {
+        let json = r##"{
+            "font": {"bold": true, "size": 12, "color": "#1F4E78"},
+            "fill": {"color": "DDEBF7"},
+            "border": {"bottom": {"style": "medium"}},
+            "alignment": {"horizontal": "center"},
+            "wrap": true,
+            "number_format": "# ##0.00"
+        }"##;
+
+        let format: Format = serde_json::from_str(json).unwrap();
+        let font = format.font.as_ref().unwrap();
+
+        assert!(font.bold);
+        assert_eq!(font.size, Some(12.0));
+        assert_eq!(font.color.as_ref().unwrap().get_argb(), "FF1F4E78");
+        assert_eq!(
+            format
+                .border
+                .as_ref()
+                .unwrap()
+                .bottom
+                .as_ref()
+                .unwrap()
+                .style,
+            BorderStyle::Medium
+        );
+        assert!(format.wrap);
+
+        let restored: Format =
+            serde_json::from_str(&serde_json::to_string(&format).unwrap()).unwrap();
+        assert_eq!(restored, format);
+
+        assert!(serde_json::from_str::<Format>(r#"{"bold": true}"#).is_err());
+        assert!(serde_json::from_str::<Format>(r#"{"fill": {"color": "xx"}}"#).is_err());
+    }
 }
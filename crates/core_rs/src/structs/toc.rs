@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    coordinate::Coordinate,
+    sheet::validate_sheet_name,
+    style::{Font, Format},
+};
+use crate::{MAX_COL, MAX_ROW};
+
+/// Цвет гиперссылок, как у стиля «Гиперссылка» в Excel.
+const LINK_COLOR: &str = "0563C1";
+
+/// Параметры листа оглавления книги.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TocOptions {
+    /// Имя листа оглавления. Прежнее оглавление с этим именем заменяется
+    pub sheet_name: String,
+    /// Заголовок над списком листов
+    pub title: Option<String>,
+    /// Описания листов по имени листа
+    pub descriptions: BTreeMap<String, String>,
+    /// Текст ссылки на оглавление на каждом листе. `None` - без ссылки
+    pub back_link: Option<String>,
+    /// Ячейка для ссылки на оглавление
+    pub back_link_cell: Coordinate,
+}
+
+impl Default for TocOptions {
+    fn default() -> Self {
+        TocOptions {
+            sheet_name: "Оглавление".to_string(),
+            title: Some("Оглавление".to_string()),
+            descriptions: BTreeMap::new(),
+            back_link: Some("К оглавлению".to_string()),
+            back_link_cell: Coordinate::new(1, 1),
+        }
+    }
+}
+
+impl TocOptions {
+    /// Метод для проверки параметров перед построением оглавления
+    pub fn validate(&self) -> Result<()> {
+        validate_sheet_name(&self.sheet_name)?;
+
+        let Coordinate { row, column } = self.back_link_cell;
+        if row == 0 || column == 0 || row > MAX_ROW || column > MAX_COL {
+            bail!("Invalid back link cell ({row}, {column})");
+        }
+
+        Ok(())
+    }
+
+    /// Стиль ссылок оглавления
+    pub(crate) fn link_format() -> Format {
+        Format {
+            font: Some(Font {
+                underline: true,
+                color: LINK_COLOR.parse().ok(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Стиль заголовка оглавления
+    pub(crate) fn title_format() -> Format {
+        Format {
+            font: Some(Font {
+                bold: true,
+                size: Some(14.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
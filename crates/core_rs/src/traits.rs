@@ -5,17 +5,24 @@ use chrono::NaiveDateTime;
 use parking_lot::RwLock;
 
 use crate::structs::{
-    cell::Cell, comment::Comment, coordinate::Coordinate, range::Range, style::Style,
+    cell::Cell, cells::SearchOrder, comment::Comment, conditional_format::ConditionalFormat,
+    coordinate::Coordinate, data_validation::DataValidation, hyperlink::Hyperlink,
+    page_setup::PageSetup, protection::SheetProtection, range::Range, rich_text::RichText,
+    sheet_view::SheetView, style::Style,
 };
 
 pub trait ReadableCell {
     fn get_coordinate(&self) -> &Coordinate;
     fn get_letter(&self) -> String;
     fn get_value(&self) -> String;
+    fn get_display_value(&self) -> String;
     fn get_formula(&self) -> Option<String>;
     fn get_data_type(&self) -> String;
     fn get_hidden_value(&self) -> Option<String>;
     fn get_style(&self) -> Option<Style>;
+    fn get_number_format(&self) -> Option<String>;
+    fn get_hyperlink(&self) -> Option<Hyperlink>;
+    fn get_rich_text(&self) -> Option<RichText>;
     fn is_formula(&self) -> bool;
     fn is_value_bool(&self) -> bool;
     fn is_value_numeric(&self) -> bool;
@@ -31,8 +38,11 @@ pub trait WriteableCell {
     fn set_value_integer(&mut self, value: i32) -> &mut Self;
     fn set_value_bool(&mut self, value: bool) -> &mut Self;
     fn set_value_datetime(&mut self, value: NaiveDateTime) -> &mut Self;
+    fn set_value_rich_text(&mut self, value: RichText) -> &mut Self;
     fn set_formula(&mut self, value: &str) -> &mut Self;
-    fn set_style(&mut self, value: &str) -> &mut Self;
+    fn set_style<T: Into<Style>>(&mut self, value: T) -> &mut Self;
+    fn set_number_format(&mut self, value: &str) -> &mut Self;
+    fn set_hyperlink(&mut self, target: &str, tooltip: Option<&str>) -> &mut Self;
     fn set_hidden_value(&mut self, value: &str) -> &mut Self;
 }
 
@@ -57,14 +67,54 @@ pub trait ReadableSheet {
         end_col: Option<u16>,
     ) -> impl Iterator<Item = &Arc<RwLock<Cell>>>;
     fn get_merge_cell_collection(&self) -> &[Range];
+    fn merged_range_at(&self, coordinate: &Coordinate) -> Option<&Range>;
+    fn get_cell_value_merged<T>(&self, coordinate: T) -> String
+    where
+        T: Into<Coordinate>;
+    fn get_conditional_formats(&self) -> &[ConditionalFormat];
+    fn get_data_validations(&self) -> &[DataValidation];
+    fn get_sheet_view(&self) -> &SheetView;
+    fn get_auto_filter(&self) -> Option<&Range>;
+    fn get_page_setup(&self) -> &PageSetup;
+    fn get_protection(&self) -> Option<&SheetProtection>;
     fn get_height_by_row(&self, row_num: u32) -> &f64;
     fn get_hidden_by_row(&self, row_num: u32) -> &bool;
     fn get_width_by_column(&self, col_num: u16) -> &f64;
     fn get_hidden_by_column(&self, col_num: u16) -> &bool;
+    fn get_level_by_row(&self, row_num: u32) -> &u32;
+    fn get_collapsed_by_row(&self, row_num: u32) -> &bool;
+    fn get_level_by_column(&self, col_num: u16) -> &u32;
+    fn get_collapsed_by_column(&self, col_num: u16) -> &bool;
+    fn get_summary_below(&self) -> bool;
+    fn get_summary_right(&self) -> bool;
     fn get_comments(&self) -> &[Arc<RwLock<Comment>>];
 
-    fn find_cell_by_regex(&self, regex: &str) -> Result<Option<&Arc<RwLock<Cell>>>>;
-    fn find_cell_by_str(&self, value: &str) -> Result<Option<&Arc<RwLock<Cell>>>>;
+    /// Первая подходящая ячейка в порядке обхода `order`
+    fn find_cell_by_regex(
+        &self,
+        regex: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>>;
+    /// Первая ячейка с заданным значением в порядке обхода `order`
+    fn find_cell_by_str(
+        &self,
+        value: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>>;
+    /// Как `find_cell_by_regex`, но ячейки, закрытые объединением,
+    /// пропускаются: объединение ищется по значению левой верхней ячейки
+    fn find_cell_by_regex_merged(
+        &self,
+        regex: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>>;
+    /// Как `find_cell_by_str`, но ячейки, закрытые объединением,
+    /// пропускаются: объединение ищется по значению левой верхней ячейки
+    fn find_cell_by_str_merged(
+        &self,
+        value: &str,
+        order: SearchOrder,
+    ) -> Result<Option<&Arc<RwLock<Cell>>>>;
     fn find_cell_by_coords(&self, row: u32, col: u16) -> Result<Option<&Arc<RwLock<Cell>>>>;
     fn find_cell_by_letter(&self, letter: &str) -> Result<Option<&Arc<RwLock<Cell>>>>;
     fn find_cells_by_regex(&self, regex: &str) -> Result<Vec<&Arc<RwLock<Cell>>>>;
@@ -102,18 +152,47 @@ pub trait ReadableSheet {
     fn find_values_by_col_rows(&self, col: u16, rows: Vec<u32>) -> Result<Vec<String>>;
     fn find_values_by_row_cols(&self, row: u32, cols: Vec<u16>) -> Result<Vec<String>>;
     fn find_value_by_coords(&self, row: u32, col: u16) -> Result<Option<String>>;
+    fn find_value_by_coords_merged(&self, row: u32, col: u16) -> Result<Option<String>>;
+    /// Значения колонки в порядке строк листа без повторов, как в
+    /// `find_values_by_col_rows`; каждая ячейка объединения возвращает
+    /// значение его левой верхней ячейки
+    fn find_values_by_col_rows_merged(&self, col: u16, rows: Vec<u32>) -> Result<Vec<String>>;
+    /// Значения строки в порядке колонок листа без повторов, как в
+    /// `find_values_by_row_cols`; каждая ячейка объединения возвращает
+    /// значение его левой верхней ячейки
+    fn find_values_by_row_cols_merged(&self, row: u32, cols: Vec<u16>) -> Result<Vec<String>>;
 }
 
 pub trait WriteableSheet {
     fn set_name(&mut self, name: &str);
     fn set_sheet_state(&mut self, state: &str);
-    fn add_merge_range(&mut self, range: Range);
+    fn add_merge_range(&mut self, range: Range) -> Result<()>;
+    fn add_merge_range_clear(&mut self, range: Range) -> Result<()>;
+    fn remove_merge_range(&mut self, range: &Range) -> bool;
+    fn unmerge(&mut self, coordinate: &Coordinate) -> Option<Range>;
+    fn add_conditional_format(&mut self, value: ConditionalFormat) -> Result<()>;
+    fn add_data_validation(&mut self, value: DataValidation) -> Result<()>;
+    fn set_sheet_view(&mut self, value: SheetView) -> Result<()>;
+    fn set_freeze_panes(&mut self, rows: u32, cols: u16) -> Result<()>;
+    fn set_auto_filter(&mut self, range: Option<Range>);
+    fn set_page_setup(&mut self, value: PageSetup) -> Result<()>;
+    fn set_protection(&mut self, value: Option<SheetProtection>) -> Result<()>;
     fn add_comments(&mut self, value: Comment);
     fn cell(&mut self, coordinate: Coordinate, value: Option<&str>) -> &Arc<RwLock<Cell>>;
+    fn insert_cols(&mut self, idx: u16, amount: u16);
+    fn insert_rows(&mut self, idx: u32, amount: u32);
     fn delete_cols(&mut self, idx: u16, amount: u16);
     fn delete_rows(&mut self, idx: u32, amount: u32);
     fn set_height_row(&mut self, row_num: u32, val: f64);
     fn set_hidden_row(&mut self, row_num: u32, val: bool);
     fn set_width_column(&mut self, col_num: u16, val: f64);
     fn set_hidden_column(&mut self, col_num: u16, val: bool);
+    fn group_rows(&mut self, start: u32, end: u32, level: u32) -> Result<()>;
+    fn group_cols(&mut self, start: u16, end: u16, level: u32) -> Result<()>;
+    fn collapse_rows(&mut self, start: u32, end: u32) -> Result<()>;
+    fn expand_rows(&mut self, start: u32, end: u32) -> Result<()>;
+    fn collapse_cols(&mut self, start: u16, end: u16) -> Result<()>;
+    fn expand_cols(&mut self, start: u16, end: u16) -> Result<()>;
+    fn set_summary_below(&mut self, val: bool);
+    fn set_summary_right(&mut self, val: bool);
 }
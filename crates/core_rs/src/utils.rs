@@ -1,7 +1,17 @@
 use crate::structs::coordinate::CellIndex;
+use chrono::{NaiveDate, NaiveDateTime};
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Точка отсчета серийных дат Excel (с учетом ошибки 1900 года).
+fn excel_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .unwrap_or_default()
+}
+
 /// Преобразование номера колонки в букву.
 /// Данный метод используется по обработки формирования данных excel.
 /// 1. Принимает номер колонки как `u32`.
@@ -31,6 +41,29 @@ pub fn get_letter_coordinate(row: u32, col: u16) -> String {
     format!("{}{}", index_to_alpha(col), row)
 }
 
+/// Преобразование даты и времени в серийный номер Excel
+pub fn datetime_to_excel_serial(value: &NaiveDateTime) -> f64 {
+    let duration = *value - excel_epoch();
+
+    duration.num_milliseconds() as f64 / 1000.0 / SECONDS_PER_DAY
+}
+
+/// Преобразование серийного номера Excel в дату и время
+pub fn excel_serial_to_datetime(value: f64) -> Option<NaiveDateTime> {
+    if !value.is_finite() || value < 0.0 {
+        return None;
+    }
+
+    let millis = (value * SECONDS_PER_DAY * 1000.0).round() as i64;
+    excel_epoch().checked_add_signed(chrono::Duration::milliseconds(millis))
+}
+
+/// Пропуск `false` при сериализации флагов
+#[inline]
+pub(crate) fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 /// Определение формата по типу
 pub(crate) fn _get_number_format_by_datatype(data_type: &str) -> String {
     match data_type {
@@ -67,7 +100,7 @@ where
     T: AsRef<str>,
 {
     lazy_static! {
-        static ref RE: Regex = Regex::new(r"((\$)?([A-Z]{1,3}))?((\$)?([0-9]+))?").unwrap();
+        static ref RE: Regex = Regex::new(r"^((\$)?([A-Z]{1,3}))?((\$)?([0-9]+))?$").unwrap();
     }
 
     let caps = RE.captures(coordinate.as_ref()).ok().flatten();
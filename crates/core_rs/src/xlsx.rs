@@ -0,0 +1,40 @@
+pub mod reader;
+pub mod writer;
+
+pub(crate) mod styles;
+
+use std::borrow::Cow;
+
+use crate::{structs::range::Range, utils::get_letter_coordinate};
+
+pub(crate) const XML_HEADER: &str =
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+/// Экранирование текста для XML с удалением недопустимых символов.
+pub(crate) fn escape(value: &str) -> Cow<'_, str> {
+    if value
+        .chars()
+        .any(|c| c < '\u{20}' && !matches!(c, '\t' | '\n' | '\r'))
+    {
+        let cleaned: String = value
+            .chars()
+            .filter(|c| *c >= '\u{20}' || matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+
+        return Cow::Owned(quick_xml::escape::escape(&cleaned).into_owned());
+    }
+
+    quick_xml::escape::escape(value)
+}
+
+/// Получение ссылки на диапазон в стиле A1:B2
+pub(crate) fn range_ref(range: &Range) -> String {
+    let start = get_letter_coordinate(range.start_row, range.start_col);
+    let end = get_letter_coordinate(range.end_row, range.end_col);
+
+    if start == end {
+        start
+    } else {
+        format!("{start}:{end}")
+    }
+}
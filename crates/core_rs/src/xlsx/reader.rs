@@ -0,0 +1,1191 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+    sync::Arc,
+};
+
+use ahash::HashMap;
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+use zip::ZipArchive;
+
+use super::styles::{builtin_num_fmt, is_date_format};
+use crate::{
+    MAX_COL,
+    datatype::{CellRawValue, CellValue},
+    formula::{
+        lexer::{RefKind, TokenKind, tokenize},
+        shift::offset_formula,
+    },
+    structs::{
+        cell::Cell,
+        comment::Comment,
+        coordinate::Coordinate,
+        defined_name::DefinedName,
+        hyperlink::Hyperlink,
+        page_setup::{FitToPage, Orientation, PageSetup},
+        protection::SheetProtection,
+        range::Range,
+        rich_text::{RichText, TextRun},
+        sheet::Sheet,
+        sheet_view::{SheetView, Split},
+        style::Font,
+    },
+    traits::{ReadableCell, WriteableCell, WriteableSheet},
+    utils::excel_serial_to_datetime,
+};
+
+/// Описание листа из xl/workbook.xml
+#[derive(Debug)]
+struct SheetEntry {
+    name: String,
+    state: String,
+    path: String,
+    /// Служебные имена листа (область печати, сквозные строки)
+    builtin_names: Vec<(String, String)>,
+}
+
+/// Загрузка листов из файла XLSX.
+pub fn load_xlsx<P: AsRef<Path>>(path: P) -> Result<Vec<Sheet>> {
+    Ok(load_package(path)?.0)
+}
+
+/// Чтение книги XLSX в коллекцию листов.
+pub fn read_xlsx<R: Read + Seek>(reader: R) -> Result<Vec<Sheet>> {
+    Ok(read_package(reader)?.0)
+}
+
+/// Загрузка листов и имен книги из файла XLSX.
+pub(crate) fn load_package<P: AsRef<Path>>(path: P) -> Result<(Vec<Sheet>, Vec<DefinedName>)> {
+    let file = File::open(path.as_ref())
+        .with_context(|| format!("Failed to open '{}'", path.as_ref().display()))?;
+
+    read_package(BufReader::new(file))
+}
+
+/// Чтение листов и имен книги XLSX.
+pub(crate) fn read_package<R: Read + Seek>(reader: R) -> Result<(Vec<Sheet>, Vec<DefinedName>)> {
+    let mut archive = ZipArchive::new(reader).context("Failed to open XLSX archive")?;
+
+    let workbook_path = read_rels(&mut archive, "_rels/.rels", "")?
+        .into_values()
+        .find(|target| target.ends_with(".xml"))
+        .unwrap_or_else(|| "xl/workbook.xml".to_string());
+
+    let workbook_dir = parent_dir(&workbook_path);
+    let workbook_rels = read_rels(&mut archive, &rels_path(&workbook_path), &workbook_dir)?;
+
+    let shared_strings = match workbook_rels
+        .values()
+        .find(|t| t.ends_with("sharedStrings.xml"))
+    {
+        Some(path) => read_shared_strings(&read_part(&mut archive, path)?)?,
+        None => Vec::new(),
+    };
+
+    let date_styles = match workbook_rels.values().find(|t| t.ends_with("styles.xml")) {
+        Some(path) => read_date_styles(&read_part(&mut archive, path)?)?,
+        None => Vec::new(),
+    };
+
+    let (entries, names) =
+        read_workbook(&read_part(&mut archive, &workbook_path)?, &workbook_rels)?;
+
+    let sheets = entries
+        .into_iter()
+        .map(|entry| {
+            let (sheet_rels, external_rels) = read_all_rels(
+                &mut archive,
+                &rels_path(&entry.path),
+                &parent_dir(&entry.path),
+            )?;
+
+            let xml = read_part(&mut archive, &entry.path)?;
+            let mut sheet =
+                read_sheet(&entry, &xml, &shared_strings, &date_styles, &external_rels)?;
+
+            if let Some(path) = sheet_rels.values().find(|t| t.contains("comments")) {
+                for comment in read_comments(&read_part(&mut archive, path)?)? {
+                    sheet.add_comments(comment);
+                }
+            }
+
+            Ok(sheet)
+        })
+        .collect::<Result<_>>()?;
+
+    Ok((sheets, names))
+}
+
+/// Чтение части пакета в строку.
+fn read_part<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Result<String> {
+    let mut file = archive
+        .by_name(path)
+        .with_context(|| format!("Part '{path}' not found in XLSX"))?;
+
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)?;
+
+    Ok(xml)
+}
+
+/// Путь к файлу связей для части пакета.
+fn rels_path(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((dir, name)) => format!("{dir}/_rels/{name}.rels"),
+        None => format!("_rels/{path}.rels"),
+    }
+}
+
+/// Каталог части пакета.
+fn parent_dir(path: &str) -> String {
+    path.rsplit_once('/')
+        .map(|(dir, _)| dir.to_string())
+        .unwrap_or_default()
+}
+
+/// Разрешение относительного пути цели связи.
+fn resolve_target(base: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for part in target.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+/// Получение значения атрибута по локальному имени.
+fn attr(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes().flatten().find_map(|a| {
+        if a.key.local_name().as_ref() == name {
+            a.unescape_value().ok().map(|v| v.into_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/// Чтение файла связей: Id -> путь к цели.
+fn read_rels<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    base: &str,
+) -> Result<HashMap<String, String>> {
+    Ok(read_all_rels(archive, path, base)?.0)
+}
+
+/// Чтение файла связей: Id -> путь к цели и Id -> внешний адрес.
+fn read_all_rels<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    base: &str,
+) -> Result<(HashMap<String, String>, HashMap<String, String>)> {
+    let mut rels = HashMap::default();
+    let mut external_rels = HashMap::default();
+
+    if archive.index_for_name(path).is_none() {
+        return Ok((rels, external_rels));
+    }
+
+    let xml = read_part(archive, path)?;
+    let mut reader = Reader::from_str(&xml);
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                let external = attr(&e, b"TargetMode").is_some_and(|m| m == "External");
+                if let (Some(id), Some(target)) = (attr(&e, b"Id"), attr(&e, b"Target")) {
+                    if external {
+                        external_rels.insert(id, target);
+                    } else {
+                        rels.insert(id, resolve_target(base, &target));
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((rels, external_rels))
+}
+
+/// Чтение списка листов книги.
+fn read_workbook(
+    xml: &str,
+    rels: &HashMap<String, String>,
+) -> Result<(Vec<SheetEntry>, Vec<DefinedName>)> {
+    let mut reader = Reader::from_str(xml);
+    let mut entries = Vec::new();
+    let mut sheet_names = Vec::new();
+    let mut names = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"definedName" => {
+                let name = attr(&e, b"name").unwrap_or_default();
+                let scope = attr(&e, b"localSheetId")
+                    .and_then(|id| id.parse::<usize>().ok())
+                    .and_then(|id| sheet_names.get(id).cloned());
+                let reference = read_text_content(&mut reader, b"definedName")?;
+
+                // Служебные имена Excel относятся к параметрам листа,
+                // имена, которые не удалось разобрать, пропускаем
+                if name.starts_with("_xlnm.") {
+                    if let Some(entry) = entries
+                        .iter_mut()
+                        .find(|e: &&mut SheetEntry| Some(&e.name) == scope.as_ref())
+                    {
+                        entry.builtin_names.push((name, reference));
+                    }
+                } else if let Ok(defined_name) =
+                    DefinedName::new(&name, &reference, scope.as_deref())
+                {
+                    names.push(defined_name);
+                }
+            }
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                let name = attr(&e, b"name").unwrap_or_default();
+                let state = attr(&e, b"state").unwrap_or_else(|| "visible".to_string());
+                sheet_names.push(name.clone());
+
+                if let Some(path) = attr(&e, b"id").and_then(|id| rels.get(&id)) {
+                    entries.push(SheetEntry {
+                        name,
+                        state,
+                        path: path.clone(),
+                        builtin_names: Vec::new(),
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((entries, names))
+}
+
+/// Чтение текста элемента (t, r/t) до закрывающего тега `end`.
+fn read_text(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<String> {
+    let mut text = String::new();
+    let mut in_text = false;
+    let mut in_phonetic = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Text(t) if in_text && !in_phonetic => text.push_str(&t.unescape()?),
+            Event::CData(t) if in_text && !in_phonetic => {
+                text.push_str(&String::from_utf8_lossy(&t.into_inner()))
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                name if name == end => break,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+/// Чтение строки (si, is) с фрагментами форматированного текста до тега `end`.
+/// Строка без оформленных фрагментов возвращается обычным текстом.
+fn read_string_item(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<CellRawValue> {
+    let mut text = String::new();
+    let mut runs: Vec<TextRun> = Vec::new();
+    let mut in_run = false;
+    let mut in_props = false;
+    let mut in_text = false;
+    let mut in_phonetic = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"r" if !in_phonetic => {
+                    in_run = true;
+                    runs.push(TextRun::default());
+                }
+                b"rPr" if in_run => in_props = true,
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Empty(e) if in_props => {
+                if let Some(run) = runs.last_mut() {
+                    read_run_property(run.font.get_or_insert_default(), &e);
+                }
+            }
+            Event::Text(t) if in_text && !in_phonetic => {
+                let value = t.unescape()?;
+                match runs.last_mut().filter(|_| in_run) {
+                    Some(run) => run.text.push_str(&value),
+                    None => text.push_str(&value),
+                }
+            }
+            Event::CData(t) if in_text && !in_phonetic => {
+                let value = String::from_utf8_lossy(&t.into_inner()).to_string();
+                match runs.last_mut().filter(|_| in_run) {
+                    Some(run) => run.text.push_str(&value),
+                    None => text.push_str(&value),
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"r" => in_run = false,
+                b"rPr" => in_props = false,
+                b"rPh" => in_phonetic = false,
+                name if name == end => break,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if runs.iter().any(|r| r.font.is_some()) {
+        return Ok(CellRawValue::RichText(RichText::new(runs)));
+    }
+
+    for run in runs {
+        text.push_str(&run.text);
+    }
+
+    Ok(CellRawValue::String(text.into_boxed_str()))
+}
+
+/// Чтение свойства шрифта фрагмента (rPr).
+fn read_run_property(font: &mut Font, e: &BytesStart) {
+    let val = attr(e, b"val");
+    let enabled = val.as_deref().is_none_or(is_true);
+
+    match e.local_name().as_ref() {
+        b"b" => font.bold = enabled,
+        b"i" => font.italic = enabled,
+        b"strike" => font.strike = enabled,
+        b"u" => font.underline = val.as_deref() != Some("none"),
+        b"sz" => font.size = val.and_then(|v| v.parse().ok()),
+        b"rFont" => font.name = val,
+        b"color" => font.color = attr(e, b"rgb").and_then(|c| c.parse().ok()),
+        _ => {}
+    }
+}
+
+/// Чтение таблицы общих строк.
+fn read_shared_strings(xml: &str) -> Result<Vec<CellRawValue>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"si" => {
+                strings.push(read_string_item(&mut reader, b"si")?);
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => {
+                strings.push(CellRawValue::Empty)
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(strings)
+}
+
+/// Чтение стилей: для каждого cellXfs признак формата даты.
+fn read_date_styles(xml: &str) -> Result<Vec<bool>> {
+    let mut reader = Reader::from_str(xml);
+    let mut num_fmts: HashMap<u32, String> = HashMap::default();
+    let mut date_styles = Vec::new();
+    let mut in_cell_xfs = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"numFmt" => {
+                    if let (Some(id), Some(code)) = (
+                        attr(&e, b"numFmtId").and_then(|v| v.parse().ok()),
+                        attr(&e, b"formatCode"),
+                    ) {
+                        num_fmts.insert(id, code);
+                    }
+                }
+                b"cellXfs" => in_cell_xfs = true,
+                b"xf" if in_cell_xfs => {
+                    let id: u32 = attr(&e, b"numFmtId")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or_default();
+
+                    let is_date = match num_fmts.get(&id) {
+                        Some(code) => is_date_format(code),
+                        None => builtin_num_fmt(id).is_some_and(is_date_format),
+                    };
+                    date_styles.push(is_date);
+                }
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"cellXfs" => in_cell_xfs = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(date_styles)
+}
+
+/// Сырые данные ячейки из sheetData.
+#[derive(Debug, Default)]
+struct RawCell {
+    coordinate: Coordinate,
+    cell_type: String,
+    style: usize,
+    value: Option<String>,
+    /// Форматированный текст ячейки, `value` - его текст
+    rich_text: Option<RichText>,
+    formula: Option<String>,
+    /// Номер общей формулы (`<f t="shared" si="..">`)
+    shared_formula: Option<String>,
+}
+
+impl RawCell {
+    /// Преобразование в ячейку с определением типа данных.
+    fn into_cell(self, shared_strings: &[CellRawValue], date_styles: &[bool]) -> Cell {
+        let mut rich_text = self.rich_text;
+
+        let (value, data_type) = match self.cell_type.as_str() {
+            "s" => {
+                let shared = self
+                    .value
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .and_then(|idx| shared_strings.get(idx));
+
+                if let Some(CellRawValue::RichText(r)) = shared {
+                    rich_text = Some(r.clone());
+                }
+
+                (shared.map(ToString::to_string), "s")
+            }
+            "str" | "inlineStr" | "e" => (self.value, "s"),
+            "b" => (
+                self.value
+                    .map(|v| if v.trim() == "1" { "TRUE" } else { "FALSE" }.to_string()),
+                "b",
+            ),
+            "d" => (self.value.map(|v| v.replace('T', " ")), "d"),
+            _ => {
+                let is_date = date_styles.get(self.style).copied().unwrap_or(false);
+
+                match self.value.as_deref().map(str::parse::<f64>) {
+                    Some(Ok(serial)) if is_date => (
+                        excel_serial_to_datetime(serial)
+                            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                            .or(self.value),
+                        "d",
+                    ),
+                    _ => (self.value, "n"),
+                }
+            }
+        };
+
+        match self.formula {
+            Some(formula) => {
+                // Строковый результат формулы сохраняем без угадывания типа
+                let mut cell = Cell::extract(self.coordinate, None, Some(formula), "f", None);
+                if let Some(value) = value {
+                    let raw_value = if data_type == "s" {
+                        CellRawValue::String(value.into_boxed_str())
+                    } else {
+                        CellValue::guess_typed_data(&value)
+                    };
+                    cell.set_formula_result(raw_value);
+                }
+
+                cell
+            }
+            None => {
+                let mut cell = Cell::extract(self.coordinate, value, None, data_type, None);
+                if let Some(rich_text) = rich_text {
+                    cell.set_value_rich_text(rich_text);
+                }
+
+                cell
+            }
+        }
+    }
+}
+
+/// Чтение листа.
+fn read_sheet(
+    entry: &SheetEntry,
+    xml: &str,
+    shared_strings: &[CellRawValue],
+    date_styles: &[bool],
+    external_rels: &HashMap<String, String>,
+) -> Result<Sheet> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut map = ahash::HashMap::default();
+    let mut ranges = Vec::new();
+    let mut row_heights = Vec::new();
+    let mut hidden_rows = Vec::new();
+    let mut col_widths = Vec::new();
+    let mut hidden_cols = Vec::new();
+    let mut row_outline = Vec::new();
+    let mut col_outline = Vec::new();
+    let mut summary = (true, true);
+    let mut view = SheetView::default();
+    let mut auto_filter = None;
+    let mut setup = PageSetup::default();
+    let mut hyperlinks = Vec::new();
+    let mut protection = None;
+
+    let mut current_row: u32 = 0;
+    let mut current_col: u16 = 0;
+    let mut cell: Option<RawCell> = None;
+    // Общие формулы по номеру: текст и ячейка, для которой он записан
+    let mut shared_formulas: HashMap<String, (String, Coordinate)> = HashMap::default();
+    let mut in_value = false;
+    let mut in_formula = false;
+
+    loop {
+        let event = reader.read_event()?;
+        let is_empty = matches!(event, Event::Empty(_));
+
+        match event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"row" => {
+                    current_row = attr(&e, b"r")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(current_row + 1);
+                    current_col = 0;
+
+                    if let Some(ht) = attr(&e, b"ht").and_then(|v| v.parse::<f64>().ok()) {
+                        row_heights.push((current_row, ht));
+                    }
+                    if attr(&e, b"hidden").is_some_and(|v| is_true(&v)) {
+                        hidden_rows.push(current_row);
+                    }
+                    let level = attr(&e, b"outlineLevel").and_then(|v| v.parse::<u32>().ok());
+                    let collapsed = attr(&e, b"collapsed").is_some_and(|v| is_true(&v));
+                    if level.is_some() || collapsed {
+                        row_outline.push((current_row, level.unwrap_or(0), collapsed));
+                    }
+                }
+                b"col" => {
+                    let min: u16 = attr(&e, b"min").and_then(|v| v.parse().ok()).unwrap_or(1);
+                    let max: u16 = attr(&e, b"max")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(min)
+                        .min(MAX_COL);
+                    let width = attr(&e, b"width").and_then(|v| v.parse::<f64>().ok());
+                    let hidden = attr(&e, b"hidden").is_some_and(|v| is_true(&v));
+                    let custom = attr(&e, b"customWidth").is_some_and(|v| is_true(&v));
+                    let level = attr(&e, b"outlineLevel").and_then(|v| v.parse::<u32>().ok());
+                    let collapsed = attr(&e, b"collapsed").is_some_and(|v| is_true(&v));
+
+                    for col in min..=max {
+                        if let Some(width) = width.filter(|_| custom || hidden) {
+                            col_widths.push((col, width));
+                        }
+                        if hidden {
+                            hidden_cols.push(col);
+                        }
+                        if level.is_some() || collapsed {
+                            col_outline.push((col, level.unwrap_or(0), collapsed));
+                        }
+                    }
+                }
+                b"c" => {
+                    let coordinate = attr(&e, b"r")
+                        .and_then(|r| Coordinate::parse(&r).ok())
+                        .unwrap_or_else(|| Coordinate::new(current_row, current_col + 1));
+                    current_col = coordinate.column;
+
+                    let raw = RawCell {
+                        coordinate,
+                        cell_type: attr(&e, b"t").unwrap_or_default(),
+                        style: attr(&e, b"s").and_then(|v| v.parse().ok()).unwrap_or(0),
+                        ..Default::default()
+                    };
+
+                    if is_empty {
+                        let c = raw.into_cell(shared_strings, date_styles);
+                        let coord = c.get_coordinate();
+                        map.insert((coord.row, coord.column), Arc::new(RwLock::new(c)));
+                    } else {
+                        cell = Some(raw);
+                    }
+                }
+                b"v" if !is_empty => in_value = true,
+                b"f" => {
+                    // Текст есть у обычной формулы и у главной ячейки общей формулы,
+                    // зависимые ячейки общей формулы получают ее со сдвигом
+                    in_formula = !is_empty;
+                    if let Some(c) = cell.as_mut()
+                        && attr(&e, b"t").as_deref() == Some("shared")
+                    {
+                        c.shared_formula = attr(&e, b"si");
+                    }
+                }
+                b"is" if !is_empty => {
+                    let value = read_string_item(&mut reader, b"is")?;
+                    if let Some(c) = cell.as_mut() {
+                        c.value = Some(value.to_string());
+                        if let CellRawValue::RichText(r) = value {
+                            c.rich_text = Some(r);
+                        }
+                    }
+                }
+                b"mergeCell" => {
+                    if let Some(range) = attr(&e, b"ref").and_then(|r| Range::parse(&r).ok()) {
+                        ranges.push(range);
+                    }
+                }
+                b"hyperlink" => {
+                    let external = attr(&e, b"id").and_then(|id| external_rels.get(&id));
+                    let location = attr(&e, b"location");
+
+                    let target = match (external, location) {
+                        (Some(url), Some(location)) => Some(format!("{url}#{location}")),
+                        (Some(url), None) => Some(url.clone()),
+                        // Место в книге, которое не является ссылкой (например имя), помечаем `#`
+                        (None, Some(location)) => {
+                            match Hyperlink::new(&location, None).get_location() {
+                                Some(_) => Some(location),
+                                None => Some(format!("#{location}")),
+                            }
+                        }
+                        (None, None) => None,
+                    };
+
+                    if let (Some(range), Some(target)) =
+                        (attr(&e, b"ref").and_then(|r| Range::parse(&r).ok()), target)
+                    {
+                        let tooltip = attr(&e, b"tooltip");
+                        hyperlinks.push((range, Hyperlink::new(&target, tooltip.as_deref())));
+                    }
+                }
+                b"autoFilter" => auto_filter = attr(&e, b"ref").and_then(|r| Range::parse(&r).ok()),
+                b"sheetProtection" => protection = read_sheet_protection(&e),
+                b"outlinePr" => {
+                    summary = (
+                        attr(&e, b"summaryBelow").is_none_or(|v| is_true(&v)),
+                        attr(&e, b"summaryRight").is_none_or(|v| is_true(&v)),
+                    );
+                }
+                b"tabColor" => view.tab_color = attr(&e, b"rgb").and_then(|c| c.parse().ok()),
+                b"sheetView" => {
+                    view.show_grid_lines = attr(&e, b"showGridLines").is_none_or(|v| is_true(&v));
+                    view.right_to_left = attr(&e, b"rightToLeft").is_some_and(|v| is_true(&v));
+                    if let Some(zoom) = attr(&e, b"zoomScale").and_then(|v| v.parse().ok()) {
+                        view.zoom = zoom;
+                    }
+                }
+                b"pane" => {
+                    let x: f64 = attr(&e, b"xSplit")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0.0);
+                    let y: f64 = attr(&e, b"ySplit")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0.0);
+
+                    match attr(&e, b"state").as_deref() {
+                        Some("frozen" | "frozenSplit") => {
+                            view.frozen_cols = x as u16;
+                            view.frozen_rows = y as u32;
+                        }
+                        _ if x > 0.0 || y > 0.0 => view.split = Some(Split { x, y }),
+                        _ => {}
+                    }
+                }
+                b"pageSetUpPr" if attr(&e, b"fitToPage").is_some_and(|v| is_true(&v)) => {
+                    setup.fit_to_page = Some(FitToPage::default());
+                }
+                b"pageMargins" => {
+                    let margins = &mut setup.margins;
+                    for (name, value) in [
+                        (&b"left"[..], &mut margins.left),
+                        (b"right", &mut margins.right),
+                        (b"top", &mut margins.top),
+                        (b"bottom", &mut margins.bottom),
+                        (b"header", &mut margins.header),
+                        (b"footer", &mut margins.footer),
+                    ] {
+                        if let Some(v) = attr(&e, name).and_then(|v| v.parse().ok()) {
+                            *value = v;
+                        }
+                    }
+                }
+                b"pageSetup" => {
+                    setup.paper_size = attr(&e, b"paperSize").and_then(|v| v.parse().ok());
+                    setup.scale = attr(&e, b"scale")
+                        .and_then(|v| v.parse().ok())
+                        .filter(|scale| *scale != 100);
+                    if attr(&e, b"orientation").as_deref() == Some("landscape") {
+                        setup.orientation = Orientation::Landscape;
+                    }
+                    if let Some(fit) = setup.fit_to_page.as_mut() {
+                        let pages = |name| attr(&e, name).and_then(|v| v.parse().ok());
+                        fit.width = pages(b"fitToWidth").unwrap_or(1);
+                        fit.height = pages(b"fitToHeight").unwrap_or(1);
+                    }
+                }
+                b"oddHeader" if !is_empty => {
+                    setup.header = Some(read_text_content(&mut reader, b"oddHeader")?);
+                }
+                b"oddFooter" if !is_empty => {
+                    setup.footer = Some(read_text_content(&mut reader, b"oddFooter")?);
+                }
+                b"selection" => {
+                    view.selected_cell =
+                        attr(&e, b"activeCell").and_then(|c| Coordinate::parse(&c).ok());
+                }
+                _ => {}
+            },
+            Event::Text(t) => {
+                if let Some(c) = cell.as_mut() {
+                    if in_value {
+                        c.value
+                            .get_or_insert_with(String::new)
+                            .push_str(&t.unescape()?);
+                    } else if in_formula {
+                        c.formula
+                            .get_or_insert_with(String::new)
+                            .push_str(&t.unescape()?);
+                    }
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" => in_value = false,
+                b"f" => in_formula = false,
+                b"c" => {
+                    if let Some(mut raw) = cell.take() {
+                        if let Some(si) = raw.shared_formula.take() {
+                            match &raw.formula {
+                                Some(formula) => {
+                                    shared_formulas
+                                        .insert(si, (formula.clone(), raw.coordinate.clone()));
+                                }
+                                None => {
+                                    raw.formula = shared_formulas.get(&si).map(|(f, origin)| {
+                                        let rows = raw.coordinate.row as i64 - origin.row as i64;
+                                        let cols =
+                                            raw.coordinate.column as i64 - origin.column as i64;
+                                        offset_formula(f, rows, cols).unwrap_or_else(|| f.clone())
+                                    });
+                                }
+                            }
+                        }
+
+                        let c = raw.into_cell(shared_strings, date_styles);
+                        let coord = c.get_coordinate();
+                        map.insert((coord.row, coord.column), Arc::new(RwLock::new(c)));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    for (range, link) in hyperlinks {
+        let coordinate = Coordinate::new(range.start_row, range.start_col);
+        map.entry((coordinate.row, coordinate.column))
+            .or_insert_with(|| Arc::new(RwLock::new(Cell::new(coordinate, None))))
+            .write()
+            .set_hyperlink(link.get_target(), link.get_tooltip());
+    }
+
+    let mut sheet = Sheet::extract(&entry.name, &entry.state, ranges, map);
+
+    for (row, height) in row_heights {
+        sheet.set_height_row(row, height);
+    }
+    for row in hidden_rows {
+        sheet.set_hidden_row(row, true);
+    }
+    for (col, width) in col_widths {
+        sheet.set_width_column(col, width);
+    }
+    for col in hidden_cols {
+        sheet.set_hidden_column(col, true);
+    }
+    for (row, level, collapsed) in row_outline {
+        let _ = sheet.group_rows(row, row, level);
+        sheet.set_collapsed_row(row, collapsed);
+    }
+    for (col, level, collapsed) in col_outline {
+        let _ = sheet.group_cols(col, col, level);
+        sheet.set_collapsed_column(col, collapsed);
+    }
+    sheet.set_summary_below(summary.0);
+    sheet.set_summary_right(summary.1);
+
+    for (name, reference) in &entry.builtin_names {
+        read_print_names(&mut setup, name, reference);
+    }
+
+    // Некорректные настройки отображения и печати не мешают чтению данных
+    let _ = sheet.set_sheet_view(view);
+    let _ = sheet.set_page_setup(setup);
+    sheet.set_auto_filter(auto_filter);
+    let _ = sheet.set_protection(protection);
+
+    Ok(sheet)
+}
+
+/// Чтение защиты листа. Атрибуты XML запрещают действия, флаги структуры - разрешают.
+fn read_sheet_protection(e: &BytesStart) -> Option<SheetProtection> {
+    if !attr(e, b"sheet").is_some_and(|v| is_true(&v)) {
+        return None;
+    }
+
+    let flag = |name: &[u8], default: bool| attr(e, name).map_or(default, |v| is_true(&v));
+
+    Some(SheetProtection {
+        password_hash: attr(e, b"password"),
+        select_locked_cells: !flag(b"selectLockedCells", false),
+        select_unlocked_cells: !flag(b"selectUnlockedCells", false),
+        format_cells: !flag(b"formatCells", true),
+        format_columns: !flag(b"formatColumns", true),
+        format_rows: !flag(b"formatRows", true),
+        insert_columns: !flag(b"insertColumns", true),
+        insert_rows: !flag(b"insertRows", true),
+        insert_hyperlinks: !flag(b"insertHyperlinks", true),
+        delete_columns: !flag(b"deleteColumns", true),
+        delete_rows: !flag(b"deleteRows", true),
+        sort: !flag(b"sort", true),
+        auto_filter: !flag(b"autoFilter", true),
+        pivot_tables: !flag(b"pivotTables", true),
+        objects: !flag(b"objects", false),
+        scenarios: !flag(b"scenarios", false),
+    })
+}
+
+/// Чтение комментариев листа.
+fn read_comments(xml: &str) -> Result<Vec<Comment>> {
+    let mut reader = Reader::from_str(xml);
+    let mut authors = Vec::new();
+    let mut comments = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"author" => authors.push(read_text_content(&mut reader, b"author")?),
+                b"comment" => {
+                    let coordinate = attr(&e, b"ref")
+                        .and_then(|r| Coordinate::parse(&r).ok())
+                        .unwrap_or_default();
+                    let author = attr(&e, b"authorId")
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .and_then(|id| authors.get(id).cloned())
+                        .unwrap_or_default();
+                    let text = read_text(&mut reader, b"comment")?;
+
+                    let mut comment = Comment::new(coordinate, &author);
+                    comment.set_text(&text);
+                    comments.push(comment);
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(comments)
+}
+
+/// Чтение простого текстового содержимого элемента.
+fn read_text_content(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<String> {
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Text(t) => text.push_str(&t.unescape()?),
+            Event::End(e) if e.local_name().as_ref() == end => break,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+/// Чтение области печати и сквозных строк/колонок из служебных имен.
+fn read_print_names(setup: &mut PageSetup, name: &str, reference: &str) {
+    let Ok(tokens) = tokenize(reference) else {
+        return;
+    };
+
+    for token in tokens {
+        let TokenKind::Reference(reference) = token.kind else {
+            continue;
+        };
+
+        match (name, reference.kind) {
+            ("_xlnm.Print_Area", RefKind::Range(a, b)) => {
+                setup.print_area = Some(Range::new(a.row, b.row, a.col, b.col));
+            }
+            ("_xlnm.Print_Area", RefKind::Cell(a)) => {
+                setup.print_area = Some(Range::new(a.row, a.row, a.col, a.col));
+            }
+            ("_xlnm.Print_Titles", RefKind::Rows { start, end, .. }) => {
+                setup.print_title_rows = Some((start, end));
+            }
+            ("_xlnm.Print_Titles", RefKind::Cols { start, end, .. }) => {
+                setup.print_title_cols = Some((start, end));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Признак истинности атрибута
+fn is_true(value: &str) -> bool {
+    matches!(value, "1" | "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        structs::book::Book,
+        traits::{ReadableSheet, WriteableCell},
+    };
+
+    fn roundtrip(book: &Book) -> Vec<Sheet> {
+        let mut buf = Cursor::new(Vec::new());
+        book.write_xlsx(&mut buf).unwrap();
+        buf.set_position(0);
+
+        read_xlsx(buf).unwrap()
+    }
+
+    #[test]
+    fn read_values() {
+        let mut book = Book::new();
+        let sheet = book.add_sheet("Данные", "visible").unwrap();
+
+        {
+            let mut sheet = sheet.write();
+            sheet.cell(Coordinate::new(1, 1), Some("Итого"));
+            sheet.cell(Coordinate::new(1, 2), Some("42"));
+            sheet.cell(Coordinate::new(1, 3), Some("4.5"));
+            sheet.cell(Coordinate::new(1, 4), Some("true"));
+            sheet.cell(Coordinate::new(1, 5), Some("2024-01-02 10:30:00"));
+            sheet
+                .cell(Coordinate::new(2, 2), None)
+                .write()
+                .set_formula("=B1*2");
+        }
+
+        let sheets = roundtrip(&book);
+        let sheet = &sheets[0];
+
+        assert_eq!(sheet.get_name(), "Данные");
+        assert_eq!(sheet.get_cell_value((1, 1)), "Итого");
+        assert_eq!(sheet.get_cell_value((1, 2)), "42");
+        assert_eq!(sheet.get_cell_value((1, 3)), "4.5");
+        assert_eq!(sheet.get_cell_value((1, 4)), "true");
+        assert_eq!(sheet.get_cell_value((1, 5)), "2024-01-02 10:30:00");
+
+        let cell = sheet.find_cell_by_coords(1, 5).unwrap().unwrap().read();
+        assert_eq!(cell.get_data_type(), "d");
+
+        let cell = sheet.find_cell_by_coords(2, 2).unwrap().unwrap().read();
+        assert!(cell.is_formula());
+        assert_eq!(cell.get_formula().unwrap(), "B1*2");
+    }
+
+    #[test]
+    fn read_structure() {
+        let mut book = Book::new();
+        let sheet = book.add_sheet("A", "visible").unwrap();
+
+        {
+            let mut sheet = sheet.write();
+            sheet.cell(Coordinate::new(1, 1), Some("Заголовок"));
+            sheet.add_merge_range(Range::new(1, 2, 1, 3)).unwrap();
+            sheet.set_height_row(1, 30.0);
+            sheet.set_hidden_row(3, true);
+            sheet.set_width_column(2, 40.0);
+            sheet.set_hidden_column(4, true);
+
+            let mut comment = Comment::new(Coordinate::new(1, 1), "A.C");
+            comment.set_text("Примечание");
+            sheet.add_comments(comment);
+        }
+
+        book.add_sheet("B", "hidden").unwrap();
+
+        let sheets = roundtrip(&book);
+        let sheet = &sheets[0];
+
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[1].get_sheet_state(), "hidden");
+        assert_eq!(sheet.get_merge_cell_collection(), &[Range::new(1, 2, 1, 3)]);
+        assert_eq!(*sheet.get_height_by_row(1), 30.0);
+        assert!(*sheet.get_hidden_by_row(3));
+        assert_eq!(*sheet.get_width_by_column(2), 40.0);
+        assert!(*sheet.get_hidden_by_column(4));
+
+        let comment = sheet.get_comments()[0].read();
+        assert_eq!(comment.get_author(), "A.C");
+        assert_eq!(comment.get_text(), "Примечание");
+    }
+
+    /// Книга в том виде, в котором ее сохраняет Excel: общие строки,
+    /// формулы, протянутые вниз и вправо, записаны как общие (`t="shared"`)
+    fn excel_package() -> Cursor<Vec<u8>> {
+        let parts = [
+            (
+                "[Content_Types].xml",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+                    r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+                    r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+                    r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+                    r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                    r#"<Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>"#,
+                    "</Types>"
+                ),
+            ),
+            (
+                "_rels/.rels",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+                    "</Relationships>"
+                ),
+            ),
+            (
+                "xl/workbook.xml",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+                    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+                    r#"xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006" mc:Ignorable="x15">"#,
+                    r#"<fileVersion appName="xl" lastEdited="7" lowestEdited="7" rupBuild="27425"/>"#,
+                    r#"<workbookPr defaultThemeVersion="166925"/>"#,
+                    r#"<bookViews><workbookView xWindow="-120" yWindow="-120" windowWidth="29040" windowHeight="15840"/></bookViews>"#,
+                    r#"<sheets><sheet name="Лист1" sheetId="1" r:id="rId1"/></sheets>"#,
+                    r#"<calcPr calcId="191029"/></workbook>"#
+                ),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+                    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="sharedStrings.xml"/>"#,
+                    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+                    "</Relationships>"
+                ),
+            ),
+            (
+                "xl/sharedStrings.xml",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="4" uniqueCount="4">"#,
+                    "<si><t>Цена</t></si><si><t>Кол-во</t></si><si><t>Сумма</t></si><si><t>Итого</t></si></sst>"
+                ),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                concat!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                    r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+                    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+                    r#"xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006" mc:Ignorable="x14ac xr xr2 xr3" "#,
+                    r#"xmlns:x14ac="http://schemas.microsoft.com/office/spreadsheetml/2009/9/ac">"#,
+                    r#"<dimension ref="A1:D5"/><sheetViews><sheetView tabSelected="1" workbookViewId="0"/></sheetViews>"#,
+                    r#"<sheetFormatPr defaultRowHeight="15" x14ac:dyDescent="0.25"/><sheetData>"#,
+                    r#"<row r="1" spans="1:4" x14ac:dyDescent="0.25">"#,
+                    r#"<c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>2</v></c></row>"#,
+                    r#"<row r="2" spans="1:4" x14ac:dyDescent="0.25"><c r="A2"><v>10</v></c><c r="B2"><v>2</v></c>"#,
+                    r#"<c r="C2"><f>A2*B2</f><v>20</v></c><c r="D2"><f t="shared" ref="D2:D4" si="0">C2/$C$5</f><v>0.2</v></c></row>"#,
+                    r#"<row r="3" spans="1:4" x14ac:dyDescent="0.25"><c r="A3"><v>20</v></c><c r="B3"><v>2</v></c>"#,
+                    r#"<c r="C3"><f t="shared" ref="C3:C4" si="1">A3*B3</f><v>40</v></c><c r="D3"><f t="shared" si="0"/><v>0.4</v></c></row>"#,
+                    r#"<row r="4" spans="1:4" x14ac:dyDescent="0.25"><c r="A4"><v>40</v></c><c r="B4"><v>1</v></c>"#,
+                    r#"<c r="C4"><f t="shared" si="1"/><v>40</v></c><c r="D4"><f t="shared" si="0"/><v>0.4</v></c></row>"#,
+                    r#"<row r="5" spans="1:4" x14ac:dyDescent="0.25"><c r="A5" t="s"><v>3</v></c>"#,
+                    r#"<c r="B5"><f t="shared" ref="B5:C5" si="2">SUM(B2:B4)</f><v>5</v></c><c r="C5"><f t="shared" si="2"/><v>100</v></c></row>"#,
+                    r#"</sheetData><pageMargins left="0.7" right="0.7" top="0.75" bottom="0.75" header="0.3" footer="0.3"/></worksheet>"#
+                ),
+            ),
+        ];
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in parts {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut zip, data.as_bytes()).unwrap();
+        }
+
+        let mut buf = zip.finish().unwrap();
+        buf.set_position(0);
+        buf
+    }
+
+    #[test]
+    fn read_excel_shared_formulas() {
+        let sheets = read_xlsx(excel_package()).unwrap();
+        let sheet = &sheets[0];
+        let formula = |row, col| {
+            let cell = sheet.find_cell_by_coords(row, col).unwrap().unwrap();
+            cell.read().get_formula()
+        };
+
+        assert_eq!(sheet.get_name(), "Лист1");
+        assert_eq!(sheet.get_cell_value((1, 3)), "Сумма");
+        assert_eq!(formula(2, 3).as_deref(), Some("A2*B2"));
+        assert_eq!(formula(3, 3).as_deref(), Some("A3*B3"));
+        assert_eq!(formula(4, 3).as_deref(), Some("A4*B4"));
+        assert_eq!(formula(2, 4).as_deref(), Some("C2/$C$5"));
+        assert_eq!(formula(4, 4).as_deref(), Some("C4/$C$5"));
+        assert_eq!(formula(5, 3).as_deref(), Some("SUM(C2:C4)"));
+        assert_eq!(sheet.get_cell_value((4, 3)), "40");
+    }
+
+    #[test]
+    fn resolve_targets() {
+        assert_eq!(
+            resolve_target("xl", "worksheets/sheet1.xml"),
+            "xl/worksheets/sheet1.xml"
+        );
+        assert_eq!(
+            resolve_target("xl/worksheets", "../comments1.xml"),
+            "xl/comments1.xml"
+        );
+        assert_eq!(resolve_target("xl", "/xl/styles.xml"), "xl/styles.xml");
+    }
+}
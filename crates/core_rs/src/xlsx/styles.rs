@@ -0,0 +1,609 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use ahash::HashMap;
+
+use super::escape;
+use crate::structs::style::{Alignment, Border, CellProtection, Font, Format, Style};
+
+/// Первый идентификатор пользовательского числового формата.
+const CUSTOM_NUM_FMT_ID: u32 = 164;
+
+/// Встроенные числовые форматы Excel.
+pub(crate) const BUILTIN_NUM_FMTS: [(u32, &str); 23] = [
+    (0, "General"),
+    (1, "0"),
+    (2, "0.00"),
+    (3, "#,##0"),
+    (4, "#,##0.00"),
+    (9, "0%"),
+    (10, "0.00%"),
+    (11, "0.00E+00"),
+    (12, "# ?/?"),
+    (13, "# ??/??"),
+    (14, "mm-dd-yy"),
+    (15, "d-mmm-yy"),
+    (16, "d-mmm"),
+    (17, "mmm-yy"),
+    (18, "h:mm AM/PM"),
+    (19, "h:mm:ss AM/PM"),
+    (20, "h:mm"),
+    (21, "h:mm:ss"),
+    (22, "m/d/yy h:mm"),
+    (45, "mm:ss"),
+    (46, "[h]:mm:ss"),
+    (47, "mmss.0"),
+    (49, "@"),
+];
+
+/// Получение кода встроенного числового формата по идентификатору
+pub(crate) fn builtin_num_fmt(id: u32) -> Option<&'static str> {
+    BUILTIN_NUM_FMTS
+        .iter()
+        .find(|(i, _)| *i == id)
+        .map(|(_, code)| *code)
+}
+
+/// Проверка, является ли числовой формат форматом даты/времени
+pub(crate) fn is_date_format(code: &str) -> bool {
+    let mut in_quotes = false;
+    let mut in_brackets = false;
+    let mut prev_escape = false;
+
+    for ch in code.chars() {
+        if prev_escape {
+            prev_escape = false;
+            continue;
+        }
+
+        match ch {
+            '\\' => prev_escape = true,
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_brackets = true,
+            ']' if !in_quotes => in_brackets = false,
+            // Секция текста или общий формат не являются датой
+            '@' if !in_quotes => return false,
+            'd' | 'D' | 'm' | 'M' | 'y' | 'Y' | 'h' | 'H' | 's' | 'S'
+                if !in_quotes && !in_brackets =>
+            {
+                return true;
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Шрифт по умолчанию.
+const DEFAULT_FONT: &str = r#"<font><sz val="11"/><name val="Calibri"/><family val="2"/></font>"#;
+
+/// Ключ формата ячейки (cellXfs).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct XfKey {
+    num_fmt_id: u32,
+    font_id: usize,
+    fill_id: usize,
+    border_id: usize,
+    alignment: Option<String>,
+    protection: Option<String>,
+}
+
+/// Список уникальных XML-элементов (шрифтов, заливок, границ).
+#[derive(Debug)]
+struct Pool {
+    items: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl Pool {
+    fn new(defaults: &[&str]) -> Self {
+        let items: Vec<String> = defaults.iter().map(|d| d.to_string()).collect();
+        let index = items
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.clone(), i))
+            .collect();
+
+        Self { items, index }
+    }
+
+    fn add(&mut self, xml: String) -> usize {
+        if let Some(idx) = self.index.get(&xml) {
+            return *idx;
+        }
+
+        let idx = self.items.len();
+        self.index.insert(xml.clone(), idx);
+        self.items.push(xml);
+
+        idx
+    }
+
+    fn write(&self, xml: &mut String, tag: &str) {
+        let _ = write!(xml, r#"<{tag} count="{}">"#, self.items.len());
+        for item in &self.items {
+            xml.push_str(item);
+        }
+        let _ = write!(xml, "</{tag}>");
+    }
+}
+
+/// Таблица стилей книги, собираемая при записи листов.
+#[derive(Debug)]
+pub(crate) struct StyleTable<'a> {
+    registry: Option<&'a BTreeMap<String, Format>>,
+    num_fmts: Vec<(u32, String)>,
+    num_fmt_index: HashMap<String, u32>,
+    fonts: Pool,
+    fills: Pool,
+    borders: Pool,
+    dxfs: Pool,
+    xfs: Vec<XfKey>,
+    xf_index: HashMap<XfKey, usize>,
+}
+
+impl Default for StyleTable<'_> {
+    fn default() -> Self {
+        let default_xf = XfKey::default();
+
+        let mut xf_index = HashMap::default();
+        xf_index.insert(default_xf.clone(), 0);
+
+        Self {
+            registry: None,
+            num_fmts: Vec::new(),
+            num_fmt_index: HashMap::default(),
+            fonts: Pool::new(&[DEFAULT_FONT]),
+            fills: Pool::new(&[
+                r#"<fill><patternFill patternType="none"/></fill>"#,
+                r#"<fill><patternFill patternType="gray125"/></fill>"#,
+            ]),
+            borders: Pool::new(&["<border><left/><right/><top/><bottom/><diagonal/></border>"]),
+            dxfs: Pool::new(&[]),
+            xfs: vec![default_xf],
+            xf_index,
+        }
+    }
+}
+
+impl<'a> StyleTable<'a> {
+    /// Таблица стилей с реестром именованных стилей книги
+    pub(crate) fn new(registry: &'a BTreeMap<String, Format>) -> Self {
+        Self {
+            registry: Some(registry),
+            ..Default::default()
+        }
+    }
+
+    /// Метод для получения идентификатора числового формата
+    fn num_fmt_id(&mut self, code: &str) -> u32 {
+        if let Some((id, _)) = BUILTIN_NUM_FMTS.iter().find(|(_, c)| *c == code) {
+            return *id;
+        }
+
+        if let Some(id) = self.num_fmt_index.get(code) {
+            return *id;
+        }
+
+        let id = CUSTOM_NUM_FMT_ID + self.num_fmts.len() as u32;
+        self.num_fmts.push((id, code.to_string()));
+        self.num_fmt_index.insert(code.to_string(), id);
+
+        id
+    }
+
+    /// Метод для получения описания стиля по имени из реестра или встроенного
+    fn resolve<'s>(&self, style: &'s Style) -> Option<&'s Format>
+    where
+        'a: 's,
+    {
+        let registry = self.registry;
+
+        style
+            .get_format()
+            .or_else(|| registry.and_then(|r| r.get(&style.get_id())))
+    }
+
+    /// Метод для получения индекса дифференциального формата (dxf) условного
+    /// форматирования. `None`, если стиль не найден.
+    pub(crate) fn dxf(&mut self, style: &Style) -> Option<usize> {
+        let format = self.resolve(style)?;
+        let num_fmt = format
+            .number_format
+            .as_deref()
+            .map(|code| (self.num_fmt_id(code), code));
+
+        Some(self.dxfs.add(dxf_xml(format, num_fmt)))
+    }
+
+    /// Метод для получения индекса формата ячейки по стилю и числовому формату.
+    /// Числовой формат ячейки приоритетнее формата стиля, `default_number_format`
+    /// используется, если не задан ни один из них.
+    /// Неизвестные имена стилей записываются стилем по умолчанию.
+    pub(crate) fn cell_xf(
+        &mut self,
+        style: Option<&Style>,
+        number_format: Option<&str>,
+        default_number_format: &str,
+    ) -> usize {
+        let format = style.and_then(|s| self.resolve(s));
+
+        let number_format = number_format
+            .or_else(|| format.and_then(|f| f.number_format.as_deref()))
+            .unwrap_or(default_number_format);
+
+        self.format_xf(format, number_format)
+    }
+
+    fn format_xf(&mut self, format: Option<&Format>, number_format: &str) -> usize {
+        let mut key = XfKey {
+            num_fmt_id: self.num_fmt_id(number_format),
+            ..Default::default()
+        };
+
+        if let Some(format) = format {
+            if let Some(font) = &format.font {
+                key.font_id = self.fonts.add(font_xml(font));
+            }
+            if let Some(fill) = &format.fill {
+                key.fill_id = self.fills.add(format!(
+                    r#"<fill><patternFill patternType="solid"><fgColor rgb="{}"/><bgColor indexed="64"/></patternFill></fill>"#,
+                    fill.color.get_argb()
+                ));
+            }
+            if let Some(border) = &format.border {
+                key.border_id = self.borders.add(border_xml(border));
+            }
+            key.alignment = alignment_xml(format.alignment.as_ref(), format.wrap);
+            key.protection = format.protection.as_ref().and_then(protection_xml);
+        }
+
+        if let Some(idx) = self.xf_index.get(&key) {
+            return *idx;
+        }
+
+        let idx = self.xfs.len();
+        self.xfs.push(key.clone());
+        self.xf_index.insert(key, idx);
+
+        idx
+    }
+
+    /// Формирование xl/styles.xml
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::from(super::XML_HEADER);
+        xml.push_str(
+            r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        );
+
+        if !self.num_fmts.is_empty() {
+            let _ = write!(xml, r#"<numFmts count="{}">"#, self.num_fmts.len());
+            for (id, code) in &self.num_fmts {
+                let _ = write!(
+                    xml,
+                    r#"<numFmt numFmtId="{id}" formatCode="{}"/>"#,
+                    escape(code)
+                );
+            }
+            xml.push_str("</numFmts>");
+        }
+
+        self.fonts.write(&mut xml, "fonts");
+        self.fills.write(&mut xml, "fills");
+        self.borders.write(&mut xml, "borders");
+        xml.push_str(
+            r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+        );
+
+        let _ = write!(xml, r#"<cellXfs count="{}">"#, self.xfs.len());
+        for xf in &self.xfs {
+            let _ = write!(
+                xml,
+                r#"<xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}" xfId="0""#,
+                xf.num_fmt_id, xf.font_id, xf.fill_id, xf.border_id
+            );
+            if xf.num_fmt_id != 0 {
+                xml.push_str(r#" applyNumberFormat="1""#);
+            }
+            if xf.font_id != 0 {
+                xml.push_str(r#" applyFont="1""#);
+            }
+            if xf.fill_id != 0 {
+                xml.push_str(r#" applyFill="1""#);
+            }
+            if xf.border_id != 0 {
+                xml.push_str(r#" applyBorder="1""#);
+            }
+            if xf.alignment.is_some() {
+                xml.push_str(r#" applyAlignment="1""#);
+            }
+            if xf.protection.is_some() {
+                xml.push_str(r#" applyProtection="1""#);
+            }
+            match (&xf.alignment, &xf.protection) {
+                (None, None) => xml.push_str("/>"),
+                (alignment, protection) => {
+                    let _ = write!(
+                        xml,
+                        ">{}{}</xf>",
+                        alignment.as_deref().unwrap_or_default(),
+                        protection.as_deref().unwrap_or_default()
+                    );
+                }
+            }
+        }
+        xml.push_str("</cellXfs>");
+
+        xml.push_str(
+            r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
+        );
+        self.dxfs.write(&mut xml, "dxfs");
+        xml.push_str(r#"<tableStyles count="0"/></styleSheet>"#);
+
+        xml
+    }
+}
+
+/// Формирование XML шрифта
+fn font_xml(font: &Font) -> String {
+    let mut xml = String::from("<font>");
+
+    if font.bold {
+        xml.push_str("<b/>");
+    }
+    if font.italic {
+        xml.push_str("<i/>");
+    }
+    if font.strike {
+        xml.push_str("<strike/>");
+    }
+    if font.underline {
+        xml.push_str("<u/>");
+    }
+    let _ = write!(xml, r#"<sz val="{}"/>"#, font.size.unwrap_or(11.0));
+    if let Some(color) = &font.color {
+        let _ = write!(xml, r#"<color rgb="{}"/>"#, color.get_argb());
+    }
+    let _ = write!(
+        xml,
+        r#"<name val="{}"/><family val="2"/></font>"#,
+        escape(font.name.as_deref().unwrap_or("Calibri"))
+    );
+
+    xml
+}
+
+/// Формирование XML шрифта фрагмента форматированного текста
+pub(crate) fn run_properties_xml(font: &Font) -> String {
+    let mut xml = String::from("<rPr>");
+
+    if let Some(name) = &font.name {
+        let _ = write!(xml, r#"<rFont val="{}"/>"#, escape(name));
+    }
+    if font.bold {
+        xml.push_str("<b/>");
+    }
+    if font.italic {
+        xml.push_str("<i/>");
+    }
+    if font.strike {
+        xml.push_str("<strike/>");
+    }
+    if let Some(color) = &font.color {
+        let _ = write!(xml, r#"<color rgb="{}"/>"#, color.get_argb());
+    }
+    if let Some(size) = font.size {
+        let _ = write!(xml, r#"<sz val="{size}"/>"#);
+    }
+    if font.underline {
+        xml.push_str("<u/>");
+    }
+    xml.push_str("</rPr>");
+
+    xml
+}
+
+/// Формирование XML дифференциального формата условного форматирования
+fn dxf_xml(format: &Format, num_fmt: Option<(u32, &str)>) -> String {
+    let mut xml = String::from("<dxf>");
+
+    if let Some(font) = &format.font {
+        xml.push_str("<font>");
+        if font.bold {
+            xml.push_str("<b/>");
+        }
+        if font.italic {
+            xml.push_str("<i/>");
+        }
+        if font.strike {
+            xml.push_str("<strike/>");
+        }
+        if font.underline {
+            xml.push_str("<u/>");
+        }
+        if let Some(color) = &font.color {
+            let _ = write!(xml, r#"<color rgb="{}"/>"#, color.get_argb());
+        }
+        xml.push_str("</font>");
+    }
+    if let Some((id, code)) = num_fmt {
+        let _ = write!(
+            xml,
+            r#"<numFmt numFmtId="{id}" formatCode="{}"/>"#,
+            escape(code)
+        );
+    }
+    if let Some(fill) = &format.fill {
+        let _ = write!(
+            xml,
+            r#"<fill><patternFill><bgColor rgb="{}"/></patternFill></fill>"#,
+            fill.color.get_argb()
+        );
+    }
+    if let Some(alignment) = alignment_xml(format.alignment.as_ref(), format.wrap) {
+        xml.push_str(&alignment);
+    }
+    if let Some(border) = &format.border {
+        xml.push_str(&border_xml(border));
+    }
+    xml.push_str("</dxf>");
+
+    xml
+}
+
+/// Формирование XML границ
+fn border_xml(border: &Border) -> String {
+    let mut xml = String::from("<border>");
+
+    for (tag, side) in [
+        ("left", &border.left),
+        ("right", &border.right),
+        ("top", &border.top),
+        ("bottom", &border.bottom),
+    ] {
+        match side {
+            Some(side) => {
+                let _ = write!(xml, r#"<{tag} style="{}">"#, side.style);
+                if let Some(color) = &side.color {
+                    let _ = write!(xml, r#"<color rgb="{}"/>"#, color.get_argb());
+                } else {
+                    xml.push_str(r#"<color auto="1"/>"#);
+                }
+                let _ = write!(xml, "</{tag}>");
+            }
+            None => {
+                let _ = write!(xml, "<{tag}/>");
+            }
+        }
+    }
+    xml.push_str("<diagonal/></border>");
+
+    xml
+}
+
+/// Формирование XML выравнивания. `None`, если выравнивание не задано.
+fn alignment_xml(alignment: Option<&Alignment>, wrap: bool) -> Option<String> {
+    let mut attrs = String::new();
+
+    if let Some(alignment) = alignment {
+        if let Some(h) = alignment.horizontal {
+            let _ = write!(attrs, r#" horizontal="{h}""#);
+        }
+        if let Some(v) = alignment.vertical {
+            let _ = write!(attrs, r#" vertical="{v}""#);
+        }
+        if wrap {
+            attrs.push_str(r#" wrapText="1""#);
+        }
+        if alignment.indent > 0 {
+            let _ = write!(attrs, r#" indent="{}""#, alignment.indent);
+        }
+    } else if wrap {
+        attrs.push_str(r#" wrapText="1""#);
+    }
+
+    (!attrs.is_empty()).then(|| format!("<alignment{attrs}/>"))
+}
+
+/// Формирование XML защиты ячейки. `None` для защиты по умолчанию
+fn protection_xml(protection: &CellProtection) -> Option<String> {
+    let mut attrs = String::new();
+
+    if !protection.locked {
+        attrs.push_str(r#" locked="0""#);
+    }
+    if protection.hidden {
+        attrs.push_str(r#" hidden="1""#);
+    }
+
+    (!attrs.is_empty()).then(|| format!("<protection{attrs}/>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::style::{BorderSide, Fill};
+
+    #[test]
+    fn builtin_formats() {
+        let mut table = StyleTable::default();
+
+        assert_eq!(table.cell_xf(None, None, "General"), 0);
+        assert_eq!(table.cell_xf(None, None, "mm-dd-yy"), 1);
+        assert_eq!(table.cell_xf(None, None, "mm-dd-yy"), 1);
+        assert!(table.num_fmts.is_empty());
+    }
+
+    #[test]
+    fn date_formats() {
+        assert!(is_date_format("mm-dd-yy"));
+        assert!(is_date_format("dd.mm.yyyy"));
+        assert!(is_date_format("[h]:mm:ss"));
+        assert!(!is_date_format("General"));
+        assert!(!is_date_format("# ##0,00"));
+        assert!(!is_date_format("[Red]0.00"));
+        assert!(!is_date_format("0.0\" дн.\""));
+    }
+
+    #[test]
+    fn custom_formats() {
+        let mut table = StyleTable::default();
+
+        assert_eq!(table.cell_xf(None, None, "# ##0,00"), 1);
+        assert_eq!(table.num_fmts, vec![(164, "# ##0,00".to_string())]);
+        assert!(table.to_xml().contains(r##"formatCode="# ##0,00""##));
+    }
+
+    #[test]
+    fn number_format_priority() {
+        let percent = Style::inline(Format {
+            number_format: Some("0.0%".to_string()),
+            ..Default::default()
+        });
+        let mut table = StyleTable::default();
+
+        let by_style = table.cell_xf(Some(&percent), None, "mm-dd-yy");
+        let by_cell = table.cell_xf(Some(&percent), Some("# ##0,00"), "mm-dd-yy");
+        let by_type = table.cell_xf(None, None, "mm-dd-yy");
+
+        assert_eq!(table.xfs[by_style].num_fmt_id, 164);
+        assert_eq!(table.xfs[by_cell].num_fmt_id, 165);
+        assert_eq!(table.xfs[by_type].num_fmt_id, 14);
+    }
+
+    #[test]
+    fn registered_styles() {
+        let header = Format {
+            font: Some(Font {
+                bold: true,
+                ..Default::default()
+            }),
+            fill: Some(Fill {
+                color: "DDEBF7".parse().unwrap(),
+            }),
+            border: Some(Border::all(BorderSide::default())),
+            wrap: true,
+            ..Default::default()
+        };
+        let registry = BTreeMap::from([("header".to_string(), header.clone())]);
+        let mut table = StyleTable::new(&registry);
+
+        let named = table.cell_xf(Some(&Style::new("header")), None, "General");
+        assert_eq!(named, 1);
+        assert_eq!(
+            table.cell_xf(Some(&Style::inline(header)), None, "General"),
+            named
+        );
+        assert_eq!(
+            table.cell_xf(Some(&Style::new("unknown")), None, "General"),
+            0
+        );
+        assert_eq!(table.cell_xf(None, None, "General"), 0);
+
+        let xml = table.to_xml();
+        assert!(xml.contains(r#"<fonts count="2">"#));
+        assert!(xml.contains(r#"<fgColor rgb="FFDDEBF7"/>"#));
+        assert!(xml.contains(r#"<left style="thin"><color auto="1"/></left>"#));
+        assert!(xml.contains(r#"applyAlignment="1"><alignment wrapText="1"/></xf>"#));
+    }
+}
@@ -0,0 +1,1760 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{Seek, Write},
+};
+
+use ahash::{HashMap, HashSet};
+use anyhow::{Result, bail};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use super::{
+    XML_HEADER, escape, range_ref,
+    styles::{StyleTable, run_properties_xml},
+};
+use crate::{
+    datatype::CellRawValue,
+    formula::{FormulaError, shift::sheet_prefix},
+    structs::{
+        book::Book,
+        cell::Cell,
+        conditional_format::{CellIsOperator, ConditionalRule, ThresholdKind},
+        data_validation::{ErrorStyle, ValidationRule, list_formula},
+        defined_name::DefinedName,
+        page_setup::{Orientation, PageSetup},
+        protection::{SheetProtection, WorkbookProtection},
+        range::Range,
+        rich_text::RichText,
+        sheet::{Sheet, validate_sheet_name},
+        sheet_view::SheetView,
+    },
+    traits::{ReadableCell, ReadableSheet},
+    utils::{
+        _get_number_format_by_datatype, datetime_to_excel_serial, get_letter_coordinate,
+        index_to_alpha,
+    },
+};
+
+const NS_MAIN: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const NS_REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_PKG_REL: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+const REL_WORKSHEET: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet";
+const REL_STYLES: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles";
+const REL_SHARED_STRINGS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings";
+const REL_COMMENTS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments";
+const REL_HYPERLINK: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+const REL_VML_DRAWING: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/vmlDrawing";
+const REL_OFFICE_DOCUMENT: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument";
+
+const CT_WORKBOOK: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml";
+const CT_WORKSHEET: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml";
+const CT_STYLES: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml";
+const CT_SHARED_STRINGS: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml";
+const CT_COMMENTS: &str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml";
+
+/// Таблица общих строк книги (xl/sharedStrings.xml).
+#[derive(Debug, Default)]
+struct SharedStrings {
+    /// Содержимое элементов `si`
+    items: Vec<String>,
+    index: HashMap<String, usize>,
+    count: usize,
+}
+
+impl SharedStrings {
+    fn add(&mut self, value: &str) -> usize {
+        let mut xml = String::new();
+        push_text(&mut xml, value);
+
+        self.add_item(xml)
+    }
+
+    fn add_rich_text(&mut self, value: &RichText) -> usize {
+        let mut xml = String::new();
+        for run in value.get_runs() {
+            xml.push_str("<r>");
+            if let Some(font) = &run.font {
+                xml.push_str(&run_properties_xml(font));
+            }
+            push_text(&mut xml, &run.text);
+            xml.push_str("</r>");
+        }
+
+        self.add_item(xml)
+    }
+
+    fn add_item(&mut self, xml: String) -> usize {
+        self.count += 1;
+
+        if let Some(idx) = self.index.get(&xml) {
+            return *idx;
+        }
+
+        let idx = self.items.len();
+        self.index.insert(xml.clone(), idx);
+        self.items.push(xml);
+
+        idx
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = String::from(XML_HEADER);
+        let _ = write!(
+            xml,
+            r#"<sst xmlns="{NS_MAIN}" count="{}" uniqueCount="{}">"#,
+            self.count,
+            self.items.len()
+        );
+
+        for item in &self.items {
+            let _ = write!(xml, "<si>{item}</si>");
+        }
+        xml.push_str("</sst>");
+
+        xml
+    }
+}
+
+/// Части пакета, относящиеся к одному листу.
+#[derive(Debug, Default)]
+struct SheetPart {
+    xml: String,
+    rels: Vec<(String, &'static str, String)>,
+    comments: Option<(String, String)>,
+}
+
+/// Запись книги в формате XLSX.
+pub(crate) fn write_book<W: Write + Seek>(book: &Book, writer: W) -> Result<()> {
+    let sheets = book.get_sheet_collection();
+    if sheets.is_empty() {
+        bail!("Failed to write XLSX: book has no sheets");
+    }
+
+    let guards: Vec<_> = sheets.iter().map(|s| s.read()).collect();
+    validate_sheet_names(guards.iter().map(|s| s.get_name()))?;
+
+    let mut shared_strings = SharedStrings::default();
+    let mut styles = StyleTable::new(book.get_styles());
+
+    let parts: Vec<SheetPart> = guards
+        .iter()
+        .enumerate()
+        .map(|(idx, sheet)| write_sheet(sheet, idx + 1, &mut shared_strings, &mut styles))
+        .collect();
+
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let add_part = |zip: &mut ZipWriter<W>, name: &str, data: &str| -> Result<()> {
+        zip.start_file(name, options)?;
+        zip.write_all(data.as_bytes())?;
+
+        Ok(())
+    };
+
+    add_part(&mut zip, "[Content_Types].xml", &content_types_xml(&parts))?;
+    add_part(&mut zip, "_rels/.rels", &root_rels_xml())?;
+    add_part(
+        &mut zip,
+        "xl/workbook.xml",
+        &workbook_xml(&guards, book.get_defined_names(), book.get_protection())?,
+    )?;
+    add_part(
+        &mut zip,
+        "xl/_rels/workbook.xml.rels",
+        &workbook_rels_xml(guards.len()),
+    )?;
+    add_part(&mut zip, "xl/styles.xml", &styles.to_xml())?;
+    add_part(&mut zip, "xl/sharedStrings.xml", &shared_strings.to_xml())?;
+
+    for (idx, part) in parts.iter().enumerate() {
+        let num = idx + 1;
+        add_part(
+            &mut zip,
+            &format!("xl/worksheets/sheet{num}.xml"),
+            &part.xml,
+        )?;
+
+        if !part.rels.is_empty() {
+            add_part(
+                &mut zip,
+                &format!("xl/worksheets/_rels/sheet{num}.xml.rels"),
+                &rels_xml(&part.rels),
+            )?;
+        }
+
+        if let Some((comments, vml)) = &part.comments {
+            add_part(&mut zip, &format!("xl/comments{num}.xml"), comments)?;
+            add_part(&mut zip, &format!("xl/drawings/vmlDrawing{num}.vml"), vml)?;
+        }
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
+
+/// Проверка имен листов на допустимость в Excel.
+fn validate_sheet_names<I: Iterator<Item = String>>(names: I) -> Result<()> {
+    let mut seen = HashSet::default();
+
+    for name in names {
+        validate_sheet_name(&name)?;
+        if !seen.insert(name.to_lowercase()) {
+            bail!("Duplicate sheet name '{name}'");
+        }
+    }
+
+    Ok(())
+}
+
+/// Формирование части листа.
+fn write_sheet(
+    sheet: &Sheet,
+    num: usize,
+    shared_strings: &mut SharedStrings,
+    styles: &mut StyleTable<'_>,
+) -> SheetPart {
+    let mut part = SheetPart::default();
+    let xml = &mut part.xml;
+
+    xml.push_str(XML_HEADER);
+    let _ = write!(xml, r#"<worksheet xmlns="{NS_MAIN}" xmlns:r="{NS_REL}">"#);
+
+    let view = sheet.get_sheet_view();
+    let setup = sheet.get_page_setup();
+
+    write_sheet_pr(xml, sheet);
+
+    let max_row = sheet.get_max_row();
+    let max_col = sheet.get_max_column();
+    if max_row > 0 && max_col > 0 {
+        let _ = write!(
+            xml,
+            r#"<dimension ref="A1:{}"/>"#,
+            get_letter_coordinate(max_row, max_col)
+        );
+    } else {
+        xml.push_str(r#"<dimension ref="A1"/>"#);
+    }
+
+    write_sheet_view(xml, view);
+
+    let rows = sheet.get_row_dimensions();
+    let columns = sheet.get_column_dimensions();
+
+    let _ = write!(
+        xml,
+        r#"<sheetFormatPr defaultRowHeight="{}" customHeight="1" defaultColWidth="{}""#,
+        rows.get_default_height(),
+        columns.get_default_width()
+    );
+    if rows.get_max_level() > 0 {
+        let _ = write!(xml, r#" outlineLevelRow="{}""#, rows.get_max_level());
+    }
+    if columns.get_max_level() > 0 {
+        let _ = write!(xml, r#" outlineLevelCol="{}""#, columns.get_max_level());
+    }
+    xml.push_str("/>");
+
+    write_cols(xml, sheet);
+    write_sheet_data(xml, sheet, shared_strings, styles);
+
+    if let Some(protection) = sheet.get_protection() {
+        write_sheet_protection(xml, protection);
+    }
+
+    if let Some(range) = sheet.get_auto_filter() {
+        let _ = write!(xml, r#"<autoFilter ref="{}"/>"#, range_ref(range));
+    }
+
+    let merges = sheet.get_merge_cell_collection();
+    if !merges.is_empty() {
+        let _ = write!(xml, r#"<mergeCells count="{}">"#, merges.len());
+        for range in merges {
+            let _ = write!(xml, r#"<mergeCell ref="{}"/>"#, range_ref(range));
+        }
+        xml.push_str("</mergeCells>");
+    }
+
+    write_conditional_formats(xml, sheet, styles);
+    write_data_validations(xml, sheet);
+    write_hyperlinks(xml, sheet, &mut part.rels);
+
+    write_page_setup(xml, setup);
+
+    if let Some((comments, vml)) = comments_xml(sheet, num) {
+        let comments_id = format!("rId{}", part.rels.len() + 1);
+        part.rels
+            .push((comments_id, REL_COMMENTS, format!("../comments{num}.xml")));
+
+        let vml_id = format!("rId{}", part.rels.len() + 1);
+        let _ = write!(part.xml, r#"<legacyDrawing r:id="{vml_id}"/>"#);
+        part.rels.push((
+            vml_id,
+            REL_VML_DRAWING,
+            format!("../drawings/vmlDrawing{num}.vml"),
+        ));
+
+        part.comments = Some((comments, vml));
+    }
+
+    part.xml.push_str("</worksheet>");
+
+    part
+}
+
+/// Формирование защиты листа. В XML флаги означают запрет действия,
+/// поэтому разрешенные действия записываются со значением `0`.
+fn write_sheet_protection(xml: &mut String, protection: &SheetProtection) {
+    xml.push_str(r#"<sheetProtection"#);
+    if let Some(hash) = &protection.password_hash {
+        let _ = write!(xml, r#" password="{hash}""#);
+    }
+    xml.push_str(r#" sheet="1""#);
+
+    // Запрещены по умолчанию: записываются только разрешенные
+    let denied = [
+        ("formatCells", protection.format_cells),
+        ("formatColumns", protection.format_columns),
+        ("formatRows", protection.format_rows),
+        ("insertColumns", protection.insert_columns),
+        ("insertRows", protection.insert_rows),
+        ("insertHyperlinks", protection.insert_hyperlinks),
+        ("deleteColumns", protection.delete_columns),
+        ("deleteRows", protection.delete_rows),
+        ("sort", protection.sort),
+        ("autoFilter", protection.auto_filter),
+        ("pivotTables", protection.pivot_tables),
+    ];
+    for (name, _) in denied.iter().filter(|(_, allowed)| *allowed) {
+        let _ = write!(xml, r#" {name}="0""#);
+    }
+
+    // Разрешены по умолчанию: записываются только запрещенные
+    let allowed = [
+        ("objects", protection.objects),
+        ("scenarios", protection.scenarios),
+        ("selectLockedCells", protection.select_locked_cells),
+        ("selectUnlockedCells", protection.select_unlocked_cells),
+    ];
+    for (name, _) in allowed.iter().filter(|(_, allowed)| !*allowed) {
+        let _ = write!(xml, r#" {name}="1""#);
+    }
+    xml.push_str("/>");
+}
+
+/// Формирование свойств листа: цвет ярлыка и размещение на страницах.
+fn write_sheet_pr(xml: &mut String, sheet: &Sheet) {
+    let mut pr = String::new();
+
+    if let Some(color) = &sheet.get_sheet_view().tab_color {
+        let _ = write!(pr, r#"<tabColor rgb="{}"/>"#, color.get_argb());
+    }
+    if !sheet.get_summary_below() || !sheet.get_summary_right() {
+        pr.push_str("<outlinePr");
+        if !sheet.get_summary_below() {
+            pr.push_str(r#" summaryBelow="0""#);
+        }
+        if !sheet.get_summary_right() {
+            pr.push_str(r#" summaryRight="0""#);
+        }
+        pr.push_str("/>");
+    }
+    if sheet.get_page_setup().fit_to_page.is_some() {
+        pr.push_str(r#"<pageSetUpPr fitToPage="1"/>"#);
+    }
+
+    if !pr.is_empty() {
+        let _ = write!(xml, "<sheetPr>{pr}</sheetPr>");
+    }
+}
+
+/// Формирование гиперссылок листа. Внешние адреса добавляются в связи листа.
+fn write_hyperlinks(
+    xml: &mut String,
+    sheet: &Sheet,
+    rels: &mut Vec<(String, &'static str, String)>,
+) {
+    let links: Vec<_> = sheet
+        .get_cell_collection_sorted()
+        .into_iter()
+        .filter_map(|cell| {
+            let cell = cell.read();
+            cell.get_hyperlink().map(|link| (cell.get_letter(), link))
+        })
+        .collect();
+
+    if links.is_empty() {
+        return;
+    }
+
+    xml.push_str("<hyperlinks>");
+    for (cell_ref, link) in links {
+        let _ = write!(xml, r#"<hyperlink ref="{cell_ref}""#);
+
+        match link.get_location() {
+            Some(location) => {
+                let _ = write!(xml, r#" location="{}""#, escape(location));
+            }
+            None => {
+                let id = format!("rId{}", rels.len() + 1);
+                let _ = write!(xml, r#" r:id="{id}""#);
+                rels.push((id, REL_HYPERLINK, link.get_target().to_string()));
+            }
+        }
+
+        if let Some(tooltip) = link.get_tooltip() {
+            let _ = write!(xml, r#" tooltip="{}""#, escape(tooltip));
+        }
+        xml.push_str("/>");
+    }
+    xml.push_str("</hyperlinks>");
+}
+
+/// Формирование полей, параметров страницы и колонтитулов.
+fn write_page_setup(xml: &mut String, setup: &PageSetup) {
+    let m = &setup.margins;
+    let _ = write!(
+        xml,
+        r#"<pageMargins left="{}" right="{}" top="{}" bottom="{}" header="{}" footer="{}"/>"#,
+        m.left, m.right, m.top, m.bottom, m.header, m.footer
+    );
+
+    let mut attrs = String::new();
+    if let Some(paper_size) = setup.paper_size {
+        let _ = write!(attrs, r#" paperSize="{paper_size}""#);
+    }
+    if let Some(scale) = setup.scale {
+        let _ = write!(attrs, r#" scale="{scale}""#);
+    }
+    if let Some(fit) = &setup.fit_to_page {
+        let _ = write!(
+            attrs,
+            r#" fitToWidth="{}" fitToHeight="{}""#,
+            fit.width, fit.height
+        );
+    }
+    if setup.orientation != Orientation::Portrait {
+        let _ = write!(attrs, r#" orientation="{}""#, setup.orientation);
+    }
+    if !attrs.is_empty() {
+        let _ = write!(xml, "<pageSetup{attrs}/>");
+    }
+
+    if setup.header.is_some() || setup.footer.is_some() {
+        xml.push_str("<headerFooter>");
+        if let Some(header) = &setup.header {
+            let _ = write!(xml, "<oddHeader>{}</oddHeader>", escape(header));
+        }
+        if let Some(footer) = &setup.footer {
+            let _ = write!(xml, "<oddFooter>{}</oddFooter>", escape(footer));
+        }
+        xml.push_str("</headerFooter>");
+    }
+}
+
+/// Формирование настроек отображения листа.
+fn write_sheet_view(xml: &mut String, view: &SheetView) {
+    // Цвет ярлыка записывается в sheetPr
+    let without_tab = SheetView {
+        tab_color: None,
+        ..view.clone()
+    };
+    if without_tab.is_default() {
+        return;
+    }
+
+    xml.push_str("<sheetViews><sheetView");
+    if !view.show_grid_lines {
+        xml.push_str(r#" showGridLines="0""#);
+    }
+    if view.right_to_left {
+        xml.push_str(r#" rightToLeft="1""#);
+    }
+    if view.zoom != 100 {
+        let _ = write!(xml, r#" zoomScale="{0}" zoomScaleNormal="{0}""#, view.zoom);
+    }
+    xml.push_str(r#" workbookViewId="0">"#);
+
+    let (x, y, state) = match &view.split {
+        _ if view.is_frozen() => (view.frozen_cols as f64, view.frozen_rows as f64, "frozen"),
+        Some(split) => (split.x, split.y, "split"),
+        None => (0.0, 0.0, ""),
+    };
+
+    let pane = match (x > 0.0, y > 0.0) {
+        (true, true) => Some("bottomRight"),
+        (false, true) => Some("bottomLeft"),
+        (true, false) => Some("topRight"),
+        (false, false) => None,
+    };
+
+    if let Some(pane) = pane {
+        xml.push_str("<pane");
+        if x > 0.0 {
+            let _ = write!(xml, r#" xSplit="{x}""#);
+        }
+        if y > 0.0 {
+            let _ = write!(xml, r#" ySplit="{y}""#);
+        }
+        if state == "frozen" {
+            let _ = write!(
+                xml,
+                r#" topLeftCell="{}""#,
+                get_letter_coordinate(view.frozen_rows + 1, view.frozen_cols + 1)
+            );
+        }
+        let _ = write!(xml, r#" activePane="{pane}" state="{state}"/>"#);
+    }
+
+    if let Some(cell) = &view.selected_cell {
+        let cell = get_letter_coordinate(cell.row, cell.column);
+        xml.push_str("<selection");
+        if let Some(pane) = pane {
+            let _ = write!(xml, r#" pane="{pane}""#);
+        }
+        let _ = write!(xml, r#" activeCell="{cell}" sqref="{cell}"/>"#);
+    }
+
+    xml.push_str("</sheetView></sheetViews>");
+}
+
+/// Формирование размеров колонок.
+fn write_cols(xml: &mut String, sheet: &Sheet) {
+    let columns = sheet.get_column_dimensions();
+    let default_width = columns.get_default_width();
+    let collection = columns.get_collection_sorted();
+
+    if collection.is_empty() {
+        return;
+    }
+
+    xml.push_str("<cols>");
+    for column in collection {
+        let num = column.get_col_num();
+        let _ = write!(
+            xml,
+            r#"<col min="{num}" max="{num}" width="{}""#,
+            column.get_width()
+        );
+        if column.get_width() != default_width {
+            xml.push_str(r#" customWidth="1""#);
+        }
+        if *column.get_hidden() {
+            xml.push_str(r#" hidden="1""#);
+        }
+        if *column.get_level() > 0 {
+            let _ = write!(xml, r#" outlineLevel="{}""#, column.get_level());
+        }
+        if *column.get_collapsed() {
+            xml.push_str(r#" collapsed="1""#);
+        }
+        xml.push_str("/>");
+    }
+    xml.push_str("</cols>");
+}
+
+/// Формирование условного форматирования листа.
+fn write_conditional_formats(xml: &mut String, sheet: &Sheet, styles: &mut StyleTable<'_>) {
+    // Правила без приоритета нумеруются после заданных явно
+    let mut next_priority = sheet
+        .get_conditional_formats()
+        .iter()
+        .filter_map(|cf| cf.priority)
+        .max()
+        .unwrap_or(0);
+
+    for cf in sheet.get_conditional_formats() {
+        if cf.ranges.is_empty() {
+            continue;
+        }
+
+        let sqref: Vec<String> = cf.ranges.iter().map(range_ref).collect();
+        let _ = write!(
+            xml,
+            r#"<conditionalFormatting sqref="{}">"#,
+            sqref.join(" ")
+        );
+
+        let priority = cf.priority.unwrap_or_else(|| {
+            next_priority += 1;
+            next_priority
+        });
+        let kind = match &cf.rule {
+            ConditionalRule::CellIs { .. } => "cellIs",
+            ConditionalRule::Expression { .. } => "expression",
+            ConditionalRule::ColorScale { .. } => "colorScale",
+            ConditionalRule::DataBar { .. } => "dataBar",
+            ConditionalRule::DuplicateValues { unique: false } => "duplicateValues",
+            ConditionalRule::DuplicateValues { unique: true } => "uniqueValues",
+            ConditionalRule::Top { .. } => "top10",
+        };
+        let _ = write!(xml, r#"<cfRule type="{kind}""#);
+
+        if cf.rule.has_style()
+            && let Some(dxf_id) = cf.style.as_ref().and_then(|s| styles.dxf(s))
+        {
+            let _ = write!(xml, r#" dxfId="{dxf_id}""#);
+        }
+        let _ = write!(xml, r#" priority="{priority}""#);
+        if cf.stop_if_true {
+            xml.push_str(r#" stopIfTrue="1""#);
+        }
+
+        let formula = |f: &str| format!("<formula>{}</formula>", escape(f.trim_start_matches('=')));
+        let cfvo = |kind: &ThresholdKind, value: Option<&String>| match value {
+            Some(v) => format!(r#"<cfvo type="{kind}" val="{}"/>"#, escape(v)),
+            None => format!(r#"<cfvo type="{kind}"/>"#),
+        };
+
+        match &cf.rule {
+            ConditionalRule::CellIs {
+                operator,
+                formula: f1,
+                formula2,
+            } => {
+                let _ = write!(xml, r#" operator="{operator}">{}"#, formula(f1));
+                if let Some(f2) = formula2 {
+                    xml.push_str(&formula(f2));
+                }
+                xml.push_str("</cfRule>");
+            }
+            ConditionalRule::Expression { formula: f } => {
+                let _ = write!(xml, ">{}</cfRule>", formula(f));
+            }
+            ConditionalRule::ColorScale { min, mid, max } => {
+                let stops: Vec<_> = [Some(min), mid.as_ref(), Some(max)]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+                xml.push_str("><colorScale>");
+                for stop in &stops {
+                    xml.push_str(&cfvo(&stop.kind, stop.value.as_ref()));
+                }
+                for stop in &stops {
+                    let _ = write!(xml, r#"<color rgb="{}"/>"#, stop.color.get_argb());
+                }
+                xml.push_str("</colorScale></cfRule>");
+            }
+            ConditionalRule::DataBar { color, min, max } => {
+                let _ = write!(
+                    xml,
+                    r#"><dataBar>{}{}<color rgb="{}"/></dataBar></cfRule>"#,
+                    cfvo(&min.kind, min.value.as_ref()),
+                    cfvo(&max.kind, max.value.as_ref()),
+                    color.get_argb()
+                );
+            }
+            ConditionalRule::DuplicateValues { .. } => xml.push_str("/>"),
+            ConditionalRule::Top {
+                rank,
+                bottom,
+                percent,
+            } => {
+                let _ = write!(xml, r#" rank="{rank}""#);
+                if *bottom {
+                    xml.push_str(r#" bottom="1""#);
+                }
+                if *percent {
+                    xml.push_str(r#" percent="1""#);
+                }
+                xml.push_str("/>");
+            }
+        }
+
+        xml.push_str("</conditionalFormatting>");
+    }
+}
+
+/// Формирование проверок данных листа.
+fn write_data_validations(xml: &mut String, sheet: &Sheet) {
+    let validations = sheet.get_data_validations();
+    if validations.is_empty() {
+        return;
+    }
+
+    let _ = write!(xml, r#"<dataValidations count="{}">"#, validations.len());
+    for dv in validations {
+        let (kind, constraint) = match &dv.rule {
+            ValidationRule::List { .. } | ValidationRule::ListRange { .. } => ("list", None),
+            ValidationRule::Whole(c) => ("whole", Some(c)),
+            ValidationRule::Decimal(c) => ("decimal", Some(c)),
+            ValidationRule::Date(c) => ("date", Some(c)),
+            ValidationRule::TextLength(c) => ("textLength", Some(c)),
+            ValidationRule::Custom { .. } => ("custom", None),
+        };
+
+        let _ = write!(xml, r#"<dataValidation type="{kind}""#);
+        if let Some(c) = constraint
+            && c.operator != CellIsOperator::Between
+        {
+            let _ = write!(xml, r#" operator="{}""#, c.operator);
+        }
+        if dv.error_style != ErrorStyle::Stop {
+            let _ = write!(xml, r#" errorStyle="{}""#, dv.error_style);
+        }
+        if dv.allow_blank {
+            xml.push_str(r#" allowBlank="1""#);
+        }
+        if dv.input_title.is_some() || dv.input_message.is_some() {
+            xml.push_str(r#" showInputMessage="1""#);
+        }
+        xml.push_str(r#" showErrorMessage="1""#);
+
+        for (attr, value) in [
+            ("errorTitle", &dv.error_title),
+            ("error", &dv.error_message),
+            ("promptTitle", &dv.input_title),
+            ("prompt", &dv.input_message),
+        ] {
+            if let Some(value) = value {
+                let _ = write!(xml, r#" {attr}="{}""#, escape(value));
+            }
+        }
+
+        let sqref: Vec<String> = dv.ranges.iter().map(range_ref).collect();
+        let _ = write!(xml, r#" sqref="{}">"#, sqref.join(" "));
+
+        let formula = |f: &str| escape(f.trim_start_matches('=')).into_owned();
+        match &dv.rule {
+            ValidationRule::List { values } => {
+                let _ = write!(
+                    xml,
+                    "<formula1>{}</formula1>",
+                    escape(&list_formula(values))
+                );
+            }
+            ValidationRule::ListRange { range: f } | ValidationRule::Custom { formula: f } => {
+                let _ = write!(xml, "<formula1>{}</formula1>", formula(f));
+            }
+            ValidationRule::Whole(c)
+            | ValidationRule::Decimal(c)
+            | ValidationRule::Date(c)
+            | ValidationRule::TextLength(c) => {
+                let _ = write!(xml, "<formula1>{}</formula1>", formula(&c.formula));
+                if let Some(f2) = &c.formula2 {
+                    let _ = write!(xml, "<formula2>{}</formula2>", formula(f2));
+                }
+            }
+        }
+        xml.push_str("</dataValidation>");
+    }
+    xml.push_str("</dataValidations>");
+}
+
+/// Формирование данных листа (строки и ячейки).
+fn write_sheet_data(
+    xml: &mut String,
+    sheet: &Sheet,
+    shared_strings: &mut SharedStrings,
+    styles: &mut StyleTable<'_>,
+) {
+    let rows = sheet.get_row_dimensions();
+    let default_height = rows.get_default_height();
+
+    // Строки с ячейками и строки только с размерами
+    let mut row_map: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for row in rows.get_collection_sorted() {
+        row_map.entry(row.get_row_num()).or_default();
+    }
+
+    for cell in sheet.get_cell_collection_sorted() {
+        let guard = cell.read();
+        let row = guard.get_coordinate().row;
+        let c = cell_xml(&guard, shared_strings, styles);
+
+        row_map.entry(row).or_default().push(c);
+    }
+
+    if row_map.is_empty() {
+        xml.push_str("<sheetData/>");
+        return;
+    }
+
+    xml.push_str("<sheetData>");
+    for (row_num, cells) in row_map {
+        let _ = write!(xml, r#"<row r="{row_num}""#);
+
+        let height = sheet.get_height_by_row(row_num);
+        if height != default_height {
+            let _ = write!(xml, r#" ht="{height}" customHeight="1""#);
+        }
+        if *sheet.get_hidden_by_row(row_num) {
+            xml.push_str(r#" hidden="1""#);
+        }
+        let level = sheet.get_level_by_row(row_num);
+        if *level > 0 {
+            let _ = write!(xml, r#" outlineLevel="{level}""#);
+        }
+        if *sheet.get_collapsed_by_row(row_num) {
+            xml.push_str(r#" collapsed="1""#);
+        }
+
+        if cells.is_empty() {
+            xml.push_str("/>");
+        } else {
+            xml.push('>');
+            for c in cells {
+                xml.push_str(&c);
+            }
+            xml.push_str("</row>");
+        }
+    }
+    xml.push_str("</sheetData>");
+}
+
+/// Формирование XML одной ячейки.
+fn cell_xml(cell: &Cell, shared_strings: &mut SharedStrings, styles: &mut StyleTable) -> String {
+    let mut xml = String::new();
+    let raw_value = cell.get_raw_value();
+
+    let _ = write!(xml, r#"<c r="{}""#, cell.get_letter());
+
+    let number_format = _get_number_format_by_datatype(raw_value.get_date_type());
+    let xf = styles.cell_xf(
+        cell.style(),
+        cell.get_number_format().as_deref(),
+        &number_format,
+    );
+    if xf != 0 {
+        let _ = write!(xml, r#" s="{xf}""#);
+    }
+
+    let formula = cell
+        .get_formula()
+        .filter(|_| cell.is_formula())
+        .map(|f| f.strip_prefix('=').map(str::to_string).unwrap_or(f));
+
+    match (formula, raw_value) {
+        (Some(f), value) => {
+            match value {
+                CellRawValue::String(s) if s.parse::<FormulaError>().is_ok() => {
+                    xml.push_str(r#" t="e""#)
+                }
+                CellRawValue::String(_) | CellRawValue::RichText(_) => xml.push_str(r#" t="str""#),
+                CellRawValue::Bool(_) => xml.push_str(r#" t="b""#),
+                _ => {}
+            }
+            let _ = write!(xml, "><f>{}</f>", escape(&f));
+            if let Some(v) = value_xml(value) {
+                let _ = write!(xml, "<v>{}</v>", escape(&v));
+            }
+            xml.push_str("</c>");
+        }
+        (None, CellRawValue::Empty) => xml.push_str("/>"),
+        (None, CellRawValue::String(s)) => {
+            let idx = shared_strings.add(s);
+            let _ = write!(xml, r#" t="s"><v>{idx}</v></c>"#);
+        }
+        (None, CellRawValue::RichText(r)) => {
+            let idx = shared_strings.add_rich_text(r);
+            let _ = write!(xml, r#" t="s"><v>{idx}</v></c>"#);
+        }
+        (None, CellRawValue::Bool(b)) => {
+            let _ = write!(xml, r#" t="b"><v>{}</v></c>"#, *b as u8);
+        }
+        (None, CellRawValue::Numeric(n)) if !n.is_finite() => {
+            xml.push_str(r#" t="e"><v>#NUM!</v></c>"#);
+        }
+        (None, value) => {
+            let v = value_xml(value).unwrap_or_default();
+            let _ = write!(xml, "><v>{v}</v></c>");
+        }
+    }
+
+    xml
+}
+
+/// Значение ячейки в представлении XLSX.
+fn value_xml(value: &CellRawValue) -> Option<String> {
+    match value {
+        CellRawValue::Empty => None,
+        CellRawValue::String(s) => Some(s.to_string()),
+        CellRawValue::RichText(r) => Some(r.get_text()),
+        CellRawValue::Numeric(n) if n.is_finite() => Some(n.to_string()),
+        CellRawValue::Numeric(_) => None,
+        CellRawValue::Integer(i) => Some(i.to_string()),
+        CellRawValue::Bool(b) => Some((*b as u8).to_string()),
+        CellRawValue::Datetime(dt) => Some(datetime_to_excel_serial(dt).to_string()),
+    }
+}
+
+/// Добавление текстового узла с сохранением пробелов.
+fn push_text(xml: &mut String, value: &str) {
+    if value.starts_with(char::is_whitespace) || value.ends_with(char::is_whitespace) {
+        let _ = write!(xml, r#"<t xml:space="preserve">{}</t>"#, escape(value));
+    } else {
+        let _ = write!(xml, "<t>{}</t>", escape(value));
+    }
+}
+
+/// Формирование комментариев листа и VML-разметки для их отображения.
+fn comments_xml(sheet: &Sheet, num: usize) -> Option<(String, String)> {
+    let comments = sheet.get_comments();
+    if comments.is_empty() {
+        return None;
+    }
+
+    // Одна ячейка - один комментарий, побеждает последний добавленный
+    let mut by_coord = BTreeMap::new();
+    for comment in comments {
+        let guard = comment.read();
+        let coord = guard.get_coordinate();
+
+        by_coord.insert(
+            (coord.row, coord.column),
+            (guard.get_author().to_string(), guard.get_text().to_string()),
+        );
+    }
+
+    let mut authors: Vec<String> = Vec::new();
+    let mut list = String::new();
+    for ((row, col), (author, text)) in &by_coord {
+        let author_id = match authors.iter().position(|a| a == author) {
+            Some(idx) => idx,
+            None => {
+                authors.push(author.clone());
+                authors.len() - 1
+            }
+        };
+
+        let _ = write!(
+            list,
+            r#"<comment ref="{}" authorId="{author_id}"><text>"#,
+            get_letter_coordinate(*row, *col)
+        );
+        push_text(&mut list, text);
+        list.push_str("</text></comment>");
+    }
+
+    let mut xml = String::from(XML_HEADER);
+    let _ = write!(xml, r#"<comments xmlns="{NS_MAIN}"><authors>"#);
+    for author in &authors {
+        let _ = write!(xml, "<author>{}</author>", escape(author));
+    }
+    let _ = write!(
+        xml,
+        "</authors><commentList>{list}</commentList></comments>"
+    );
+
+    let mut vml = String::from(concat!(
+        r#"<xml xmlns:v="urn:schemas-microsoft-com:vml" "#,
+        r#"xmlns:o="urn:schemas-microsoft-com:office:office" "#,
+        r#"xmlns:x="urn:schemas-microsoft-com:office:excel">"#,
+    ));
+    let _ = write!(
+        vml,
+        r#"<o:shapelayout v:ext="edit"><o:idmap v:ext="edit" data="{num}"/></o:shapelayout>"#
+    );
+    vml.push_str(concat!(
+        r#"<v:shapetype id="_x0000_t202" coordsize="21600,21600" o:spt="202" "#,
+        r#"path="m,l,21600r21600,l21600,xe"><v:stroke joinstyle="miter"/>"#,
+        r#"<v:path gradientshapeok="t" o:connecttype="rect"/></v:shapetype>"#,
+    ));
+
+    for (idx, (row, col)) in by_coord.keys().enumerate() {
+        let shape_id = 1024 * num + idx + 1;
+        let (row0, col0) = (row - 1, u32::from(*col) - 1);
+
+        let _ = write!(
+            vml,
+            concat!(
+                r##"<v:shape id="_x0000_s{}" type="#_x0000_t202" "##,
+                r#"style="position:absolute;margin-left:59.25pt;margin-top:1.5pt;width:108pt;height:59.25pt;z-index:{};visibility:hidden" "#,
+                r##"fillcolor="#ffffe1" o:insetmode="auto"><v:fill color2="#ffffe1"/>"##,
+                r#"<v:shadow on="t" color="black" obscured="t"/><v:path o:connecttype="none"/>"#,
+                r#"<v:textbox style="mso-direction-alt:auto"><div style="text-align:left"></div></v:textbox>"#,
+                r#"<x:ClientData ObjectType="Note"><x:MoveWithCells/><x:SizeWithCells/>"#,
+                r#"<x:Anchor>{}, 15, {}, 10, {}, 15, {}, 4</x:Anchor><x:AutoFill>False</x:AutoFill>"#,
+                r#"<x:Row>{}</x:Row><x:Column>{}</x:Column></x:ClientData></v:shape>"#,
+            ),
+            shape_id,
+            idx + 1,
+            col0 + 1,
+            row0.saturating_sub(1),
+            col0 + 3,
+            row0 + 3,
+            row0,
+            col0,
+        );
+    }
+    vml.push_str("</xml>");
+
+    Some((xml, vml))
+}
+
+/// Формирование [Content_Types].xml
+fn content_types_xml(parts: &[SheetPart]) -> String {
+    let mut xml = String::from(XML_HEADER);
+    xml.push_str(concat!(
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Default Extension="vml" ContentType="application/vnd.openxmlformats-officedocument.vmlDrawing"/>"#,
+    ));
+
+    let _ = write!(
+        xml,
+        r#"<Override PartName="/xl/workbook.xml" ContentType="{CT_WORKBOOK}"/>"#
+    );
+    let _ = write!(
+        xml,
+        r#"<Override PartName="/xl/styles.xml" ContentType="{CT_STYLES}"/>"#
+    );
+    let _ = write!(
+        xml,
+        r#"<Override PartName="/xl/sharedStrings.xml" ContentType="{CT_SHARED_STRINGS}"/>"#
+    );
+
+    for (idx, part) in parts.iter().enumerate() {
+        let num = idx + 1;
+        let _ = write!(
+            xml,
+            r#"<Override PartName="/xl/worksheets/sheet{num}.xml" ContentType="{CT_WORKSHEET}"/>"#
+        );
+        if part.comments.is_some() {
+            let _ = write!(
+                xml,
+                r#"<Override PartName="/xl/comments{num}.xml" ContentType="{CT_COMMENTS}"/>"#
+            );
+        }
+    }
+    xml.push_str("</Types>");
+
+    xml
+}
+
+/// Формирование _rels/.rels
+fn root_rels_xml() -> String {
+    rels_xml(&[(
+        "rId1".to_string(),
+        REL_OFFICE_DOCUMENT,
+        "xl/workbook.xml".to_string(),
+    )])
+}
+
+/// Служебные имена листа: автофильтр, область печати и сквозные строки/колонки.
+fn builtin_names(sheet: &Sheet) -> Vec<(&'static str, String)> {
+    let prefix = sheet_prefix(&sheet.get_name());
+    let mut names = Vec::new();
+
+    let range_ref = |range: &Range| {
+        format!(
+            "{prefix}!${}${}:${}${}",
+            index_to_alpha(range.start_col),
+            range.start_row,
+            index_to_alpha(range.end_col),
+            range.end_row
+        )
+    };
+
+    if let Some(range) = sheet.get_auto_filter() {
+        names.push(("_xlnm._FilterDatabase", range_ref(range)));
+    }
+
+    let setup = sheet.get_page_setup();
+    if let Some(range) = &setup.print_area {
+        names.push(("_xlnm.Print_Area", range_ref(range)));
+    }
+
+    let mut titles = Vec::new();
+    if let Some((start, end)) = setup.print_title_cols {
+        titles.push(format!(
+            "{prefix}!${}:${}",
+            index_to_alpha(start),
+            index_to_alpha(end)
+        ));
+    }
+    if let Some((start, end)) = setup.print_title_rows {
+        titles.push(format!("{prefix}!${start}:${end}"));
+    }
+    if !titles.is_empty() {
+        names.push(("_xlnm.Print_Titles", titles.join(",")));
+    }
+
+    names
+}
+
+/// Формирование xl/workbook.xml
+fn workbook_xml<S: std::ops::Deref<Target = Sheet>>(
+    sheets: &[S],
+    defined_names: &[DefinedName],
+    protection: Option<&WorkbookProtection>,
+) -> Result<String> {
+    let active_tab = sheets
+        .iter()
+        .position(|s| s.get_sheet_state() == "visible")
+        .unwrap_or_default();
+
+    let mut xml = String::from(XML_HEADER);
+    let _ = write!(xml, r#"<workbook xmlns="{NS_MAIN}" xmlns:r="{NS_REL}">"#);
+
+    if let Some(protection) = protection {
+        xml.push_str("<workbookProtection");
+        if let Some(hash) = &protection.password_hash {
+            let _ = write!(xml, r#" workbookPassword="{hash}""#);
+        }
+        if protection.lock_structure {
+            xml.push_str(r#" lockStructure="1""#);
+        }
+        if protection.lock_windows {
+            xml.push_str(r#" lockWindows="1""#);
+        }
+        xml.push_str("/>");
+    }
+
+    let _ = write!(
+        xml,
+        r#"<bookViews><workbookView activeTab="{active_tab}" firstSheet="{active_tab}"/></bookViews><sheets>"#
+    );
+
+    for (idx, sheet) in sheets.iter().enumerate() {
+        let num = idx + 1;
+        let _ = write!(
+            xml,
+            r#"<sheet name="{}" sheetId="{num}""#,
+            escape(&sheet.get_name())
+        );
+
+        match sheet.get_sheet_state().as_str() {
+            "hidden" => xml.push_str(r#" state="hidden""#),
+            "veryHidden" => xml.push_str(r#" state="veryHidden""#),
+            _ => {}
+        }
+        let _ = write!(xml, r#" r:id="rId{num}"/>"#);
+    }
+    xml.push_str("</sheets>");
+
+    let builtin: Vec<(usize, &str, String)> = sheets
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, sheet)| {
+            builtin_names(sheet)
+                .into_iter()
+                .map(move |(name, reference)| (idx, name, reference))
+        })
+        .collect();
+
+    if !defined_names.is_empty() || !builtin.is_empty() {
+        xml.push_str("<definedNames>");
+        for (idx, name, reference) in &builtin {
+            let _ = write!(xml, r#"<definedName name="{name}" localSheetId="{idx}""#);
+            if *name == "_xlnm._FilterDatabase" {
+                xml.push_str(r#" hidden="1""#);
+            }
+            let _ = write!(xml, ">{}</definedName>", escape(reference));
+        }
+        for name in defined_names {
+            let _ = write!(xml, r#"<definedName name="{}""#, escape(name.get_name()));
+            if let Some(scope) = name.get_scope() {
+                let lower = scope.to_lowercase();
+                let Some(idx) = sheets
+                    .iter()
+                    .position(|s| s.get_name().to_lowercase() == lower)
+                else {
+                    bail!(
+                        "Sheet '{scope}' of defined name '{}' not found",
+                        name.get_name()
+                    );
+                };
+                let _ = write!(xml, r#" localSheetId="{idx}""#);
+            }
+            let _ = write!(xml, ">{}</definedName>", escape(name.get_reference()));
+        }
+        xml.push_str("</definedNames>");
+    }
+
+    xml.push_str(r#"<calcPr calcId="191029" fullCalcOnLoad="1"/></workbook>"#);
+
+    Ok(xml)
+}
+
+/// Формирование xl/_rels/workbook.xml.rels
+fn workbook_rels_xml(sheets: usize) -> String {
+    let mut rels: Vec<(String, &'static str, String)> = (1..=sheets)
+        .map(|num| {
+            (
+                format!("rId{num}"),
+                REL_WORKSHEET,
+                format!("worksheets/sheet{num}.xml"),
+            )
+        })
+        .collect();
+
+    rels.push((
+        format!("rId{}", sheets + 1),
+        REL_STYLES,
+        "styles.xml".to_string(),
+    ));
+    rels.push((
+        format!("rId{}", sheets + 2),
+        REL_SHARED_STRINGS,
+        "sharedStrings.xml".to_string(),
+    ));
+
+    rels_xml(&rels)
+}
+
+/// Формирование файла связей
+fn rels_xml(rels: &[(String, &'static str, String)]) -> String {
+    let mut xml = String::from(XML_HEADER);
+    let _ = write!(xml, r#"<Relationships xmlns="{NS_PKG_REL}">"#);
+
+    for (id, rel_type, target) in rels {
+        let _ = write!(
+            xml,
+            r#"<Relationship Id="{id}" Type="{rel_type}" Target="{}""#,
+            escape(target)
+        );
+        if *rel_type == REL_HYPERLINK {
+            xml.push_str(r#" TargetMode="External""#);
+        }
+        xml.push_str("/>");
+    }
+    xml.push_str("</Relationships>");
+
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::{
+        structs::{
+            comment::Comment,
+            conditional_format::{ConditionalFormat, Threshold},
+            coordinate::Coordinate,
+            data_validation::{Constraint, DataValidation},
+            hyperlink::Hyperlink,
+            page_setup::FitToPage,
+            range::Range,
+            rich_text::TextRun,
+            style::{CellProtection, Font, Format},
+        },
+        traits::{WriteableCell, WriteableSheet},
+        xlsx::reader::{read_package, read_xlsx},
+    };
+
+    fn book() -> Book {
+        let mut book = Book::new();
+        let sheet = book.add_sheet("Отчет", "visible").unwrap();
+
+        {
+            let mut sheet = sheet.write();
+            sheet.cell(Coordinate::new(1, 1), Some("Заголовок"));
+            sheet.cell(Coordinate::new(2, 1), Some("42"));
+            sheet
+                .cell(Coordinate::new(2, 2), None)
+                .write()
+                .set_formula("=A2*2");
+            sheet.add_merge_range(Range::new(1, 1, 1, 3)).unwrap();
+            sheet.set_height_row(1, 30.0);
+            sheet.set_width_column(2, 40.0);
+            sheet.add_comments(Comment::new(Coordinate::new(1, 1), "A.C"));
+        }
+
+        book.add_sheet("Скрытый", "hidden").unwrap();
+
+        book
+    }
+
+    fn read_part(data: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut file = archive.by_name(name).unwrap();
+
+        let mut s = String::new();
+        file.read_to_string(&mut s).unwrap();
+
+        s
+    }
+
+    fn write(book: &Book) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        write_book(book, &mut buf).unwrap();
+
+        buf.into_inner()
+    }
+
+    #[test]
+    fn write_workbook() {
+        let data = write(&book());
+        let workbook = read_part(&data, "xl/workbook.xml");
+
+        assert!(workbook.contains(r#"<sheet name="Отчет" sheetId="1" r:id="rId1"/>"#));
+        assert!(workbook.contains(r#"<sheet name="Скрытый" sheetId="2" state="hidden""#));
+        assert!(!workbook.contains("<definedNames>"));
+    }
+
+    #[test]
+    fn write_defined_names() {
+        let mut book = book();
+        book.define_name("Итого", "'Отчет'!$A$2:$B$2", None)
+            .unwrap();
+        book.define_name("Ставка", "0.2", Some("Скрытый")).unwrap();
+
+        let data = write(&book);
+        let workbook = read_part(&data, "xl/workbook.xml");
+
+        assert!(workbook.contains(concat!(
+            "<definedNames>",
+            r#"<definedName name="Итого">&apos;Отчет&apos;!$A$2:$B$2</definedName>"#,
+            r#"<definedName name="Ставка" localSheetId="1">0.2</definedName>"#,
+            "</definedNames><calcPr"
+        )));
+
+        let (_, names) = read_package(Cursor::new(data)).unwrap();
+        assert_eq!(names, book.get_defined_names());
+    }
+
+    #[test]
+    fn write_defined_name_missing_scope() {
+        let mut book = book();
+        book.define_name("Ставка", "0.2", Some("Скрытый")).unwrap();
+        book.get_sheet_name("Скрытый")
+            .unwrap()
+            .write()
+            .set_name("Другой");
+
+        let mut buf = Cursor::new(Vec::new());
+        assert!(write_book(&book, &mut buf).is_err());
+    }
+
+    #[test]
+    fn write_sheet_cells() {
+        let data = write(&book());
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+        let strings = read_part(&data, "xl/sharedStrings.xml");
+
+        assert!(sheet.contains(r#"<c r="A1" t="s"><v>0</v></c>"#));
+        assert!(sheet.contains(r#"<c r="A2"><v>42</v></c>"#));
+        assert!(sheet.contains(r#"<c r="B2"><f>A2*2</f></c>"#));
+        assert!(sheet.contains(r#"<mergeCell ref="A1:C1"/>"#));
+        assert!(sheet.contains(r#"<row r="1" ht="30" customHeight="1">"#));
+        assert!(sheet.contains(r#"<col min="2" max="2" width="40" customWidth="1"/>"#));
+        assert!(strings.contains("<t>Заголовок</t>"));
+    }
+
+    #[test]
+    fn write_comments() {
+        let data = write(&book());
+        let comments = read_part(&data, "xl/comments1.xml");
+        let rels = read_part(&data, "xl/worksheets/_rels/sheet1.xml.rels");
+
+        assert!(comments.contains(r#"<comment ref="A1" authorId="0">"#));
+        assert!(rels.contains("../drawings/vmlDrawing1.vml"));
+    }
+
+    #[test]
+    fn write_conditional_formats() {
+        let mut book = Book::new();
+        let sheet = book.add_sheet("A", "visible").unwrap();
+
+        {
+            let mut sheet = sheet.write();
+            let negative = Format {
+                font: Some(Font {
+                    color: Some("9C0006".parse().unwrap()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            sheet
+                .add_conditional_format(
+                    ConditionalFormat::new(
+                        vec![Range::new(1, 10, 2, 2), Range::new(1, 10, 4, 4)],
+                        ConditionalRule::CellIs {
+                            operator: CellIsOperator::LessThan,
+                            formula: "0".to_string(),
+                            formula2: None,
+                        },
+                    )
+                    .with_style(negative),
+                )
+                .unwrap();
+            sheet
+                .add_conditional_format(
+                    ConditionalFormat::new(
+                        vec![Range::new(1, 10, 3, 3)],
+                        ConditionalRule::DataBar {
+                            color: "638EC6".parse().unwrap(),
+                            min: Threshold {
+                                kind: ThresholdKind::Num,
+                                value: Some("0".to_string()),
+                            },
+                            max: Threshold {
+                                kind: ThresholdKind::Max,
+                                value: None,
+                            },
+                        },
+                    )
+                    .with_priority(1),
+                )
+                .unwrap();
+        }
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+        let styles = read_part(&data, "xl/styles.xml");
+
+        assert!(sheet.contains(concat!(
+            r#"<conditionalFormatting sqref="B1:B10 D1:D10">"#,
+            r#"<cfRule type="cellIs" dxfId="0" priority="2" operator="lessThan">"#,
+            "<formula>0</formula></cfRule></conditionalFormatting>"
+        )));
+        assert!(sheet.contains(concat!(
+            r#"<cfRule type="dataBar" priority="1"><dataBar><cfvo type="num" val="0"/>"#,
+            r#"<cfvo type="max"/><color rgb="FF638EC6"/></dataBar></cfRule>"#
+        )));
+        assert!(
+            styles.contains(
+                r#"<dxfs count="1"><dxf><font><color rgb="FF9C0006"/></font></dxf></dxfs>"#
+            )
+        );
+    }
+
+    #[test]
+    fn write_data_validations() {
+        let mut book = Book::new();
+        let sheet = book.add_sheet("A", "visible").unwrap();
+
+        {
+            let mut sheet = sheet.write();
+            let mut list = DataValidation::new(
+                vec![Range::new(2, 20, 1, 1)],
+                ValidationRule::List {
+                    values: vec!["Да".to_string(), "Нет \"Н\"".to_string()],
+                },
+            );
+            list.input_message = Some("Выберите из списка".to_string());
+            sheet.add_data_validation(list).unwrap();
+
+            let mut amount = DataValidation::new(
+                vec![Range::new(2, 20, 2, 2)],
+                ValidationRule::Decimal(Constraint {
+                    operator: CellIsOperator::GreaterThanOrEqual,
+                    formula: "0".to_string(),
+                    formula2: None,
+                }),
+            );
+            amount.allow_blank = false;
+            amount.error_style = ErrorStyle::Warning;
+            sheet.add_data_validation(amount).unwrap();
+        }
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+
+        assert!(sheet.contains(concat!(
+            r#"<dataValidations count="2"><dataValidation type="list" allowBlank="1" "#,
+            r#"showInputMessage="1" showErrorMessage="1" prompt="Выберите из списка" "#,
+            r#"sqref="A2:A20"><formula1>&quot;Да,Нет &quot;&quot;Н&quot;&quot;&quot;</formula1>"#,
+            r#"</dataValidation>"#
+        )));
+        assert!(sheet.contains(concat!(
+            r#"<dataValidation type="decimal" operator="greaterThanOrEqual" errorStyle="warning" "#,
+            r#"showErrorMessage="1" sqref="B2:B20"><formula1>0</formula1></dataValidation>"#
+        )));
+    }
+
+    #[test]
+    fn write_sheet_view() {
+        let book = book();
+        {
+            let mut sheet = book.get_sheet_index(0).unwrap().write();
+            sheet
+                .set_sheet_view(SheetView {
+                    frozen_rows: 1,
+                    zoom: 85,
+                    show_grid_lines: false,
+                    selected_cell: Some(Coordinate::new(2, 1)),
+                    tab_color: Some("1F4E78".parse().unwrap()),
+                    ..Default::default()
+                })
+                .unwrap();
+            sheet.set_auto_filter(Some(Range::new(1, 2, 1, 2)));
+        }
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+
+        assert!(sheet.contains(r#"<sheetPr><tabColor rgb="FF1F4E78"/></sheetPr><dimension"#));
+        assert!(sheet.contains(concat!(
+            r#"<sheetViews><sheetView showGridLines="0" zoomScale="85" zoomScaleNormal="85" workbookViewId="0">"#,
+            r#"<pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/>"#,
+            r#"<selection pane="bottomLeft" activeCell="A2" sqref="A2"/>"#,
+            "</sheetView></sheetViews>"
+        )));
+        assert!(sheet.contains(r#"</sheetData><autoFilter ref="A1:B2"/><mergeCells"#));
+
+        let workbook = read_part(&data, "xl/workbook.xml");
+        assert!(workbook.contains(concat!(
+            r#"<definedName name="_xlnm._FilterDatabase" localSheetId="0" hidden="1">"#,
+            "Отчет!$A$1:$B$2</definedName>"
+        )));
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        assert_eq!(
+            sheets[0].get_sheet_view(),
+            book.get_sheet_index(0).unwrap().read().get_sheet_view()
+        );
+        assert_eq!(sheets[0].get_auto_filter(), Some(&Range::new(1, 2, 1, 2)));
+        assert!(sheets[1].get_sheet_view().is_default());
+    }
+
+    #[test]
+    fn write_outline() {
+        let book = book();
+        {
+            let mut sheet = book.get_sheet_index(0).unwrap().write();
+            sheet.set_summary_below(false);
+            sheet.group_rows(3, 4, 1).unwrap();
+            sheet.collapse_rows(3, 4).unwrap();
+            sheet.group_cols(2, 2, 2).unwrap();
+        }
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+
+        assert!(sheet.contains(r#"<sheetPr><outlinePr summaryBelow="0"/></sheetPr>"#));
+        assert!(sheet.contains(r#" outlineLevelRow="1" outlineLevelCol="2"/>"#));
+        assert!(sheet.contains(r#"<row r="2" collapsed="1""#));
+        assert!(sheet.contains(r#"<row r="3" hidden="1" outlineLevel="1""#));
+        assert!(
+            sheet.contains(r#"<col min="2" max="2" width="40" customWidth="1" outlineLevel="2"/>"#)
+        );
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        assert_eq!(*sheets[0].get_level_by_row(4), 1);
+        assert!(*sheets[0].get_hidden_by_row(4));
+        assert!(*sheets[0].get_collapsed_by_row(2));
+        assert_eq!(*sheets[0].get_level_by_column(2), 2);
+        assert!(!sheets[0].get_summary_below());
+        assert!(sheets[0].get_summary_right());
+    }
+
+    #[test]
+    fn write_protection() {
+        let mut book = book();
+        {
+            let mut sheet = book.get_sheet_index(0).unwrap().write();
+            sheet
+                .cell(Coordinate::new(3, 1), None)
+                .write()
+                .set_style(Format {
+                    protection: Some(CellProtection {
+                        locked: false,
+                        hidden: false,
+                    }),
+                    ..Default::default()
+                });
+
+            let mut protection = SheetProtection {
+                sort: true,
+                select_locked_cells: false,
+                ..Default::default()
+            };
+            protection.set_password("secret");
+            sheet.set_protection(Some(protection)).unwrap();
+        }
+        book.set_protection(Some(WorkbookProtection::default()))
+            .unwrap();
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+        let workbook = read_part(&data, "xl/workbook.xml");
+        let styles = read_part(&data, "xl/styles.xml");
+
+        assert!(sheet.contains(concat!(
+            r#"</sheetData><sheetProtection password="DAA7" sheet="1" sort="0""#,
+            r#" objects="1" scenarios="1" selectLockedCells="1"/>"#
+        )));
+        assert!(workbook.contains(r#"<workbookProtection lockStructure="1"/><bookViews>"#));
+        assert!(styles.contains(r#"applyProtection="1"><protection locked="0"/></xf>"#));
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        assert_eq!(
+            sheets[0].get_protection(),
+            book.get_sheet_index(0).unwrap().read().get_protection()
+        );
+        assert_eq!(sheets[1].get_protection(), None);
+    }
+
+    #[test]
+    fn write_page_setup() {
+        let book = book();
+        let setup = PageSetup {
+            print_area: Some(Range::new(1, 20, 1, 4)),
+            orientation: Orientation::Landscape,
+            paper_size: Some(9),
+            fit_to_page: Some(FitToPage {
+                width: 1,
+                height: 0,
+            }),
+            print_title_rows: Some((1, 2)),
+            print_title_cols: Some((1, 1)),
+            footer: Some("&CСтр. &P из &N".to_string()),
+            ..Default::default()
+        };
+        book.get_sheet_index(0)
+            .unwrap()
+            .write()
+            .set_page_setup(setup.clone())
+            .unwrap();
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+
+        assert!(sheet.contains(r#"<sheetPr><pageSetUpPr fitToPage="1"/></sheetPr>"#));
+        assert!(sheet.contains(concat!(
+            r#"<pageMargins left="0.7" right="0.7" top="0.75" bottom="0.75" header="0.3" footer="0.3"/>"#,
+            r#"<pageSetup paperSize="9" fitToWidth="1" fitToHeight="0" orientation="landscape"/>"#,
+            "<headerFooter><oddFooter>&amp;CСтр. &amp;P из &amp;N</oddFooter></headerFooter>"
+        )));
+
+        let workbook = read_part(&data, "xl/workbook.xml");
+        assert!(workbook.contains(concat!(
+            r#"<definedName name="_xlnm.Print_Area" localSheetId="0">Отчет!$A$1:$D$20</definedName>"#,
+            r#"<definedName name="_xlnm.Print_Titles" localSheetId="0">Отчет!$A:$A,Отчет!$1:$2</definedName>"#,
+        )));
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        assert_eq!(sheets[0].get_page_setup(), &setup);
+        assert!(sheets[1].get_page_setup().is_default());
+    }
+
+    #[test]
+    fn write_hyperlinks() {
+        let book = book();
+        {
+            let mut sheet = book.get_sheet_index(0).unwrap().write();
+            sheet
+                .cell(Coordinate::new(3, 1), Some("Детали"))
+                .write()
+                .set_hyperlink("'Скрытый'!A1", Some("Перейти"));
+            sheet
+                .cell(Coordinate::new(3, 2), Some("Сайт"))
+                .write()
+                .set_hyperlink("https://example.com/?a=1&b=2", None);
+        }
+
+        let data = write(&book);
+        let sheet = read_part(&data, "xl/worksheets/sheet1.xml");
+
+        assert!(sheet.contains(concat!(
+            "<hyperlinks>",
+            r#"<hyperlink ref="A3" location="&apos;Скрытый&apos;!A1" tooltip="Перейти"/>"#,
+            r#"<hyperlink ref="B3" r:id="rId1"/>"#,
+            "</hyperlinks><pageMargins"
+        )));
+
+        let rels = read_part(&data, "xl/worksheets/_rels/sheet1.xml.rels");
+        assert!(
+            rels.contains(r#"Target="https://example.com/?a=1&amp;b=2" TargetMode="External"/>"#)
+        );
+        assert!(rels.contains(r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments""#));
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        let link = |row, col| {
+            sheets[0]
+                .find_cell_by_coords(row, col)
+                .unwrap()
+                .unwrap()
+                .read()
+                .get_hyperlink()
+        };
+
+        assert_eq!(
+            link(3, 1),
+            Some(Hyperlink::new("'Скрытый'!A1", Some("Перейти")))
+        );
+        assert_eq!(
+            link(3, 2),
+            Some(Hyperlink::new("https://example.com/?a=1&b=2", None))
+        );
+    }
+
+    #[test]
+    fn write_rich_text() {
+        let book = book();
+        let text = RichText::new(vec![
+            TextRun::new(
+                "Итого: ",
+                Some(Font {
+                    bold: true,
+                    ..Default::default()
+                }),
+            ),
+            TextRun::new("100", None),
+            TextRun::new(
+                " *",
+                Some(Font {
+                    color: "FF0000".parse().ok(),
+                    size: Some(8.0),
+                    ..Default::default()
+                }),
+            ),
+        ]);
+        {
+            let mut sheet = book.get_sheet_index(0).unwrap().write();
+            sheet
+                .cell(Coordinate::new(3, 1), None)
+                .write()
+                .set_value_rich_text(text.clone());
+        }
+
+        let data = write(&book);
+        let strings = read_part(&data, "xl/sharedStrings.xml");
+        assert!(strings.contains(concat!(
+            r#"<si><r><rPr><b/></rPr><t xml:space="preserve">Итого: </t></r>"#,
+            "<r><t>100</t></r>",
+            r#"<r><rPr><color rgb="FFFF0000"/><sz val="8"/></rPr><t xml:space="preserve"> *</t></r></si>"#,
+        )));
+
+        let sheets = read_xlsx(Cursor::new(data)).unwrap();
+        let cell = sheets[0].find_cell_by_coords(3, 1).unwrap().unwrap().read();
+        assert_eq!(cell.get_value(), "Итого: 100 *");
+        assert_eq!(cell.get_rich_text(), Some(text));
+    }
+
+    #[test]
+    fn write_empty_book() {
+        let book = Book::new();
+        let mut buf = Cursor::new(Vec::new());
+
+        assert!(write_book(&book, &mut buf).is_err());
+    }
+
+    #[test]
+    fn write_duplicate_names() {
+        let mut book = Book::new();
+        book.add_sheet("A", "visible").unwrap();
+        assert!(book.add_sheet("a", "visible").is_err());
+        book.add_sheet("B", "visible")
+            .unwrap()
+            .write()
+            .set_name("a");
+
+        let mut buf = Cursor::new(Vec::new());
+
+        assert!(write_book(&book, &mut buf).is_err());
+    }
+}